@@ -289,7 +289,8 @@ cfg_if! {
                         "Kaspa NG",
                         native_options,
                         Box::new(move |cc| {
-                            let runtime = runtime::Runtime::new(&cc.egui_ctx, &settings, wallet_api, application_events, None);
+                            let adaptor = settings.bridge.enabled.then(|| crate::interop::Bridge::start(&settings.bridge));
+                            let runtime = runtime::Runtime::new(&cc.egui_ctx, &settings, wallet_api, application_events, adaptor);
                             delegate.lock().unwrap().replace(runtime.clone());
                             runtime::signals::Signals::bind(&runtime);
                             runtime.start();
@@ -25,6 +25,8 @@ pub struct Core {
     is_shutdown_pending: bool,
     settings_storage_requested: bool,
     last_settings_storage_request: Instant,
+    balance_history_requested: bool,
+    last_balance_update: Instant,
 
     runtime: Runtime,
     wallet: Arc<dyn WalletApi>,
@@ -58,6 +60,9 @@ pub struct Core {
     pub network_pressure: NetworkPressure,
     notifications: Notifications,
     pub storage: Storage,
+    pub invoices: Invoices,
+    pub permissions: interop::Permissions,
+    pub balance_history: BalanceHistory,
     // pub feerate : Option<Arc<RpcFeeEstimate>>,
     pub feerate: Option<FeerateEstimate>,
     pub node_info: Option<Box<String>>,
@@ -190,6 +195,8 @@ impl Core {
             is_shutdown_pending: false,
             settings_storage_requested: false,
             last_settings_storage_request: Instant::now(),
+            balance_history_requested: false,
+            last_balance_update: Instant::now(),
 
             wallet,
             application_events_channel,
@@ -222,6 +229,9 @@ impl Core {
             network_pressure: NetworkPressure::default(),
             notifications: Notifications::default(),
             storage,
+            invoices: Invoices::default(),
+            permissions: interop::Permissions::default(),
+            balance_history: BalanceHistory::default(),
             feerate: None,
             node_info: None,
             // daemon_storage_root: Mutex::new(daemon_storage_root),
@@ -233,6 +243,29 @@ impl Core {
 
         this.wallet_update_list();
 
+        let invoices = this.invoices.clone();
+        spawn(async move {
+            invoices.load().await?;
+            loop {
+                invoices.update_expiration();
+                task::sleep(crate::invoices::INVOICE_EXPIRATION_INTERVAL).await;
+            }
+        });
+
+        let balance_history = this.balance_history.clone();
+        spawn(async move {
+            balance_history.load().await?;
+            Ok(())
+        });
+
+        let permissions = this.permissions.clone();
+        spawn(async move {
+            permissions.load().await?;
+            permissions.update_expiration();
+            permissions.revoke_sessions();
+            Ok(())
+        });
+
         cfg_if! {
             if #[cfg(target_arch = "wasm32")] {
                 this.register_visibility_handler();
@@ -314,6 +347,52 @@ impl Core {
             .unwrap();
     }
 
+    /// Total mature balance of all accounts in the open wallet
+    pub fn wallet_balance(&self) -> Option<u64> {
+        self.account_collection.as_ref().map(|account_collection| {
+            account_collection
+                .list()
+                .iter()
+                .filter_map(|account| account.balance())
+                .map(|balance| balance.mature)
+                .sum()
+        })
+    }
+
+    /// Total mature balance of all accounts, available only once
+    /// every account in the open wallet has reported its balance
+    fn aggregate_balance(&self) -> Option<u64> {
+        self.account_collection
+            .as_ref()?
+            .list()
+            .iter()
+            .map(|account| account.balance().map(|balance| balance.mature))
+            .sum()
+    }
+
+    fn record_balance_history(&self) {
+        if let (Some(descriptor), Some(balance)) =
+            (self.wallet_descriptor.as_ref(), self.aggregate_balance())
+        {
+            let timestamp = unixtime_as_millis_f64() as u64;
+            if self.balance_history.record(
+                descriptor.filename.as_str(),
+                self.settings.node.network,
+                timestamp,
+                balance,
+            ) {
+                self.balance_history.store();
+            }
+        }
+    }
+
+    /// Relay a wallet state change to web pages connected through the adaptor
+    pub fn notify_dapps(&self, notification: interop::Notification) {
+        if let Some(adaptor) = runtime().adaptor() {
+            adaptor.notify(notification);
+        }
+    }
+
     pub fn network(&self) -> Network {
         self.settings.node.network
     }
@@ -387,6 +466,14 @@ impl Core {
     }
 
     pub fn change_current_network(&mut self, network: Network) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.storage.is_relocating() {
+            runtime().error(i18n(
+                "Node settings can not be changed while a data folder is being moved",
+            ));
+            return;
+        }
+
         if self.settings.node.network != network {
             self.settings.node.network = network;
             self.get_mut::<modules::Settings>()
@@ -397,6 +484,23 @@ impl Core {
                 .update_services(&self.settings.node, None);
         }
     }
+
+    /// Switches the node data storage folder after the data
+    /// folder has been moved to `storage_root`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn change_storage_root(&mut self, storage_root: PathBuf) {
+        let storage_root = storage_root.to_string_lossy().to_string();
+        self.settings.node.kaspad_daemon_storage_folder_enable = true;
+        self.settings.node.kaspad_daemon_storage_folder = storage_root.clone();
+        self.get_mut::<modules::Settings>()
+            .change_storage_root(&storage_root);
+        self.store_settings();
+        self.storage.track_storage_root(Some(storage_root.as_str()));
+        runtime().notify(UserNotification::success(i18n_args(
+            "Data folder moved to {path}",
+            &[("path", storage_root)],
+        )));
+    }
 }
 
 impl eframe::App for Core {
@@ -438,6 +542,18 @@ impl eframe::App for Core {
             self.settings.store_sync().unwrap();
         }
 
+        // balances reported while the node is syncing are incomplete,
+        // so the pending record is kept until the node is synced
+        if self.balance_history_requested && self.state.is_synced() {
+            let elapsed = self.last_balance_update.elapsed();
+            if elapsed > BALANCE_HISTORY_DEBOUNCE {
+                self.balance_history_requested = false;
+                self.record_balance_history();
+            } else {
+                ctx.request_repaint_after(BALANCE_HISTORY_DEBOUNCE - elapsed);
+            }
+        }
+
         ctx.input(|input| {
             input.events.iter().for_each(|event| {
                 if let Event::Key {
@@ -694,12 +810,22 @@ impl Core {
                 self.modules.clone().values().for_each(|module| {
                     module.network_change(self, network);
                 });
+
+                self.notify_dapps(interop::Notification::NetworkChanged {
+                    network: network.to_string(),
+                });
             }
             Events::UpdateStorage(_options) => {
                 #[cfg(not(target_arch = "wasm32"))]
                 self.storage
                     .update(Some(_options.with_network(self.settings.node.network)));
             }
+            Events::StorageRelocated {
+                storage_root: _storage_root,
+            } => {
+                #[cfg(not(target_arch = "wasm32"))]
+                self.change_storage_root(_storage_root);
+            }
             Events::VisibilityChange(state) => match state {
                 VisibilityState::Visible => {
                     self.module.clone().show(self);
@@ -879,6 +1005,7 @@ impl Core {
                         self.state.is_connected = false;
                         self.state.sync_state = None;
                         self.state.is_synced = None;
+                        self.runtime.metrics_service().update_sync_state(false);
                         self.state.server_version = None;
                         self.state.url = None;
                         self.state.network_id = None;
@@ -898,6 +1025,9 @@ impl Core {
                     }
                     CoreWallet::SyncState { sync_state } => {
                         self.state.sync_state = Some(sync_state);
+                        self.runtime
+                            .metrics_service()
+                            .update_sync_state(self.state.is_synced());
                     }
                     CoreWallet::ServerStatus {
                         is_synced,
@@ -906,6 +1036,9 @@ impl Core {
                         network_id,
                     } => {
                         self.state.is_synced = Some(is_synced);
+                        self.runtime
+                            .metrics_service()
+                            .update_sync_state(self.state.is_synced());
                         self.state.server_version = Some(server_version);
                         self.state.url = url;
                         self.state.network_id = Some(network_id);
@@ -983,6 +1116,7 @@ impl Core {
                         self.state.is_open = false;
                         self.account_collection = None;
                         self.wallet_descriptor = None;
+                        self.balance_history_requested = false;
                         self.prv_key_data_map = None;
 
                         self.modules.clone().into_iter().for_each(|(_, module)| {
@@ -1000,6 +1134,10 @@ impl Core {
                             let device = self.device().clone();
                             let wallet = self.wallet();
                             // log_info!("--- selecting account: {id:?}");
+                            self.notify_dapps(interop::Notification::AccountChanged {
+                                account_id: id,
+                                address: account.receive_address().to_string(),
+                            });
                             self.get_mut::<modules::AccountManager>().select(
                                 wallet,
                                 Some(account),
@@ -1051,6 +1189,8 @@ impl Core {
                             return Ok(());
                         }
 
+                        self.invoices.update_with_transaction(&record);
+
                         match record.binding().clone() {
                             Binding::Account(id) => {
                                 self.account_collection
@@ -1083,60 +1223,79 @@ impl Core {
                             }
                         }
                     }
-                    CoreWallet::Pending { record } => match record.binding().clone() {
-                        Binding::Account(id) => {
-                            self.account_collection
-                                .as_ref()
-                                .and_then(|account_collection| {
-                                    account_collection.get(&id).map(|account| {
-                                        if account
-                                            .transactions()
-                                            .replace_or_insert(Transaction::new_processing(
-                                                Arc::new(record),
-                                            ))
-                                            .is_none()
-                                        {
-                                            let mut binding = account.transactions();
-                                            while binding.len() as u64 > TRANSACTION_PAGE_SIZE {
-                                                binding.pop();
+                    CoreWallet::Pending { record } => {
+                        self.invoices.update_with_transaction(&record);
+
+                        match record.binding().clone() {
+                            Binding::Account(id) => {
+                                self.account_collection
+                                    .as_ref()
+                                    .and_then(|account_collection| {
+                                        account_collection.get(&id).map(|account| {
+                                            if account
+                                                .transactions()
+                                                .replace_or_insert(Transaction::new_processing(
+                                                    Arc::new(record),
+                                                ))
+                                                .is_none()
+                                            {
+                                                let mut binding = account.transactions();
+                                                while binding.len() as u64 > TRANSACTION_PAGE_SIZE {
+                                                    binding.pop();
+                                                }
+                                                account.set_transaction_count(
+                                                    account.transaction_count() + 1,
+                                                );
                                             }
-                                            account.set_transaction_count(
-                                                account.transaction_count() + 1,
-                                            );
-                                        }
-                                    })
-                                });
-                        }
-                        Binding::Custom(_) => {
-                            log_error!(
-                                "Error while processing transaction {}: custom bindings are not supported",
-                                record.id()
-                            );
+                                        })
+                                    });
+                            }
+                            Binding::Custom(_) => {
+                                log_error!(
+                                    "Error while processing transaction {}: custom bindings are not supported",
+                                    record.id()
+                                );
+                            }
                         }
-                    },
+                    }
 
-                    CoreWallet::Reorg { record } => match record.binding().clone() {
-                        Binding::Account(id) => {
-                            self.account_collection
-                                .as_mut()
-                                .and_then(|account_collection| {
-                                    account_collection
-                                        .get(&id)
-                                        .map(|account| account.transactions().remove(record.id()))
-                                });
-                        }
-                        Binding::Custom(_) => {
-                            log_error!(
-                                "Error while processing transaction {}: custom bindings are not supported",
-                                record.id()
-                            );
+                    CoreWallet::Reorg { record } => {
+                        self.invoices.revert_transaction(&record);
+
+                        match record.binding().clone() {
+                            Binding::Account(id) => {
+                                self.account_collection
+                                    .as_mut()
+                                    .and_then(|account_collection| {
+                                        account_collection
+                                            .get(&id)
+                                            .map(|account| account.transactions().remove(record.id()))
+                                    });
+                            }
+                            Binding::Custom(_) => {
+                                log_error!(
+                                    "Error while processing transaction {}: custom bindings are not supported",
+                                    record.id()
+                                );
+                            }
                         }
-                    },
+                    }
 
                     CoreWallet::Balance { balance, id } => {
+                        self.invoices.update_expiration();
+
                         if let Some(account_collection) = &self.account_collection {
                             if let Some(account) = account_collection.get(&id.into()) {
                                 account.update_balance(balance)?;
+                                self.balance_history_requested = true;
+                                self.last_balance_update = Instant::now();
+                                if let Some(balance) = account.balance() {
+                                    self.runtime.market_monitor_service().update_balance(
+                                        account.id(),
+                                        account.name_or_id(),
+                                        balance.mature,
+                                    );
+                                }
                             } else {
                                 log_error!("unable to find account {} while updating balance.", id);
                             }
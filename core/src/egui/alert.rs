@@ -0,0 +1,189 @@
+use crate::imports::*;
+use crate::runtime::services::market_monitor::{Alert, AlertCondition};
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum AlertKind {
+    #[default]
+    PriceCrosses,
+    ChangeExceeds,
+    BalanceBelow,
+}
+
+impl AlertKind {
+    fn list() -> [AlertKind; 3] {
+        [
+            AlertKind::PriceCrosses,
+            AlertKind::ChangeExceeds,
+            AlertKind::BalanceBelow,
+        ]
+    }
+}
+
+impl std::fmt::Display for AlertKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlertKind::PriceCrosses => write!(f, "{}", i18n("Price crosses")),
+            AlertKind::ChangeExceeds => write!(f, "{}", i18n("24h change exceeds (%)")),
+            AlertKind::BalanceBelow => write!(f, "{}", i18n("Balance drops below (KAS)")),
+        }
+    }
+}
+
+/// Editor for the list of market and balance alerts.
+#[derive(Default)]
+pub struct AlertEditor {
+    kind: AlertKind,
+    currency: String,
+    value: String,
+    account_id: Option<AccountId>,
+}
+
+impl AlertEditor {
+    fn condition(&self) -> Option<AlertCondition> {
+        let value = self
+            .value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite() && *value > 0.0)?;
+
+        match self.kind {
+            AlertKind::PriceCrosses => Some(AlertCondition::PriceCrosses {
+                currency: self.currency.clone(),
+                price: value,
+            }),
+            AlertKind::ChangeExceeds => Some(AlertCondition::ChangeExceeds {
+                currency: self.currency.clone(),
+                percent: value,
+            }),
+            AlertKind::BalanceBelow => Some(AlertCondition::BalanceBelow {
+                account_id: self.account_id,
+                amount_sompi: kaspa_to_sompi(value),
+            }),
+        }
+    }
+
+    fn account_name(core: &Core, account_id: Option<&AccountId>) -> String {
+        match account_id {
+            Some(account_id) => core
+                .account_collection()
+                .as_ref()
+                .and_then(|collection| collection.get(account_id))
+                .map(|account| account.name_or_id())
+                .unwrap_or_else(|| account_id.short()),
+            None => i18n("Any account").to_string(),
+        }
+    }
+
+    fn describe(core: &Core, condition: &AlertCondition) -> String {
+        match condition {
+            AlertCondition::PriceCrosses { currency, price } => i18n_args(
+                "Price crosses {price}",
+                &[(
+                    "price",
+                    format_currency_with_symbol(
+                        *price,
+                        precision_from_symbol(currency),
+                        &currency.to_uppercase(),
+                    ),
+                )],
+            ),
+            AlertCondition::ChangeExceeds { currency, percent } => i18n_args(
+                "24h change in {currency} exceeds {percent}%",
+                &[
+                    ("currency", currency.to_uppercase()),
+                    ("percent", percent.to_string()),
+                ],
+            ),
+            AlertCondition::BalanceBelow {
+                account_id,
+                amount_sompi,
+            } => i18n_args(
+                "{account} balance drops below {amount} KAS",
+                &[
+                    ("account", Self::account_name(core, account_id.as_ref())),
+                    ("amount", sompi_to_kaspa_string(*amount_sompi)),
+                ],
+            ),
+        }
+    }
+
+    /// Renders the alert list and the form used to add new alerts.
+    pub fn ui(&mut self, ui: &mut Ui, core: &Core, currencies: &[String], alerts: &mut Vec<Alert>) {
+        let mut remove = None;
+        for (index, alert) in alerts.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut alert.enabled, Self::describe(core, &alert.condition));
+                if ui
+                    .small_button(egui_phosphor::light::X)
+                    .on_hover_text(i18n("Remove"))
+                    .clicked()
+                {
+                    remove = Some(index);
+                }
+            });
+        }
+        if let Some(index) = remove {
+            alerts.remove(index);
+        }
+
+        if !currencies.contains(&self.currency) {
+            self.currency = currencies.first().cloned().unwrap_or_default();
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            egui::ComboBox::from_id_salt("market_alert_kind_selector")
+                .selected_text(self.kind.to_string())
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                    AlertKind::list().into_iter().for_each(|kind| {
+                        let text = kind.to_string();
+                        ui.selectable_value(&mut self.kind, kind, text);
+                    });
+                });
+
+            if self.kind == AlertKind::BalanceBelow {
+                egui::ComboBox::from_id_salt("market_alert_account_selector")
+                    .selected_text(Self::account_name(core, self.account_id.as_ref()))
+                    .show_ui(ui, |ui| {
+                        ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                        ui.selectable_value(&mut self.account_id, None, i18n("Any account"));
+                        if let Some(collection) = core.account_collection() {
+                            collection.list().iter().for_each(|account| {
+                                ui.selectable_value(
+                                    &mut self.account_id,
+                                    Some(account.id()),
+                                    account.name_or_id(),
+                                );
+                            });
+                        }
+                    });
+            } else {
+                egui::ComboBox::from_id_salt("market_alert_currency_selector")
+                    .selected_text(self.currency.to_uppercase())
+                    .show_ui(ui, |ui| {
+                        ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                        currencies.iter().for_each(|currency| {
+                            ui.selectable_value(
+                                &mut self.currency,
+                                currency.clone(),
+                                currency.to_uppercase(),
+                            );
+                        });
+                    });
+            }
+
+            ui.add(TextEdit::singleline(&mut self.value).desired_width(80.));
+
+            let condition = self.condition();
+            if ui
+                .add_enabled(condition.is_some(), Button::new(i18n("Add Alert")))
+                .clicked()
+                && let Some(condition) = condition
+            {
+                alerts.push(Alert::new(condition));
+                self.value.clear();
+            }
+        });
+    }
+}
@@ -0,0 +1,143 @@
+use crate::imports::*;
+use crate::runtime::services::metrics_monitor::{MetricCondition, MetricRule};
+use kaspa_metrics_core::Metric;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum RuleKind {
+    #[default]
+    Above,
+    Below,
+    Stalled,
+}
+
+impl RuleKind {
+    fn list() -> [RuleKind; 3] {
+        [RuleKind::Above, RuleKind::Below, RuleKind::Stalled]
+    }
+}
+
+impl std::fmt::Display for RuleKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleKind::Above => write!(f, "{}", i18n("is above")),
+            RuleKind::Below => write!(f, "{}", i18n("is below")),
+            RuleKind::Stalled => write!(f, "{}", i18n("is unchanged")),
+        }
+    }
+}
+
+/// Editor for the list of node metric alert rules.
+pub struct MetricRuleEditor {
+    metric: Metric,
+    kind: RuleKind,
+    value: String,
+    duration: String,
+}
+
+impl Default for MetricRuleEditor {
+    fn default() -> Self {
+        Self {
+            metric: Metric::NodeActivePeers,
+            kind: RuleKind::default(),
+            value: String::new(),
+            duration: "60".to_string(),
+        }
+    }
+}
+
+impl MetricRuleEditor {
+    fn rule(&self) -> Option<MetricRule> {
+        let duration = self.duration.trim().parse::<u64>().ok()?;
+        let value = || {
+            self.value
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite())
+        };
+
+        let condition = match self.kind {
+            RuleKind::Above => MetricCondition::Above {
+                metric: self.metric,
+                value: value()?,
+            },
+            RuleKind::Below => MetricCondition::Below {
+                metric: self.metric,
+                value: value()?,
+            },
+            RuleKind::Stalled => MetricCondition::Stalled {
+                metric: self.metric,
+            },
+        };
+
+        Some(MetricRule::new(condition, duration))
+    }
+
+    /// Renders the rule list and the form used to add new rules.
+    /// Returns `true` if the rules have been modified.
+    pub fn ui(&mut self, ui: &mut Ui, rules: &mut Vec<MetricRule>) -> bool {
+        let mut changed = false;
+
+        let mut remove = None;
+        for (index, rule) in rules.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.checkbox(&mut rule.enabled, rule.describe()).changed() {
+                    changed = true;
+                }
+                if ui
+                    .small_button(egui_phosphor::light::X)
+                    .on_hover_text(i18n("Remove"))
+                    .clicked()
+                {
+                    remove = Some(index);
+                }
+            });
+        }
+        if let Some(index) = remove {
+            rules.remove(index);
+            changed = true;
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            egui::ComboBox::from_id_salt("metric_rule_metric_selector")
+                .selected_text(i18n(self.metric.title().0))
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                    Metric::into_iter().for_each(|metric| {
+                        ui.selectable_value(&mut self.metric, metric, i18n(metric.title().0));
+                    });
+                });
+
+            egui::ComboBox::from_id_salt("metric_rule_kind_selector")
+                .selected_text(self.kind.to_string())
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                    RuleKind::list().into_iter().for_each(|kind| {
+                        let text = kind.to_string();
+                        ui.selectable_value(&mut self.kind, kind, text);
+                    });
+                });
+
+            if self.kind != RuleKind::Stalled {
+                ui.add(TextEdit::singleline(&mut self.value).desired_width(80.));
+            }
+
+            ui.label(i18n("for"));
+            ui.add(TextEdit::singleline(&mut self.duration).desired_width(40.));
+            ui.label(i18n("sec"));
+
+            let rule = self.rule();
+            if ui
+                .add_enabled(rule.is_some(), Button::new(i18n("Add Rule")))
+                .clicked()
+                && let Some(rule) = rule
+            {
+                rules.push(rule);
+                self.value.clear();
+                changed = true;
+            }
+        });
+
+        changed
+    }
+}
@@ -1,9 +1,11 @@
+mod alert;
 mod collapsable;
 mod composite_button;
 mod composite_icon;
 mod easy_mark;
 mod extensions;
 mod icon;
+mod metric_rule;
 mod mnemonic;
 mod network;
 mod pagination;
@@ -12,12 +14,14 @@ mod popup;
 mod selection_panels;
 mod theme;
 
+pub use alert::AlertEditor;
 pub use collapsable::*;
 pub use composite_button::{Composite, CompositeButton};
 pub use composite_icon::CompositeIcon;
 pub use easy_mark::easy_mark;
 pub use extensions::*;
 pub use icon::IconSize;
+pub use metric_rule::MetricRuleEditor;
 pub use mnemonic::*;
 pub use network::NetworkInterfaceEditor;
 pub use pagination::*;
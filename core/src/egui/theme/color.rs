@@ -64,6 +64,11 @@ pub struct ThemeColor {
     pub block_dag_new_block_fill_color: Color32,
     pub block_dag_block_fill_color: Color32,
     pub block_dag_block_stroke_color: Color32,
+    pub block_dag_selected_block_stroke_color: Color32,
+    pub block_dag_chain_block_fill_color: Color32,
+    pub block_dag_blue_block_fill_color: Color32,
+    pub block_dag_red_block_fill_color: Color32,
+    pub block_dag_merge_connect_color: Color32,
     pub block_dag_vspc_connect_color: Color32,
     pub block_dag_parent_connect_color: Color32,
 }
@@ -133,6 +138,11 @@ impl ThemeColor {
             block_dag_new_block_fill_color: Color32::from_rgb(220, 220, 220),
             block_dag_block_fill_color: Color32::from_rgb(173, 216, 230),
             block_dag_block_stroke_color: Color32::from_rgb(15, 84, 77),
+            block_dag_selected_block_stroke_color: Color32::from_rgb(255, 184, 0),
+            block_dag_chain_block_fill_color: Color32::from_rgb(23, 150, 137),
+            block_dag_blue_block_fill_color: Color32::from_rgb(88, 148, 235),
+            block_dag_red_block_fill_color: Color32::from_rgb(222, 92, 92),
+            block_dag_merge_connect_color: Color32::from_rgba_premultiplied(160, 160, 160, 160),
             block_dag_vspc_connect_color: Color32::from_rgb(23, 150, 137),
             block_dag_parent_connect_color: Color32::from_rgba_premultiplied(173, 216, 230, 220),
         }
@@ -202,6 +212,11 @@ impl ThemeColor {
             block_dag_new_block_fill_color: Color32::from_rgb(220, 220, 220),
             block_dag_block_fill_color: Color32::from_rgb(201, 230, 240),
             block_dag_block_stroke_color: Color32::from_rgb(42, 51, 50),
+            block_dag_selected_block_stroke_color: Color32::from_rgb(214, 110, 0),
+            block_dag_chain_block_fill_color: Color32::from_rgb(11, 77, 70),
+            block_dag_blue_block_fill_color: Color32::from_rgb(66, 125, 220),
+            block_dag_red_block_fill_color: Color32::from_rgb(210, 70, 70),
+            block_dag_merge_connect_color: Color32::from_rgba_premultiplied(0, 0, 0, 96),
             block_dag_vspc_connect_color: Color32::from_rgb(11, 77, 70),
             block_dag_parent_connect_color: Color32::from_rgba_premultiplied(0, 0, 0, 72),
         }
@@ -44,6 +44,9 @@ pub enum Error {
     #[error(transparent)]
     Bip32(#[from] kaspa_bip32::Error),
 
+    #[error(transparent)]
+    Pskt(#[from] kaspa_wallet_pskt::error::Error),
+
     #[error("Missing external kaspad node binary")]
     MissingExternalKaspadBinary,
 
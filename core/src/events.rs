@@ -12,6 +12,9 @@ pub enum Events {
     ChangeSection(TypeId),
     NetworkChange(Network),
     UpdateStorage(StorageUpdateOptions),
+    StorageRelocated {
+        storage_root: PathBuf,
+    },
     VisibilityChange(VisibilityState),
     VersionUpdate(Release),
     ThemeChange,
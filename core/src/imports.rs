@@ -79,6 +79,7 @@ pub use crate::error::Error;
 pub use crate::events::{ApplicationEventsChannel, Events};
 pub use crate::extensions::*;
 pub use crate::interop;
+pub use crate::invoices::{Invoice, InvoiceExpiry, InvoiceStatus, Invoices};
 pub use crate::market::MarketData;
 pub use crate::menu::Menu;
 pub use crate::modules;
@@ -87,15 +88,16 @@ pub use crate::network::BASIC_TRANSACTION_MASS;
 pub use crate::network::{Network, NetworkPressure};
 pub use crate::notifications::{Notifications, UserNotification, UserNotifyKind};
 pub use crate::primitives::{
-    Account, AccountCollection, AccountSelectorButtonExtension, BlockDagGraphSettings, DaaBucket,
-    DagBlock, Transaction, TransactionCollection,
+    Account, AccountCollection, AccountSelectorButtonExtension, BlockDagGraphSettings, ChainUpdate,
+    CoinbasePayload, DaaBucket, DagBlock, DagBlockView, DagEvent, DagHistory, MergeState,
+    Transaction, TransactionCollection,
 };
 pub use crate::result::Result;
 pub use crate::runtime::{Payload, Runtime, Service, runtime, spawn, spawn_with_result};
 pub use crate::settings::{
-    EstimatorMode, EstimatorSettings, KaspadNodeKind, NetworkInterfaceConfig, NetworkInterfaceKind,
-    NodeConnectionConfigKind, NodeMemoryScale, NodeSettings, RpcConfig, RpcOptions, Settings,
-    UserInterfaceSettings,
+    BridgeSettings, EstimatorMode, EstimatorSettings, KaspadNodeKind, MetricsExporterSettings,
+    NetworkInterfaceConfig, NetworkInterfaceKind, NodeConnectionConfigKind, NodeMemoryScale,
+    NodeSettings, RpcConfig, RpcOptions, Settings, UserInterfaceSettings,
 };
 pub use crate::state::State;
 pub use crate::status::Status;
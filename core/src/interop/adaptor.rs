@@ -3,11 +3,17 @@
 use crate::imports::*;
 use crate::interop::transport;
 use crate::interop::{Target, message::*};
+use kaspa_wallet_core::api::*;
+use kaspa_wallet_core::tx::{Fees, GeneratorSummary};
 
 #[derive(Debug, Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
 pub struct PendingRequest {
     pub sender_id: u64,
     pub id: Option<String>,
+    /// Origin of the web page that issued the request
+    pub origin: Option<String>,
+    /// Number of requests queued behind this one
+    pub remaining: u32,
     request: Request,
 }
 
@@ -16,9 +22,47 @@ impl PendingRequest {
         Self {
             sender_id,
             id,
+            origin: None,
+            remaining: 0,
             request,
         }
     }
+
+    pub fn with_origin(mut self, origin: Option<String>) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    pub fn with_remaining(mut self, remaining: u32) -> Self {
+        self.remaining = remaining;
+        self
+    }
+
+    pub fn request(&self) -> &Request {
+        &self.request
+    }
+}
+
+/// Secrets and state entered while approving a dApp request.
+#[derive(Default)]
+struct ApprovalContext {
+    wallet_secret: String,
+    payment_secret: String,
+    error: Option<String>,
+    estimate: Option<std::result::Result<GeneratorSummary, String>>,
+    denied_kinds: Vec<RequestKind>,
+    grant_duration: GrantDuration,
+}
+
+impl Zeroize for ApprovalContext {
+    fn zeroize(&mut self) {
+        self.wallet_secret.zeroize();
+        self.payment_secret.zeroize();
+        self.error = None;
+        self.estimate = None;
+        self.denied_kinds.clear();
+        self.grant_duration = GrantDuration::default();
+    }
 }
 
 #[repr(u64)]
@@ -27,13 +71,22 @@ pub enum ServerAction {
     PendingRequests,
     Response(u64, Option<String>, Vec<u8>),
     CloseWindow,
+    /// Borsh-serialized [`Notification`] to be relayed to connected pages
+    Notify(Vec<u8>),
 }
 
 pub struct Adaptor {
     sender: Arc<dyn transport::Sender>,
     _application_events: ApplicationEventsChannel,
     request: Mutex<Option<Request>>,
+    origin: Mutex<Option<String>>,
+    sender_id: Mutex<Option<u64>>,
+    /// Sequence number of the current request, used to scope
+    /// the results of operations performed for the request
+    sequence: AtomicU64,
+    remaining: AtomicU32,
     response: Channel<Vec<u8>>,
+    approval: Mutex<ApprovalContext>,
 }
 
 impl Adaptor {
@@ -45,12 +98,35 @@ impl Adaptor {
             sender,
             _application_events,
             request: Mutex::new(None),
+            origin: Mutex::new(None),
+            sender_id: Mutex::new(None),
+            sequence: AtomicU64::new(0),
+            remaining: AtomicU32::new(0),
             response: Channel::unbounded(),
+            approval: Mutex::new(ApprovalContext::default()),
         }
     }
 
     pub async fn init(self: Arc<Self>) -> Result<()> {
         log_info!("Adaptor::init()");
+        if let Some(pending_request) = self.next_request().await? {
+            // step through queued requests until the server queue is drained
+            workflow_core::task::spawn(async move {
+                let mut next = Some(pending_request);
+                while let Some(pending_request) = next.take() {
+                    self.clone().process(pending_request).await?;
+                    next = self.next_request().await?;
+                }
+
+                #[cfg(target_arch = "wasm32")]
+                let _ = workflow_dom::utils::window().close();
+                Ok::<(), Error>(())
+            });
+        }
+        Ok(())
+    }
+
+    async fn next_request(&self) -> Result<Option<PendingRequest>> {
         let res = self
             .sender
             .send_message(
@@ -58,45 +134,135 @@ impl Adaptor {
                 borsh::to_vec(&ServerAction::PendingRequests)?,
             )
             .await?;
-        // log_info!("Adaptor:init res: {res:?}");
-        if !res.is_empty() {
-            let this = self.clone();
-            let PendingRequest {
-                sender_id,
-                id,
-                request,
-            } = PendingRequest::try_from_slice(&res)?;
-            // log_info!("Adaptor:init req-id:{id:?}, action: {request:?}");
-            workflow_core::task::spawn(async move {
-                match self.handle_message(request).await {
-                    Ok(data) => {
-                        // log_info!("Adaptor:init handle_message: data:{data:?}");
-                        let res = this
-                            .sender
-                            .send_message(
-                                Target::Adaptor,
-                                borsh::to_vec(&ServerAction::Response(sender_id, id, data))?,
-                            )
-                            .await;
-                        if res.is_ok() {
-                            //TODO: should we check which request require autoclose?
-                            //log_info!("Adaptor:init sending window close msg");
-                            //let _ = this.sender.send_message(Target::Adaptor, ServerAction::CloseWindow.try_to_vec()?).await;
-                            #[cfg(target_arch = "wasm32")]
-                            let _ = workflow_dom::utils::window().close();
-                        }
-                        Ok(())
-                    }
-                    Err(err) => Err(err),
-                }
-            });
+
+        if res.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(PendingRequest::try_from_slice(&res)?))
         }
+    }
+
+    async fn process(self: Arc<Self>, pending_request: PendingRequest) -> Result<()> {
+        let PendingRequest {
+            sender_id,
+            id,
+            origin,
+            remaining,
+            request,
+        } = pending_request;
+
+        self.remaining.store(remaining, Ordering::Relaxed);
+        *self.sender_id.lock().unwrap() = Some(sender_id);
+        let data = self.clone().handle_message(request, origin).await?;
+        self.sender
+            .send_message(
+                Target::Adaptor,
+                borsh::to_vec(&ServerAction::Response(sender_id, id, data))?,
+            )
+            .await?;
         Ok(())
     }
 
     // clear the current action (must be called after the response is sent)
     fn clear(&self) {
+        // results of operations still running for the request are discarded
+        self.sign_message_result().remove();
+        self.transaction_estimate().remove();
+        self.transaction_result().remove();
+
         *self.request.lock().unwrap() = None;
+        *self.origin.lock().unwrap() = None;
+        *self.sender_id.lock().unwrap() = None;
+    }
+
+    // payload holding a result produced for the current request
+    fn request_payload<T: Send + 'static>(&self, name: &str) -> Payload<T> {
+        Payload::new(format!("{name}_{}", self.sequence.load(Ordering::SeqCst)))
+    }
+
+    pub(crate) fn sign_message_result(&self) -> Payload<Result<(Address, String)>> {
+        self.request_payload("adaptor_sign_message_result")
+    }
+
+    pub(crate) fn transaction_estimate(&self) -> Payload<Result<GeneratorSummary>> {
+        self.request_payload("adaptor_transaction_estimate")
+    }
+
+    pub(crate) fn transaction_result(&self) -> Payload<Result<interop::Response>> {
+        self.request_payload("adaptor_transaction_result")
+    }
+
+    // name of the current request origin displayed to the user
+    fn origin_name(&self) -> String {
+        self.origin
+            .lock()
+            .unwrap()
+            .clone()
+            .unwrap_or_else(|| i18n("Unknown origin").to_string())
+    }
+
+    /// Origin of the current request, if any
+    pub fn origin(&self) -> Option<String> {
+        self.origin.lock().unwrap().clone()
+    }
+
+    /// Currently pending request, if any
+    pub fn request(&self) -> Option<Request> {
+        self.request.lock().unwrap().clone()
+    }
+
+    /// Number of requests waiting behind the current one
+    pub fn remaining(&self) -> u32 {
+        self.remaining.load(Ordering::Relaxed)
+    }
+
+    /// Cancel the current request if it has been issued by the given sender
+    /// (i.e. the page that issued the request has been closed)
+    pub fn cancel(&self, sender_id: u64) {
+        let current = *self.sender_id.lock().unwrap();
+        if current == Some(sender_id) && self.request.lock().unwrap().is_some() {
+            self.approval.lock().unwrap().zeroize();
+            self.reject("The request has been canceled.");
+        }
+    }
+
+    /// Relay a wallet state change to connected web pages
+    pub fn notify(&self, notification: Notification) {
+        let sender = self.sender.clone();
+        workflow_core::task::spawn(async move {
+            let action = ServerAction::Notify(borsh::to_vec(&notification).unwrap());
+            if let Err(err) = sender
+                .send_message(Target::Adaptor, borsh::to_vec(&action).unwrap())
+                .await
+            {
+                log_warn!("Unable to relay notification: {err}");
+            }
+        });
+    }
+
+    /// Reject the currently pending request
+    pub fn reject(&self, error: impl Into<String>) {
+        self.respond(interop::Response::Canceled {
+            error: error.into(),
+        });
+    }
+
+    // post the response and clear the current action
+    fn respond(&self, response: interop::Response) {
+        self.response
+            .try_send(borsh::to_vec(&response).unwrap())
+            .unwrap();
+        self.clear();
+    }
+
+    fn account(core: &Core) -> Option<Account> {
+        let account_manager = core
+            .modules()
+            .get(&TypeId::of::<modules::AccountManager>())
+            .unwrap()
+            .clone();
+        let account_manager = account_manager.get::<modules::AccountManager>();
+        account_manager.account()
     }
 
     pub fn render(&self, core: &mut Core, ui: &mut Ui) -> bool {
@@ -136,25 +302,41 @@ impl Adaptor {
 
             Request::Connect {} => {
                 log_info!("Adaptor render -> Action::Connect: {:?}", request);
-                let account_manager = core
-                    .modules()
-                    .get(&TypeId::of::<modules::AccountManager>())
-                    .unwrap()
-                    .clone();
-                let account_manager = account_manager.get::<modules::AccountManager>();
-                if let Some(account) = account_manager.account() {
-                    let response = interop::Response::Connect {
+                // continue rendering the KNG UI until an account is selected
+                let Some(account) = Self::account(core) else {
+                    return false;
+                };
+
+                let granted = self
+                    .authorize(
+                        core,
+                        self.origin.lock().unwrap().as_ref(),
+                        RequestKind::Connect,
+                        Some(&account),
+                    )
+                    .is_ok();
+
+                if granted {
+                    self.respond(interop::Response::Connect {
                         address: account.receive_address().to_string(),
-                    };
-                    self.response
-                        .try_send(borsh::to_vec(&response).unwrap())
-                        .unwrap();
-                    self.clear();
+                    });
+                    false
+                } else {
+                    self.render_connect(core, ui, account);
+                    true
                 }
-                false
             }
 
-            // TODO - handle connect and sign message etc.
+            Request::SignMessage { message } => self.render_sign_message(core, ui, message),
+
+            Request::SendTransaction(transaction) => {
+                self.render_transaction_request(core, ui, transaction, true)
+            }
+
+            Request::SignTransaction(transaction) => {
+                self.render_transaction_request(core, ui, transaction, false)
+            }
+
             _ => {
                 // continue rendering to standard KNG UI
                 false
@@ -162,8 +344,682 @@ impl Adaptor {
         }
     }
 
-    pub async fn handle_message(self: Arc<Self>, request: Request) -> Result<Vec<u8>> {
+    fn render_queue_status(&self, ui: &mut Ui) {
+        let remaining = self.remaining();
+        if remaining > 0 {
+            ui.label(
+                RichText::new(i18n_args(
+                    "{count} more request(s) waiting for approval",
+                    &[("count", remaining.to_string())],
+                ))
+                .size(12.)
+                .color(theme_color().warning_color),
+            );
+        }
+    }
+
+    // verify that the request origin has been granted the permission
+    // to issue the request on behalf of the given account
+    fn authorize(
+        &self,
+        core: &Core,
+        origin: Option<&String>,
+        kind: RequestKind,
+        account: Option<&Account>,
+    ) -> std::result::Result<(), String> {
+        // only `Connect` requests are accepted without an origin (see `handle_message()`)
+        let Some(origin) = origin else {
+            return Err(i18n("The origin of this request is unknown.").to_string());
+        };
+
+        let Some(permission) = core.permissions.get(origin) else {
+            return Err(i18n("This site is not connected to the wallet.").to_string());
+        };
+
+        if !permission.allows(kind) {
+            return Err(i18n_args(
+                "This site has not been granted the permission to {kind}.",
+                &[("kind", kind.to_string().to_lowercase())],
+            ));
+        }
+
+        if let Some(account) = account
+            && !permission.allows_account(&account.id())
+        {
+            return Err(
+                i18n("This site is not permitted to use the selected account.").to_string(),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn render_connect(&self, core: &mut Core, ui: &mut Ui, account: Account) {
+        let origin = self.origin.lock().unwrap().clone();
+        let origin_name = self.origin_name();
+        let mut context = self.approval.lock().unwrap();
+
+        Panel::new(&mut *context)
+            .with_caption(i18n("Connect"))
+            .with_header(|_ctx, ui| {
+                ui.label(i18n_args(
+                    "{origin} requests to connect to your wallet",
+                    &[("origin", origin_name.as_str())],
+                ));
+                self.render_queue_status(ui);
+                ui.label(" ");
+            })
+            .with_body(|ctx, ui| {
+                ui.label(i18n_args(
+                    "Account: {account}",
+                    &[("account", account.name_or_id())],
+                ));
+                ui.label(i18n_args(
+                    "Address: {address}",
+                    &[("address", account.receive_address().to_string())],
+                ));
+                ui.label(" ");
+
+                ui.label(RichText::new(i18n("Allow this site to")).size(12.).raised());
+                for kind in RequestKind::list() {
+                    if kind == RequestKind::Connect {
+                        continue;
+                    }
+                    let mut allowed = !ctx.denied_kinds.contains(&kind);
+                    if ui.checkbox(&mut allowed, kind.to_string()).changed() {
+                        if allowed {
+                            ctx.denied_kinds.retain(|denied| *denied != kind);
+                        } else {
+                            ctx.denied_kinds.push(kind);
+                        }
+                    }
+                }
+                ui.label(" ");
+
+                ui.label(
+                    RichText::new(i18n("Permission expires in"))
+                        .size(12.)
+                        .raised(),
+                );
+                ui.horizontal_wrapped(|ui| {
+                    for duration in GrantDuration::list() {
+                        ui.radio_value(&mut ctx.grant_duration, duration, duration.to_string());
+                    }
+                });
+            })
+            .with_footer(|ctx, ui| {
+                if ui.large_button(i18n("Connect")).clicked() {
+                    // requests without an origin are connected without a
+                    // grant as they can not be attributed to a single client
+                    if let Some(origin) = origin.as_ref() {
+                        // keep accounts previously granted to the origin
+                        let mut accounts = core
+                            .permissions
+                            .get(origin)
+                            .map(|permission| permission.accounts)
+                            .unwrap_or_default();
+                        if !accounts.contains(&account.id()) {
+                            accounts.push(account.id());
+                        }
+
+                        let kinds = RequestKind::list()
+                            .into_iter()
+                            .filter(|kind| !ctx.denied_kinds.contains(kind))
+                            .collect();
+
+                        core.permissions.grant(OriginPermission::new(
+                            origin.clone(),
+                            accounts,
+                            kinds,
+                            ctx.grant_duration,
+                        ));
+                    }
+
+                    ctx.zeroize();
+                    self.respond(interop::Response::Connect {
+                        address: account.receive_address().to_string(),
+                    });
+                }
+
+                if ui.large_button(i18n("Reject")).clicked() {
+                    ctx.zeroize();
+                    self.reject("User rejected the request.");
+                }
+            })
+            .render(ui);
+    }
+
+    // returns `false` if the request has been rejected without rendering
+    fn render_sign_message(&self, core: &mut Core, ui: &mut Ui, message: String) -> bool {
+        let account = Self::account(core);
+        let requires_payment_passphrase = account
+            .as_ref()
+            .map(|account| account.requires_bip39_passphrase(core))
+            .unwrap_or(false);
+        let origin = self.origin_name();
+
+        let authorization = self.authorize(
+            core,
+            self.origin.lock().unwrap().as_ref(),
+            RequestKind::SignMessage,
+            account.as_ref(),
+        );
+
+        // requests the origin is not permitted to issue are rejected automatically
+        if let Err(err) = authorization {
+            self.approval.lock().unwrap().zeroize();
+            self.reject(err);
+            return false;
+        }
+
+        let sign_result = self.sign_message_result();
+        let mut context = self.approval.lock().unwrap();
+
+        if let Some(result) = sign_result.take() {
+            match result {
+                Ok((_address, signature)) => {
+                    context.zeroize();
+                    self.respond(interop::Response::SignMessage { signature });
+                    return true;
+                }
+                Err(err) => {
+                    context.error = Some(err.to_string());
+                }
+            }
+        }
+
+        let processing = sign_result.is_pending();
+
+        Panel::new(&mut *context)
+            .with_caption(i18n("Sign Message"))
+            .with_header(|_ctx, ui| {
+                ui.label(i18n_args(
+                    "{origin} requests a message signature",
+                    &[("origin", origin.as_str())],
+                ));
+                self.render_queue_status(ui);
+                ui.label(" ");
+            })
+            .with_body(|ctx, ui| {
+                ui.label(RichText::new(i18n("Message")).size(12.).raised());
+                egui::ScrollArea::vertical()
+                    .id_salt("adaptor_sign_message")
+                    .max_height(160.)
+                    .show(ui, |ui| {
+                        ui.label(RichText::new(message.as_str()).font(FontId::monospace(12.)));
+                    });
+                ui.label(" ");
+
+                let Some(account) = account.as_ref() else {
+                    ui.label(
+                        RichText::new(i18n(
+                            "Please open a wallet and select an account to sign with.",
+                        ))
+                        .color(theme_color().warning_color),
+                    );
+                    return;
+                };
+
+                ui.label(i18n_args(
+                    "Account: {account}",
+                    &[("account", account.name_or_id())],
+                ));
+                ui.label(i18n_args(
+                    "Address: {address}",
+                    &[("address", account.receive_address().to_string())],
+                ));
+                ui.label(" ");
+
+                ui.label(
+                    RichText::new(i18n("Enter your wallet secret"))
+                        .size(12.)
+                        .raised(),
+                );
+                ui.add_enabled(
+                    !processing,
+                    TextEdit::singleline(&mut ctx.wallet_secret).password(true),
+                );
+
+                if requires_payment_passphrase {
+                    ui.label(
+                        RichText::new(i18n("Enter your BIP39 passphrase"))
+                            .size(12.)
+                            .raised(),
+                    );
+                    ui.add_enabled(
+                        !processing,
+                        TextEdit::singleline(&mut ctx.payment_secret).password(true),
+                    );
+                }
+
+                if let Some(error) = ctx.error.as_ref() {
+                    ui.label(" ");
+                    ui.label(RichText::new(error).color(theme_color().error_color));
+                }
+            })
+            .with_footer(|ctx, ui| {
+                if processing {
+                    ui.add(egui::Spinner::new().size(32.));
+                    return;
+                }
+
+                let enabled = account
+                    .as_ref()
+                    .is_some_and(|account| !account.is_watch_only())
+                    && ctx.wallet_secret.is_not_empty()
+                    && (!requires_payment_passphrase || ctx.payment_secret.is_not_empty());
+
+                if ui.large_button_enabled(enabled, i18n("Sign")).clicked()
+                    && let Some(account) = account.as_ref()
+                {
+                    let account_id = account.id();
+                    let message = message.clone();
+                    let wallet_secret = Secret::from(ctx.wallet_secret.as_str());
+                    let payment_secret = requires_payment_passphrase
+                        .then_some(Secret::from(ctx.payment_secret.as_str()));
+                    ctx.zeroize();
+
+                    spawn_with_result(&sign_result, async move {
+                        let result = sign_personal_message(
+                            account_id,
+                            message,
+                            wallet_secret,
+                            payment_secret,
+                        )
+                        .await;
+                        runtime().request_repaint();
+                        result
+                    });
+                }
+
+                if ui.large_button(i18n("Reject")).clicked() {
+                    ctx.zeroize();
+                    self.reject("User rejected the request.");
+                }
+            })
+            .render(ui);
+
+        true
+    }
+
+    // returns `false` if the request has been rejected without rendering
+    fn render_transaction_request(
+        &self,
+        core: &mut Core,
+        ui: &mut Ui,
+        transaction: TransactionRequest,
+        submit: bool,
+    ) -> bool {
+        let account = Self::account(core);
+        let requires_payment_passphrase = account
+            .as_ref()
+            .map(|account| account.requires_bip39_passphrase(core))
+            .unwrap_or(false);
+        let origin = self.origin_name();
+        let kind = if submit {
+            RequestKind::SendTransaction
+        } else {
+            RequestKind::SignTransaction
+        };
+        let authorization = self.authorize(
+            core,
+            self.origin.lock().unwrap().as_ref(),
+            kind,
+            account.as_ref(),
+        );
+
+        // requests the origin is not permitted to issue are rejected automatically
+        if let Err(err) = authorization {
+            self.approval.lock().unwrap().zeroize();
+            self.reject(err);
+            return false;
+        }
+
+        let network_type: NetworkType = core.settings.node.network.into();
+        let fee_rate = transaction.priority.fee_rate(core.feerate.as_ref());
+        let destination = transaction.destination(network_type);
+
+        let Some(amount_sompi) = transaction.total_amount_sompi() else {
+            self.approval.lock().unwrap().zeroize();
+            self.reject("Transaction request amount exceeds the maximum supply.");
+            return false;
+        };
+
+        if self.update_transaction_results(amount_sompi) {
+            return true;
+        }
+
+        let estimate_result = self.transaction_estimate();
+        let transaction_result = self.transaction_result();
+        let mut context = self.approval.lock().unwrap();
+
+        if let (Some(account), Ok(destination)) = (account.as_ref(), destination.as_ref())
+            && context.estimate.is_none()
+            && !estimate_result.is_pending()
+        {
+            let request = AccountsEstimateRequest {
+                account_id: account.id(),
+                destination: destination.clone(),
+                priority_fee_sompi: Fees::SenderPays(0),
+                fee_rate: Some(fee_rate),
+                payload: transaction.payload.clone(),
+            };
+
+            spawn_with_result(&estimate_result, async move {
+                let generator_summary = runtime()
+                    .wallet()
+                    .accounts_estimate_call(request)
+                    .await?
+                    .generator_summary;
+                runtime().request_repaint();
+                Ok(generator_summary)
+            });
+        }
+
+        let processing = transaction_result.is_pending();
+        let (caption, header) = if submit {
+            (i18n("Send Transaction"), "{origin} requests a payment")
+        } else {
+            (
+                i18n("Sign Transaction"),
+                "{origin} requests a signed transaction",
+            )
+        };
+
+        Panel::new(&mut *context)
+            .with_caption(caption)
+            .with_header(|_ctx, ui| {
+                ui.label(i18n_args(header, &[("origin", origin.as_str())]));
+                self.render_queue_status(ui);
+                ui.label(" ");
+            })
+            .with_body(|ctx, ui| {
+                ui.label(RichText::new(i18n("Outputs")).size(12.).raised());
+                egui::ScrollArea::vertical()
+                    .id_salt("adaptor_transaction_outputs")
+                    .max_height(160.)
+                    .show(ui, |ui| {
+                        for output in transaction.outputs.iter() {
+                            ui.label(
+                                RichText::new(output.address.as_str()).font(FontId::monospace(12.)),
+                            );
+                            ui.label(
+                                RichText::new(sompi_to_kaspa_string_with_suffix(
+                                    output.amount_sompi,
+                                    &network_type,
+                                ))
+                                .strong(),
+                            );
+                            ui.add_space(4.);
+                        }
+                    });
+
+                if let Some(payload) = transaction.payload.as_ref() {
+                    ui.label(i18n_args(
+                        "Payload: {bytes} bytes",
+                        &[("bytes", payload.len().to_string())],
+                    ));
+                }
+                ui.label(i18n_args(
+                    "Fee priority: {priority} ({feerate} SOMPI/g)",
+                    &[
+                        ("priority", transaction.priority.to_string()),
+                        ("feerate", format_with_precision(fee_rate)),
+                    ],
+                ));
+                ui.label(" ");
+
+                if let Err(err) = destination.as_ref() {
+                    ui.label(RichText::new(err.to_string()).color(theme_color().error_color));
+                    return;
+                }
+
+                let Some(account) = account.as_ref() else {
+                    ui.label(
+                        RichText::new(i18n(
+                            "Please open a wallet and select an account to pay from.",
+                        ))
+                        .color(theme_color().warning_color),
+                    );
+                    return;
+                };
+
+                ui.label(i18n_args(
+                    "Account: {account}",
+                    &[("account", account.name_or_id())],
+                ));
+
+                match ctx.estimate.as_ref() {
+                    Some(Ok(estimate)) => {
+                        ui.label(format!(
+                            "{}  •  {}  •  {}",
+                            i18n_args(
+                                "Transactions: {number}",
+                                &[(
+                                    "number",
+                                    estimate.number_of_generated_transactions.to_string()
+                                )]
+                            ),
+                            i18n_args(
+                                "UTXOs: {number}",
+                                &[("number", estimate.aggregated_utxos.to_string())]
+                            ),
+                            i18n_args(
+                                "Mass: {number}g",
+                                &[("number", estimate.aggregate_mass.to_string())]
+                            ),
+                        ));
+                        ui.label(i18n_args(
+                            "Fees: {amount}",
+                            &[(
+                                "amount",
+                                sompi_to_kaspa_string_with_suffix(
+                                    estimate.aggregate_fees,
+                                    &network_type,
+                                ),
+                            )],
+                        ));
+                        ui.label(
+                            RichText::new(i18n_args(
+                                "Total: {amount}",
+                                &[(
+                                    "amount",
+                                    sompi_to_kaspa_string_with_suffix(
+                                        amount_sompi.saturating_add(estimate.aggregate_fees),
+                                        &network_type,
+                                    ),
+                                )],
+                            ))
+                            .strong(),
+                        );
+                    }
+                    Some(Err(err)) => {
+                        ui.label(RichText::new(err).color(theme_color().error_color));
+                    }
+                    None => {
+                        ui.horizontal(|ui| {
+                            ui.add(egui::Spinner::new());
+                            ui.label(i18n("Estimating fees..."));
+                        });
+                    }
+                }
+                ui.label(" ");
+
+                ui.label(
+                    RichText::new(i18n("Enter your wallet secret"))
+                        .size(12.)
+                        .raised(),
+                );
+                ui.add_enabled(
+                    !processing,
+                    TextEdit::singleline(&mut ctx.wallet_secret).password(true),
+                );
+
+                if requires_payment_passphrase {
+                    ui.label(
+                        RichText::new(i18n("Enter your BIP39 passphrase"))
+                            .size(12.)
+                            .raised(),
+                    );
+                    ui.add_enabled(
+                        !processing,
+                        TextEdit::singleline(&mut ctx.payment_secret).password(true),
+                    );
+                }
+
+                if let Some(error) = ctx.error.as_ref() {
+                    ui.label(" ");
+                    ui.label(RichText::new(error).color(theme_color().error_color));
+                }
+            })
+            .with_footer(|ctx, ui| {
+                if processing {
+                    ui.add(egui::Spinner::new().size(32.));
+                    return;
+                }
+
+                let enabled = account
+                    .as_ref()
+                    .is_some_and(|account| !account.is_watch_only())
+                    && matches!(ctx.estimate, Some(Ok(_)))
+                    && ctx.wallet_secret.is_not_empty()
+                    && (!requires_payment_passphrase || ctx.payment_secret.is_not_empty());
+
+                let label = if submit { i18n("Send") } else { i18n("Sign") };
+                if ui.large_button_enabled(enabled, label).clicked()
+                    && let (Some(account), Ok(destination)) =
+                        (account.as_ref(), destination.as_ref())
+                {
+                    let account_id = account.id();
+                    let destination = destination.clone();
+                    let payload = transaction.payload.clone();
+                    let wallet_secret = Secret::from(ctx.wallet_secret.as_str());
+                    let payment_secret = requires_payment_passphrase
+                        .then_some(Secret::from(ctx.payment_secret.as_str()));
+                    ctx.wallet_secret.zeroize();
+                    ctx.payment_secret.zeroize();
+                    ctx.error = None;
+
+                    spawn_with_result(&transaction_result, async move {
+                        let response = if submit {
+                            let request = AccountsSendRequest {
+                                account_id,
+                                destination,
+                                wallet_secret,
+                                payment_secret,
+                                fee_rate: Some(fee_rate),
+                                priority_fee_sompi: Fees::SenderPays(0),
+                                payload,
+                            };
+                            let transaction_ids = runtime()
+                                .wallet()
+                                .accounts_send_call(request)
+                                .await?
+                                .transaction_ids;
+                            interop::Response::SendTransaction {
+                                transaction_ids: transaction_ids
+                                    .iter()
+                                    .map(|id| id.to_string())
+                                    .collect(),
+                            }
+                        } else {
+                            // bundles are created from the account keys directly
+                            // as there is no wallet API call producing a PSKB
+                            let account = load_local_account(&account_id).await?;
+                            let abortable = Abortable::default();
+                            let bundle = account
+                                .pskb_from_send_generator(
+                                    destination,
+                                    Some(fee_rate),
+                                    Fees::SenderPays(0),
+                                    payload,
+                                    wallet_secret,
+                                    payment_secret,
+                                    &abortable,
+                                )
+                                .await?;
+                            interop::Response::SignTransaction {
+                                pskb: bundle.serialize()?,
+                            }
+                        };
+                        runtime().request_repaint();
+                        Ok(response)
+                    });
+                }
+
+                if ui.large_button(i18n("Reject")).clicked() {
+                    ctx.zeroize();
+                    self.reject("User rejected the request.");
+                }
+            })
+            .render(ui);
+
+        true
+    }
+
+    // applies the fee estimate and the transaction produced for the current
+    // request, returns `true` once the request has been responded to
+    pub(crate) fn update_transaction_results(&self, amount_sompi: u64) -> bool {
+        let mut context = self.approval.lock().unwrap();
+
+        if let Some(result) = self.transaction_estimate().take() {
+            let result = result.map_err(|err| err.to_string()).and_then(|estimate| {
+                amount_sompi
+                    .checked_add(estimate.aggregate_fees)
+                    .map(|_| estimate)
+                    .ok_or_else(|| {
+                        i18n("Transaction amount including fees is too large").to_string()
+                    })
+            });
+            context.estimate = Some(result);
+        }
+
+        if let Some(result) = self.transaction_result().take() {
+            match result {
+                Ok(response) => {
+                    context.zeroize();
+                    self.respond(response);
+                    return true;
+                }
+                Err(err) => {
+                    context.error = Some(err.to_string());
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Fee estimate of the current transaction request, if available
+    pub(crate) fn estimate(&self) -> Option<std::result::Result<GeneratorSummary, String>> {
+        self.approval.lock().unwrap().estimate.clone()
+    }
+
+    pub async fn handle_message(
+        self: Arc<Self>,
+        request: Request,
+        origin: Option<String>,
+    ) -> Result<Vec<u8>> {
+        // permissions are granted per origin, so requests other than
+        // `Connect` are rejected if they can not be attributed to one
+        if origin.is_none()
+            && request
+                .kind()
+                .is_some_and(|kind| kind != RequestKind::Connect)
+        {
+            let response = interop::Response::Canceled {
+                error: "Unknown request origin.".to_string(),
+            };
+            return Ok(borsh::to_vec(&response)?);
+        }
+
+        self.sequence.fetch_add(1, Ordering::SeqCst);
+        *self.origin.lock().unwrap() = origin;
         self.request.lock().unwrap().replace(request);
+        // requests may arrive while the application is idle (e.g. over the wallet bridge)
+        if let Some(runtime) = crate::runtime::try_runtime() {
+            runtime.request_repaint();
+        }
 
         let response = self.response.receiver.recv().await?;
         Ok(response)
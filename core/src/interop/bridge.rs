@@ -0,0 +1,482 @@
+//! Local WebSocket wallet bridge
+//!
+//! Exposes the approval [`Adaptor`] of the desktop application to local
+//! web apps and scripts over a WebSocket bound to the loopback interface.
+//! Clients must pair using the token displayed in the application settings
+//! before issuing requests.
+
+use crate::imports::*;
+use crate::interop::transport;
+use crate::interop::{
+    Adaptor, LOCAL_SCRIPT_ORIGIN_PREFIX, PendingRequest, ServerAction, Target, message::*,
+};
+use futures::SinkExt;
+use std::sync::Weak;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::handshake::server::{
+    ErrorResponse, Request as HandshakeRequest, Response as HandshakeResponse,
+};
+
+/// Time after which a request that has not been processed is canceled.
+const PENDING_REQUEST_TIMEOUT: f64 = 5.0 * 60.0 * 1000.0;
+
+/// Message sent by a bridge client.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClientMessage {
+    action: String,
+    #[serde(default)]
+    rid: Option<String>,
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+struct QueuedRequest {
+    request: PendingRequest,
+    expires: f64,
+}
+
+#[derive(Default)]
+struct Queue {
+    requests: VecDeque<QueuedRequest>,
+    /// Connection and request id of the request presented to the user
+    waiting: Option<(u64, Option<String>)>,
+    /// Adaptor is stepping through the queue
+    active: bool,
+}
+
+pub struct Bridge {
+    token: String,
+    queue: Mutex<Queue>,
+    /// Origin and outbound channel of paired connections
+    connections: Mutex<AHashMap<u64, (String, Sender<String>)>>,
+    adaptor: Mutex<Weak<Adaptor>>,
+    next_connection_id: AtomicU64,
+}
+
+impl Bridge {
+    pub fn new(token: &str) -> Self {
+        Self {
+            token: token.to_string(),
+            queue: Mutex::new(Queue::default()),
+            connections: Mutex::new(AHashMap::default()),
+            adaptor: Mutex::new(Weak::new()),
+            next_connection_id: AtomicU64::new(0),
+        }
+    }
+
+    /// Creates the bridge along with the adaptor presenting its requests
+    /// and starts listening on the configured localhost port.
+    pub fn start(settings: &BridgeSettings) -> Arc<Adaptor> {
+        let bridge = Arc::new(Self::new(settings.token.as_str()));
+        let adaptor = bridge.bind();
+
+        let port = settings.port;
+        let this = bridge.clone();
+        tokio::spawn(async move {
+            if let Err(err) = this.listen(port).await {
+                log_error!("Wallet bridge error: {err}");
+            }
+        });
+
+        adaptor
+    }
+
+    /// Creates the adaptor receiving requests from this bridge.
+    pub fn bind(self: &Arc<Self>) -> Arc<Adaptor> {
+        let adaptor = Arc::new(Adaptor::new(
+            self.clone(),
+            ApplicationEventsChannel::unbounded(),
+        ));
+        *self.adaptor.lock().unwrap() = Arc::downgrade(&adaptor);
+        adaptor
+    }
+
+    async fn listen(self: Arc<Self>, port: u16) -> Result<()> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .await
+            .map_err(|err| Error::custom(format!("unable to bind port {port}: {err}")))?;
+        log_info!("Wallet bridge listening on ws://127.0.0.1:{port}");
+
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let this = self.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = this.connection(stream).await {
+                            log_warn!("Wallet bridge connection error: {err}");
+                        }
+                    });
+                }
+                Err(err) => {
+                    log_warn!("Wallet bridge accept error: {err}");
+                }
+            }
+        }
+    }
+
+    async fn connection(self: Arc<Self>, stream: TcpStream) -> Result<()> {
+        let mut origin = None;
+        let websocket = tokio_tungstenite::accept_hdr_async(
+            stream,
+            |request: &HandshakeRequest,
+             response: HandshakeResponse|
+             -> std::result::Result<HandshakeResponse, ErrorResponse> {
+                origin = request
+                    .headers()
+                    .get("origin")
+                    .and_then(|value| value.to_str().ok())
+                    .map(String::from);
+                Ok(response)
+            },
+        )
+        .await
+        .map_err(|err| Error::custom(err.to_string()))?;
+        // clients without an origin are told apart by their paired session
+        let origin = origin.unwrap_or_else(Self::session_origin);
+
+        let (mut sink, mut stream) = websocket.split();
+
+        // the first message must carry the pairing token
+        let paired = match stream.next().await {
+            Some(Ok(Message::Text(text))) => serde_json::from_str::<ClientMessage>(text.as_str())
+                .is_ok_and(|message| {
+                    message.action == "Pair"
+                        && message
+                            .data
+                            .as_str()
+                            .is_some_and(|token| self.is_valid_token(token))
+                }),
+            _ => false,
+        };
+
+        if !paired {
+            let response = Response::Canceled {
+                error: "Invalid pairing token.".to_string(),
+            };
+            sink.send(Message::text(Self::encode(None, &response)))
+                .await
+                .ok();
+            sink.close().await.ok();
+            return Ok(());
+        }
+
+        let connection_id = self.next_connection_id.fetch_add(1, Ordering::SeqCst);
+        let outbound = Channel::<String>::unbounded();
+        self.register(connection_id, origin.as_str(), outbound.sender.clone());
+        outbound
+            .sender
+            .try_send(serde_json::json!({ "type": "Paired" }).to_string())
+            .ok();
+
+        let result = loop {
+            select! {
+                msg = outbound.receiver.recv().fuse() => {
+                    let Ok(text) = msg else { break Ok(()) };
+                    if let Err(err) = sink.send(Message::text(text)).await {
+                        break Err(Error::custom(err.to_string()));
+                    }
+                },
+                msg = stream.next().fuse() => {
+                    match msg {
+                        Some(Ok(Message::Text(text))) => {
+                            self.handle_message(connection_id, origin.as_str(), text.as_str()).await;
+                        }
+                        Some(Ok(Message::Close(_))) | None => break Ok(()),
+                        Some(Ok(_)) => { },
+                        Some(Err(err)) => break Err(Error::custom(err.to_string())),
+                    }
+                },
+            }
+        };
+
+        self.disconnect(connection_id);
+        result
+    }
+
+    /// Compares the pairing token in constant time.
+    fn is_valid_token(&self, token: &str) -> bool {
+        let (expected, token) = (self.token.as_bytes(), token.as_bytes());
+        expected.len() == token.len()
+            && expected
+                .iter()
+                .zip(token)
+                .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+
+    /// Creates a unique origin for a client that does not supply an `Origin`
+    /// header, so that permissions are granted to a single paired session.
+    pub fn session_origin() -> String {
+        use rand::RngExt;
+        let bytes: [u8; 8] = rand::rng().random();
+        let session = bytes
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        format!("{LOCAL_SCRIPT_ORIGIN_PREFIX}{session}")
+    }
+
+    /// Processes a message received from a paired connection.
+    pub async fn handle_message(self: &Arc<Self>, connection_id: u64, origin: &str, text: &str) {
+        let ClientMessage { action, rid, data } = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(err) => {
+                self.cancel(connection_id, None, format!("Invalid message: {err}"));
+                return;
+            }
+        };
+
+        let request = match action.as_str() {
+            "Connect" => Request::Connect {},
+            "SignMessage" => match data.as_str() {
+                Some(message) => Request::SignMessage {
+                    message: message.to_string(),
+                },
+                None => {
+                    self.cancel(connection_id, rid, "Message must be a string.".to_string());
+                    return;
+                }
+            },
+            "SendTransaction" | "SignTransaction" => {
+                match serde_json::from_value::<TransactionRequest>(data) {
+                    Ok(transaction) if action == "SendTransaction" => {
+                        Request::SendTransaction(transaction)
+                    }
+                    Ok(transaction) => Request::SignTransaction(transaction),
+                    Err(err) => {
+                        self.cancel(
+                            connection_id,
+                            rid,
+                            format!("Invalid transaction request: {err}"),
+                        );
+                        return;
+                    }
+                }
+            }
+            _ => {
+                self.cancel(connection_id, rid, format!("Unsupported action: {action}"));
+                return;
+            }
+        };
+
+        // requests other than `Connect` require a grant issued to the origin
+        if !matches!(request, Request::Connect {}) {
+            let permissions = Permissions::default();
+            if let Err(err) = permissions.load().await {
+                log_warn!("Wallet bridge: unable to load permissions: {err}");
+            }
+            let allowed = request
+                .kind()
+                .is_some_and(|kind| permissions.is_allowed(origin, kind));
+            if !allowed {
+                self.cancel(
+                    connection_id,
+                    rid,
+                    format!(
+                        "{origin} is not permitted to issue this request. Please connect first."
+                    ),
+                );
+                return;
+            }
+        }
+
+        self.enqueue(
+            PendingRequest::new(connection_id, rid, request).with_origin(Some(origin.to_string())),
+        );
+    }
+
+    /// Queues the request and wakes up the adaptor if it is idle.
+    pub fn enqueue(self: &Arc<Self>, request: PendingRequest) {
+        self.prune();
+
+        let expires = unixtime_as_millis_f64() + PENDING_REQUEST_TIMEOUT;
+        let activate = {
+            let mut queue = self.queue.lock().unwrap();
+            queue.requests.push_back(QueuedRequest { request, expires });
+            !std::mem::replace(&mut queue.active, true)
+        };
+
+        if activate {
+            let adaptor = self.adaptor.lock().unwrap().upgrade();
+            match adaptor {
+                Some(adaptor) => {
+                    workflow_core::task::spawn(async move {
+                        if let Err(err) = adaptor.init().await {
+                            log_error!("Wallet bridge: adaptor error: {err}");
+                        }
+                    });
+                }
+                None => {
+                    self.queue.lock().unwrap().active = false;
+                }
+            }
+        }
+    }
+
+    // cancel requests that have not been processed in time
+    fn prune(&self) {
+        let now = unixtime_as_millis_f64();
+        let expired = {
+            let mut queue = self.queue.lock().unwrap();
+            let (expired, pending): (VecDeque<_>, VecDeque<_>) = queue
+                .requests
+                .drain(..)
+                .partition(|queued| queued.expires <= now);
+            queue.requests = pending;
+            expired
+        };
+
+        for QueuedRequest { request, .. } in expired {
+            self.cancel(
+                request.sender_id,
+                request.id,
+                "The request has timed out.".to_string(),
+            );
+        }
+    }
+
+    fn next_request(&self) -> Option<PendingRequest> {
+        self.prune();
+
+        let mut queue = self.queue.lock().unwrap();
+        match queue.requests.pop_front() {
+            Some(QueuedRequest { request, .. }) => {
+                queue.waiting = Some((request.sender_id, request.id.clone()));
+                let remaining = queue.requests.len() as u32;
+                Some(request.with_remaining(remaining))
+            }
+            None => {
+                queue.active = false;
+                None
+            }
+        }
+    }
+
+    // drop requests issued by a closed connection
+    fn disconnect(&self, connection_id: u64) {
+        self.connections.lock().unwrap().remove(&connection_id);
+
+        let in_flight = {
+            let mut queue = self.queue.lock().unwrap();
+            queue
+                .requests
+                .retain(|queued| queued.request.sender_id != connection_id);
+            queue
+                .waiting
+                .as_ref()
+                .is_some_and(|(id, _)| *id == connection_id)
+        };
+
+        if in_flight {
+            if let Some(adaptor) = self.adaptor.lock().unwrap().upgrade() {
+                adaptor.cancel(connection_id);
+            }
+        }
+    }
+
+    /// Registers an outbound channel for a connection.
+    pub fn register(&self, connection_id: u64, origin: &str, sender: Sender<String>) {
+        self.connections
+            .lock()
+            .unwrap()
+            .insert(connection_id, (origin.to_string(), sender));
+    }
+
+    fn cancel(&self, connection_id: u64, rid: Option<String>, error: String) {
+        self.reply(connection_id, rid, &Response::Canceled { error });
+    }
+
+    fn reply(&self, connection_id: u64, rid: Option<String>, response: &Response) {
+        let sender = self
+            .connections
+            .lock()
+            .unwrap()
+            .get(&connection_id)
+            .map(|(_, sender)| sender.clone());
+        if let Some(sender) = sender {
+            sender.try_send(Self::encode(rid, response)).ok();
+        }
+    }
+
+    /// Posts the notification to connections of origins holding a grant.
+    pub async fn broadcast(&self, notification: &Notification) {
+        let permissions = Permissions::default();
+        if let Err(err) = permissions.load().await {
+            log_warn!("Wallet bridge: unable to load permissions: {err}");
+        }
+
+        let connections = self
+            .connections
+            .lock()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        for (origin, sender) in connections {
+            if let Some(event) = notification.event_for(permissions.get(origin.as_str()).as_ref()) {
+                sender.try_send(Self::encode_event(&event)).ok();
+            }
+        }
+    }
+
+    /// Encodes a page event as JSON.
+    pub fn encode_event(event: &PageEvent) -> String {
+        let mut value = serde_json::to_value(event).unwrap_or_default();
+        if let Some(object) = value.as_object_mut() {
+            object.insert("type".to_string(), "Event".into());
+        }
+        value.to_string()
+    }
+
+    /// Encodes a response as JSON, tagged with the id of the request.
+    pub fn encode(rid: Option<String>, response: &Response) -> String {
+        let mut value = serde_json::to_value(response).unwrap_or_default();
+        if let (Some(rid), Some(object)) = (rid, value.as_object_mut()) {
+            object.insert("rid".to_string(), rid.into());
+        }
+        value.to_string()
+    }
+}
+
+#[async_trait]
+impl transport::Sender for Bridge {
+    async fn send_message(
+        &self,
+        target: Target,
+        data: Vec<u8>,
+    ) -> kaspa_wallet_core::result::Result<Vec<u8>> {
+        if !matches!(target, Target::Adaptor) {
+            return Err(kaspa_wallet_core::error::Error::custom(
+                "Unsupported wallet bridge target",
+            ));
+        }
+
+        let action = ServerAction::try_from_slice(&data)
+            .map_err(|err| kaspa_wallet_core::error::Error::custom(err.to_string()))?;
+
+        match action {
+            ServerAction::PendingRequests => Ok(self
+                .next_request()
+                .map(|request| borsh::to_vec(&request).unwrap())
+                .unwrap_or_default()),
+            ServerAction::Response(connection_id, rid, data) => {
+                let response = Response::try_from_slice(&data)
+                    .map_err(|err| kaspa_wallet_core::error::Error::custom(err.to_string()))?;
+                let waiting = self.queue.lock().unwrap().waiting.take();
+                if waiting == Some((connection_id, rid.clone())) {
+                    self.reply(connection_id, rid, &response);
+                }
+                Ok(vec![])
+            }
+            ServerAction::CloseWindow => Ok(vec![]),
+            ServerAction::Notify(data) => {
+                let notification = Notification::try_from_slice(&data)
+                    .map_err(|err| kaspa_wallet_core::error::Error::custom(err.to_string()))?;
+                self.broadcast(&notification).await;
+                Ok(vec![])
+            }
+        }
+    }
+}
@@ -48,7 +48,7 @@ impl Client {
             Target::Runtime => Ok(None),
             Target::Adaptor => {
                 let action = Request::try_from_slice(&data)?;
-                let response = self.adaptor.clone().handle_message(action).await?;
+                let response = self.adaptor.clone().handle_message(action, None).await?;
                 Ok(Some(response))
             }
         }
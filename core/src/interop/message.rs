@@ -1,11 +1,24 @@
 use crate::imports::*;
+use kaspa_wallet_core::tx::{PaymentDestination, PaymentOutput, PaymentOutputs};
 
 #[derive(Debug, Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
 pub enum Request {
-    Test { data: String },
+    Test {
+        data: String,
+    },
     Connect {},
-    SignMessage { message: String },
+    SignMessage {
+        message: String,
+    },
+    /// Build, sign and submit a payment
+    SendTransaction(TransactionRequest),
+    /// Build and sign a payment, returning the signed bundle without submitting it
+    SignTransaction(TransactionRequest),
     CloseWindow,
+    /// Cancel the request issued by the given sender
+    Cancel {
+        sender_id: u64,
+    },
 }
 
 // #[repr(u64)]
@@ -15,8 +28,156 @@ pub enum Response {
     Test { response: String },
     Connect { address: String },
     SignMessage { signature: String },
+    SendTransaction { transaction_ids: Vec<String> },
+    SignTransaction { pskb: String },
     Canceled { error: String },
 }
+
+/// Wallet state change relayed by the application to connected web pages.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum Notification {
+    /// The user has selected a different account
+    AccountChanged {
+        account_id: AccountId,
+        address: String,
+    },
+    /// The user has switched to a different network
+    NetworkChanged { network: String },
+}
+
+/// Event posted to a connected web page as `{ type: "Event", event, data }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", content = "data", rename_all = "camelCase")]
+pub enum PageEvent {
+    /// Addresses available to the page; empty if the selected
+    /// account has not been shared with the page's origin
+    AccountsChanged {
+        accounts: Vec<String>,
+    },
+    NetworkChanged {
+        network: String,
+    },
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum FeePriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl FeePriority {
+    /// Fee rate (SOMPI per gram) for this priority based on the current network estimate.
+    pub fn fee_rate(&self, feerate: Option<&FeerateEstimate>) -> f64 {
+        let Some(feerate) = feerate else {
+            return 1.0;
+        };
+
+        let bucket = match self {
+            FeePriority::Low => feerate.low.value(),
+            FeePriority::Normal => feerate.economic.value(),
+            FeePriority::High => feerate.priority.value(),
+        };
+
+        bucket.feerate.max(1.0)
+    }
+}
+
+impl std::fmt::Display for FeePriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FeePriority::Low => write!(f, "{}", i18n("Low")),
+            FeePriority::Normal => write!(f, "{}", i18n("Economic")),
+            FeePriority::High => write!(f, "{}", i18n("Priority")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionRequestOutput {
+    pub address: String,
+    pub amount_sompi: u64,
+}
+
+/// Payment requested by a dApp.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionRequest {
+    pub outputs: Vec<TransactionRequestOutput>,
+    #[serde(default)]
+    pub payload: Option<Vec<u8>>,
+    #[serde(default)]
+    pub priority: FeePriority,
+}
+
+impl TransactionRequest {
+    /// Sum of the requested output amounts, `None` if the sum overflows.
+    pub fn total_amount_sompi(&self) -> Option<u64> {
+        self.outputs
+            .iter()
+            .try_fold(0u64, |total, output| total.checked_add(output.amount_sompi))
+    }
+
+    /// Validates the requested outputs against the given network and
+    /// converts them into a payment destination.
+    pub fn destination(&self, network_type: NetworkType) -> Result<PaymentDestination> {
+        if self.outputs.is_empty() {
+            return Err(Error::custom("Transaction request has no outputs"));
+        }
+
+        if self.total_amount_sompi().is_none() {
+            return Err(Error::custom(
+                "Transaction request amount exceeds the maximum supply",
+            ));
+        }
+
+        let outputs = self
+            .outputs
+            .iter()
+            .map(|output| {
+                let address = Address::try_from(output.address.as_str()).map_err(|err| {
+                    Error::custom(format!("Invalid address {}: {err}", output.address))
+                })?;
+
+                if NetworkType::try_from(address.prefix)? != network_type {
+                    return Err(Error::custom(format!(
+                        "Address {} does not belong to the {network_type} network",
+                        output.address
+                    )));
+                }
+
+                if output.amount_sompi == 0 {
+                    return Err(Error::custom(format!(
+                        "Output amount for {} must be greater than zero",
+                        output.address
+                    )));
+                }
+
+                Ok(PaymentOutput {
+                    address,
+                    amount: output.amount_sompi,
+                    covenant: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(PaymentOutputs { outputs }.into())
+    }
+}
 // pub enum Response {
 //     Test(TestResponse),
 //     Connect(ConnectResponse),
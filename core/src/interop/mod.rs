@@ -1,11 +1,17 @@
 //! Wallet Interop
 
 pub mod adaptor;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod bridge;
 pub mod client;
 pub mod message;
+pub mod permissions;
 pub mod transport;
 
 pub use adaptor::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use bridge::*;
 pub use client::*;
 pub use message::*;
+pub use permissions::*;
 pub use transport::*;
@@ -0,0 +1,272 @@
+use crate::imports::*;
+use crate::interop::message::{Notification, PageEvent, Request};
+use kaspa_wallet_core::storage::local::storage::Storage;
+
+/// Kind of a dApp request that can be granted to an origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RequestKind {
+    Connect,
+    SignMessage,
+    SendTransaction,
+    SignTransaction,
+}
+
+impl RequestKind {
+    pub fn list() -> [RequestKind; 4] {
+        [
+            RequestKind::Connect,
+            RequestKind::SignMessage,
+            RequestKind::SendTransaction,
+            RequestKind::SignTransaction,
+        ]
+    }
+}
+
+impl std::fmt::Display for RequestKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestKind::Connect => write!(f, "{}", i18n("Connect")),
+            RequestKind::SignMessage => write!(f, "{}", i18n("Sign Messages")),
+            RequestKind::SendTransaction => write!(f, "{}", i18n("Send Transactions")),
+            RequestKind::SignTransaction => write!(f, "{}", i18n("Sign Transactions")),
+        }
+    }
+}
+
+impl Request {
+    /// Permission required to process this request, `None` for
+    /// requests that are not issued by web pages.
+    pub fn kind(&self) -> Option<RequestKind> {
+        match self {
+            Request::Connect {} => Some(RequestKind::Connect),
+            Request::SignMessage { .. } => Some(RequestKind::SignMessage),
+            Request::SendTransaction(_) => Some(RequestKind::SendTransaction),
+            Request::SignTransaction(_) => Some(RequestKind::SignTransaction),
+            Request::Test { .. } | Request::CloseWindow | Request::Cancel { .. } => None,
+        }
+    }
+}
+
+impl Notification {
+    /// Event to be posted to a page of an origin holding the given grant.
+    /// Pages of origins without a grant are not notified.
+    pub fn event_for(&self, permission: Option<&OriginPermission>) -> Option<PageEvent> {
+        let permission = permission?;
+        match self {
+            Notification::AccountChanged {
+                account_id,
+                address,
+            } => {
+                let accounts = if permission.allows_account(account_id) {
+                    vec![address.clone()]
+                } else {
+                    vec![]
+                };
+                Some(PageEvent::AccountsChanged { accounts })
+            }
+            Notification::NetworkChanged { network } => Some(PageEvent::NetworkChanged {
+                network: network.clone(),
+            }),
+        }
+    }
+}
+
+/// Lifetime of a permission granted to an origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GrantDuration {
+    Day,
+    #[default]
+    Month,
+    Unlimited,
+}
+
+impl GrantDuration {
+    pub fn list() -> [GrantDuration; 3] {
+        [
+            GrantDuration::Day,
+            GrantDuration::Month,
+            GrantDuration::Unlimited,
+        ]
+    }
+
+    /// Expiration timestamp (in milliseconds) for a grant issued at `now`.
+    pub fn expires(&self, now: u64) -> Option<u64> {
+        const DAY: u64 = 24 * 60 * 60 * 1000;
+        match self {
+            GrantDuration::Day => Some(now + DAY),
+            GrantDuration::Month => Some(now + DAY * 30),
+            GrantDuration::Unlimited => None,
+        }
+    }
+}
+
+impl std::fmt::Display for GrantDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrantDuration::Day => write!(f, "{}", i18n("24 hours")),
+            GrantDuration::Month => write!(f, "{}", i18n("30 days")),
+            GrantDuration::Unlimited => write!(f, "{}", i18n("Until revoked")),
+        }
+    }
+}
+
+/// Permissions granted to a single web origin.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OriginPermission {
+    pub origin: String,
+    pub accounts: Vec<AccountId>,
+    pub kinds: Vec<RequestKind>,
+    pub created: u64,
+    pub expires: Option<u64>,
+}
+
+impl OriginPermission {
+    pub fn new(
+        origin: String,
+        accounts: Vec<AccountId>,
+        kinds: Vec<RequestKind>,
+        duration: GrantDuration,
+    ) -> Self {
+        let created = unixtime_as_millis_f64() as u64;
+        Self {
+            origin,
+            accounts,
+            kinds,
+            created,
+            expires: duration.expires(created),
+        }
+    }
+
+    pub fn is_expired_at(&self, now: u64) -> bool {
+        self.expires.is_some_and(|expires| now >= expires)
+    }
+
+    pub fn allows(&self, kind: RequestKind) -> bool {
+        self.kinds.contains(&kind)
+    }
+
+    pub fn allows_account(&self, account_id: &AccountId) -> bool {
+        self.accounts.contains(account_id)
+    }
+}
+
+/// Prefix of origins assigned to wallet bridge sessions of clients that
+/// do not supply an `Origin` header. Such grants do not outlive the session.
+pub const LOCAL_SCRIPT_ORIGIN_PREFIX: &str = "local-script#";
+
+fn storage() -> Result<Storage> {
+    Ok(Storage::try_new("kaspa-ng.permissions")?)
+}
+
+/// Persistent registry of permissions granted to dApp origins.
+/// Shared between the extension background server, which rejects
+/// requests from origins without a grant, and the user interface.
+#[derive(Default, Clone)]
+pub struct Permissions {
+    inner: Arc<Mutex<Vec<OriginPermission>>>,
+}
+
+impl Permissions {
+    pub fn list(&self) -> Vec<OriginPermission> {
+        self.inner.lock().unwrap().clone()
+    }
+
+    /// Returns a non-expired grant for the given origin.
+    pub fn get(&self, origin: &str) -> Option<OriginPermission> {
+        let now = unixtime_as_millis_f64() as u64;
+        self.inner
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|permission| permission.origin == origin && !permission.is_expired_at(now))
+            .cloned()
+    }
+
+    /// Checks if the origin has been granted the permission to issue the request.
+    pub fn is_allowed(&self, origin: &str, kind: RequestKind) -> bool {
+        self.get(origin)
+            .is_some_and(|permission| permission.allows(kind))
+    }
+
+    /// Inserts or replaces the grant for the permission origin.
+    pub fn grant(&self, permission: OriginPermission) {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            inner.retain(|existing| existing.origin != permission.origin);
+            inner.insert(0, permission);
+        }
+        self.store();
+    }
+
+    pub fn revoke(&self, origin: &str) {
+        self.inner
+            .lock()
+            .unwrap()
+            .retain(|permission| permission.origin != origin);
+        self.store();
+    }
+
+    pub fn revoke_all(&self) {
+        self.inner.lock().unwrap().clear();
+        self.store();
+    }
+
+    /// Removes expired grants.
+    pub fn update_expiration(&self) {
+        let now = unixtime_as_millis_f64() as u64;
+        let updated = {
+            let mut inner = self.inner.lock().unwrap();
+            let len = inner.len();
+            inner.retain(|permission| !permission.is_expired_at(now));
+            inner.len() != len
+        };
+
+        if updated {
+            self.store();
+        }
+    }
+
+    /// Removes grants issued to wallet bridge sessions of a previous run.
+    pub fn revoke_sessions(&self) {
+        let updated = {
+            let mut inner = self.inner.lock().unwrap();
+            let len = inner.len();
+            inner.retain(|permission| !permission.origin.starts_with(LOCAL_SCRIPT_ORIGIN_PREFIX));
+            inner.len() != len
+        };
+
+        if updated {
+            self.store();
+        }
+    }
+
+    pub fn store(&self) {
+        let permissions = self.list();
+        spawn(async move {
+            let storage = storage()?;
+            storage.ensure_dir().await?;
+            workflow_store::fs::write_json(storage.filename(), &permissions).await?;
+            Ok(())
+        });
+    }
+
+    pub async fn load(&self) -> Result<()> {
+        use workflow_store::fs::read_json;
+
+        let storage = storage()?;
+        if storage.exists().await.unwrap_or(false) {
+            match read_json::<Vec<OriginPermission>>(storage.filename()).await {
+                Ok(permissions) => {
+                    *self.inner.lock().unwrap() = permissions;
+                }
+                Err(error) => {
+                    log_warn!("Permissions::load() error: {}", error);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,408 @@
+use crate::imports::*;
+use kaspa_wallet_core::storage::local::storage::Storage;
+use kaspa_wallet_core::storage::{Binding, transaction::TransactionData};
+use xxhash_rust::xxh3::xxh3_64;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum InvoiceStatus {
+    Pending,
+    PartiallyPaid,
+    Paid,
+    Expired,
+}
+
+impl InvoiceStatus {
+    pub fn is_open(&self) -> bool {
+        matches!(self, InvoiceStatus::Pending | InvoiceStatus::PartiallyPaid)
+    }
+}
+
+impl std::fmt::Display for InvoiceStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvoiceStatus::Pending => write!(f, "{}", i18n("Pending")),
+            InvoiceStatus::PartiallyPaid => write!(f, "{}", i18n("Partially Paid")),
+            InvoiceStatus::Paid => write!(f, "{}", i18n("Paid")),
+            InvoiceStatus::Expired => write!(f, "{}", i18n("Expired")),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvoiceExpiry {
+    OneHour,
+    #[default]
+    OneDay,
+    OneWeek,
+    Never,
+}
+
+impl InvoiceExpiry {
+    pub fn list() -> [InvoiceExpiry; 4] {
+        [
+            InvoiceExpiry::OneHour,
+            InvoiceExpiry::OneDay,
+            InvoiceExpiry::OneWeek,
+            InvoiceExpiry::Never,
+        ]
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        match self {
+            InvoiceExpiry::OneHour => Some(Duration::from_secs(60 * 60)),
+            InvoiceExpiry::OneDay => Some(Duration::from_secs(60 * 60 * 24)),
+            InvoiceExpiry::OneWeek => Some(Duration::from_secs(60 * 60 * 24 * 7)),
+            InvoiceExpiry::Never => None,
+        }
+    }
+}
+
+impl std::fmt::Display for InvoiceExpiry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvoiceExpiry::OneHour => write!(f, "{}", i18n("1 hour")),
+            InvoiceExpiry::OneDay => write!(f, "{}", i18n("24 hours")),
+            InvoiceExpiry::OneWeek => write!(f, "{}", i18n("7 days")),
+            InvoiceExpiry::Never => write!(f, "{}", i18n("Never")),
+        }
+    }
+}
+
+/// Percent-encodes a URI query parameter value.
+fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// Builds a payment request URI (`<address>?amount=<kas>&label=<label>`).
+pub fn payment_request_uri(
+    address: &str,
+    amount_sompi: Option<u64>,
+    label: Option<&str>,
+) -> String {
+    let mut params = vec![];
+    if let Some(amount_sompi) = amount_sompi {
+        params.push(format!("amount={}", sompi_to_kaspa(amount_sompi)));
+    }
+    if let Some(label) = label {
+        params.push(format!("label={}", encode_query_value(label)));
+    }
+
+    if params.is_empty() {
+        address.to_string()
+    } else {
+        format!("{address}?{}", params.join("&"))
+    }
+}
+
+/// A payment request bound to a dedicated receive address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Invoice {
+    pub id: String,
+    pub account_id: AccountId,
+    pub network: Network,
+    pub address: String,
+    /// Expected amount; `None` accepts any amount.
+    pub amount_sompi: Option<u64>,
+    pub received_sompi: u64,
+    pub label: Option<String>,
+    pub created: u64,
+    pub expires: Option<u64>,
+    pub status: InvoiceStatus,
+    pub transactions: Vec<TransactionId>,
+}
+
+impl Invoice {
+    pub fn new(
+        account_id: AccountId,
+        network: Network,
+        address: &Address,
+        amount_sompi: Option<u64>,
+        label: Option<String>,
+        expiry: InvoiceExpiry,
+    ) -> Self {
+        let address = address.to_string();
+        let created = unixtime_as_millis_f64() as u64;
+        let id = format!("{:016x}", xxh3_64(format!("{address}{created}").as_bytes()));
+        let expires = expiry
+            .duration()
+            .map(|duration| created + duration.as_millis() as u64);
+
+        Self {
+            id,
+            account_id,
+            network,
+            address,
+            amount_sompi,
+            received_sompi: 0,
+            label,
+            created,
+            expires,
+            status: InvoiceStatus::Pending,
+            transactions: vec![],
+        }
+    }
+
+    pub fn request_uri(&self) -> String {
+        payment_request_uri(&self.address, self.amount_sompi, self.label.as_deref())
+    }
+
+    pub fn name(&self) -> String {
+        self.label.clone().unwrap_or_else(|| self.id.clone())
+    }
+
+    pub fn is_expired_at(&self, now: u64) -> bool {
+        self.expires.map(|expires| now >= expires).unwrap_or(false)
+    }
+
+    /// Sum of the incoming transaction outputs paying to this invoice address.
+    fn received_amount(&self, record: &TransactionRecord) -> u64 {
+        match record.transaction_data() {
+            TransactionData::Incoming { utxo_entries, .. }
+            | TransactionData::External { utxo_entries, .. } => utxo_entries
+                .iter()
+                .filter(|utxo| {
+                    utxo.address
+                        .as_ref()
+                        .map(|address| address.to_string() == self.address)
+                        .unwrap_or(false)
+                })
+                .map(|utxo| utxo.amount)
+                .sum::<u64>(),
+            _ => 0,
+        }
+    }
+
+    /// Credits the outputs of an incoming transaction that pay to this
+    /// invoice address. Returns `true` if the received amount has changed.
+    fn apply(&mut self, record: &TransactionRecord) -> bool {
+        self.credit(*record.id(), self.received_amount(record))
+    }
+
+    /// Credits a payment received by the given transaction.
+    /// Returns `true` if the received amount has changed.
+    pub fn credit(&mut self, transaction_id: TransactionId, amount: u64) -> bool {
+        if amount == 0 || self.transactions.contains(&transaction_id) {
+            return false;
+        }
+
+        self.transactions.push(transaction_id);
+        self.received_sompi += amount;
+        self.update_status();
+        true
+    }
+
+    /// Reverts a payment credited by the given transaction.
+    /// Returns `true` if the received amount has changed.
+    pub fn revert(&mut self, transaction_id: &TransactionId, amount: u64) -> bool {
+        let Some(index) = self.transactions.iter().position(|id| id == transaction_id) else {
+            return false;
+        };
+
+        self.transactions.remove(index);
+        self.received_sompi = self.received_sompi.saturating_sub(amount);
+        self.update_status();
+        true
+    }
+
+    fn update_status(&mut self) {
+        self.status = match self.amount_sompi {
+            _ if self.received_sompi == 0 => InvoiceStatus::Pending,
+            Some(amount_sompi) if self.received_sompi < amount_sompi => {
+                InvoiceStatus::PartiallyPaid
+            }
+            _ => InvoiceStatus::Paid,
+        };
+    }
+}
+
+/// Interval at which open invoices are checked for expiration.
+pub const INVOICE_EXPIRATION_INTERVAL: Duration = Duration::from_secs(60);
+
+fn storage() -> Result<Storage> {
+    Ok(Storage::try_new("kaspa-ng.invoices")?)
+}
+
+/// Persistent invoice registry shared between [`Core`] and the
+/// payment request module.
+#[derive(Default, Clone)]
+pub struct Invoices {
+    inner: Arc<Mutex<Vec<Invoice>>>,
+}
+
+impl Invoices {
+    pub fn list(&self) -> Vec<Invoice> {
+        self.inner.lock().unwrap().clone()
+    }
+
+    pub fn account_invoices(&self, account_id: &AccountId) -> Vec<Invoice> {
+        self.inner
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|invoice| &invoice.account_id == account_id)
+            .cloned()
+            .collect()
+    }
+
+    pub fn get(&self, id: &str) -> Option<Invoice> {
+        self.inner
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|invoice| invoice.id == id)
+            .cloned()
+    }
+
+    pub fn insert(&self, invoice: Invoice) {
+        self.inner.lock().unwrap().insert(0, invoice);
+        self.store();
+    }
+
+    pub fn remove(&self, id: &str) {
+        self.inner
+            .lock()
+            .unwrap()
+            .retain(|invoice| invoice.id != id);
+        self.store();
+    }
+
+    /// Matches an incoming wallet transaction against open invoices.
+    pub fn update_with_transaction(&self, record: &TransactionRecord) {
+        let Binding::Account(account_id) = record.binding() else {
+            return;
+        };
+
+        let updated = self
+            .inner
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter(|invoice| &invoice.account_id == account_id)
+            .filter(|invoice| invoice.status != InvoiceStatus::Paid)
+            .filter_map(|invoice| invoice.apply(record).then(|| invoice.clone()))
+            .collect::<Vec<_>>();
+
+        if !updated.is_empty() {
+            updated.iter().for_each(notify);
+            self.store();
+        }
+    }
+
+    /// Reverts invoice payments made by a transaction that has been
+    /// removed from the DAG by a reorg.
+    pub fn revert_transaction(&self, record: &TransactionRecord) {
+        let Binding::Account(account_id) = record.binding() else {
+            return;
+        };
+
+        let updated = self
+            .inner
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter(|invoice| &invoice.account_id == account_id)
+            .filter_map(|invoice| {
+                let amount = invoice.received_amount(record);
+                invoice.revert(record.id(), amount).then(|| invoice.clone())
+            })
+            .collect::<Vec<_>>();
+
+        if !updated.is_empty() {
+            updated.iter().for_each(notify_reverted);
+            self.store();
+        }
+    }
+
+    /// Marks open invoices past their expiry time as expired.
+    pub fn update_expiration(&self) {
+        let now = unixtime_as_millis_f64() as u64;
+
+        let updated = self
+            .inner
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter(|invoice| invoice.status.is_open() && invoice.is_expired_at(now))
+            .map(|invoice| {
+                invoice.status = InvoiceStatus::Expired;
+                invoice.clone()
+            })
+            .collect::<Vec<_>>();
+
+        if !updated.is_empty() {
+            updated.iter().for_each(notify);
+            self.store();
+        }
+    }
+
+    pub fn store(&self) {
+        let invoices = self.list();
+        spawn(async move {
+            let storage = storage()?;
+            storage.ensure_dir().await?;
+            workflow_store::fs::write_json(storage.filename(), &invoices).await?;
+            Ok(())
+        });
+    }
+
+    pub async fn load(&self) -> Result<()> {
+        use workflow_store::fs::read_json;
+
+        let storage = storage()?;
+        if storage.exists().await.unwrap_or(false) {
+            match read_json::<Vec<Invoice>>(storage.filename()).await {
+                Ok(invoices) => {
+                    *self.inner.lock().unwrap() = invoices;
+                }
+                Err(error) => {
+                    log_warn!("Invoices::load() error: {}", error);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn notify(invoice: &Invoice) {
+    let name = invoice.name();
+    let received = sompi_to_kaspa_string(invoice.received_sompi);
+
+    let user_notification = match invoice.status {
+        InvoiceStatus::Paid => UserNotification::success(i18n_args(
+            "Invoice {invoice} has been paid ({amount} KAS received)",
+            &[("invoice", name), ("amount", received)],
+        )),
+        InvoiceStatus::PartiallyPaid => UserNotification::info(i18n_args(
+            "Invoice {invoice} has been partially paid ({amount} KAS received)",
+            &[("invoice", name), ("amount", received)],
+        )),
+        InvoiceStatus::Expired => UserNotification::warning(i18n_args(
+            "Invoice {invoice} has expired",
+            &[("invoice", name)],
+        )),
+        InvoiceStatus::Pending => return,
+    };
+
+    runtime().notify(user_notification);
+}
+
+fn notify_reverted(invoice: &Invoice) {
+    runtime().notify(UserNotification::warning(i18n_args(
+        "Payment of invoice {invoice} has been reverted by a DAG reorg ({amount} KAS received)",
+        &[
+            ("invoice", invoice.name()),
+            ("amount", sompi_to_kaspa_string(invoice.received_sompi)),
+        ],
+    )));
+}
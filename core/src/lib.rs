@@ -16,6 +16,7 @@ pub mod fonts;
 pub mod frame;
 pub mod imports;
 pub mod interop;
+pub mod invoices;
 pub mod market;
 pub mod menu;
 pub mod mobile;
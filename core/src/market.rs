@@ -1,4 +1,5 @@
 use crate::imports::*;
+use kaspa_wallet_core::storage::local::storage::Storage;
 
 #[derive(Debug)]
 pub struct MarketData {
@@ -24,11 +25,218 @@ impl MarketData {
 
 pub type MarketDataMap = AHashMap<String, MarketData>;
 
-#[derive(Default, Debug)]
-pub struct Ohlc {}
+/// Price candle; the timestamp is in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Candle {
+    pub timestamp: f64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+impl From<[f64; 5]> for Candle {
+    fn from([timestamp, open, high, low, close]: [f64; 5]) -> Self {
+        Self {
+            timestamp,
+            open,
+            high,
+            low,
+            close,
+        }
+    }
+}
+
+/// Historical prices in a single currency.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Ohlc {
+    pub candles: Vec<Candle>,
+    /// Time of the last update in milliseconds
+    pub updated: f64,
+}
+
+impl Ohlc {
+    pub fn new(mut candles: Vec<Candle>) -> Self {
+        candles.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+        Self {
+            candles,
+            updated: unixtime_as_millis_f64(),
+        }
+    }
+
+    pub fn is_stale(&self, now: f64, max_age: Duration) -> bool {
+        now - self.updated > max_age.as_millis() as f64
+    }
+
+    pub fn last_close(&self) -> Option<f64> {
+        self.candles.last().map(|candle| candle.close)
+    }
+}
 
 pub type OhlcMap = AHashMap<String, Ohlc>;
 
+fn ohlc_storage() -> Result<Storage> {
+    Ok(Storage::try_new("kaspa-ng.ohlc")?)
+}
+
+/// Stores fetched price history so that charts are available
+/// before the provider is queried again.
+pub async fn store_ohlc_cache(ohlc: &OhlcMap) -> Result<()> {
+    let ohlc = ohlc.iter().collect::<HashMap<_, _>>();
+    let storage = ohlc_storage()?;
+    storage.ensure_dir().await?;
+    workflow_store::fs::write_json(storage.filename(), &ohlc).await?;
+    Ok(())
+}
+
+pub async fn load_ohlc_cache() -> Result<OhlcMap> {
+    let storage = ohlc_storage()?;
+    if storage.exists().await.unwrap_or(false) {
+        match workflow_store::fs::read_json::<HashMap<String, Ohlc>>(storage.filename()).await {
+            Ok(ohlc) => return Ok(ohlc.into_iter().collect()),
+            Err(error) => {
+                log_warn!("Unable to load the price history cache: {}", error);
+            }
+        }
+    }
+    Ok(OhlcMap::default())
+}
+
+/// Minimum interval between two recorded balance changes; a change
+/// within this interval replaces the previous record.
+const BALANCE_HISTORY_RESOLUTION: u64 = 60 * 1000;
+/// Maximum number of records retained per wallet and network.
+const BALANCE_HISTORY_LIMIT: usize = 8192;
+/// Period without balance updates after which the wallet balance is
+/// recorded, so that bursts of per-account updates produce a single record.
+pub const BALANCE_HISTORY_DEBOUNCE: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct BalanceRecord {
+    pub wallet: String,
+    pub network: Network,
+    pub timestamp: u64,
+    pub balance_sompi: u64,
+}
+
+fn balance_history_storage() -> Result<Storage> {
+    Ok(Storage::try_new("kaspa-ng.balance-history")?)
+}
+
+/// Wallet balance changes recorded over time, used to chart
+/// the value of the wallet in the primary currency.
+#[derive(Default, Clone)]
+pub struct BalanceHistory {
+    inner: Arc<Mutex<Vec<BalanceRecord>>>,
+}
+
+impl BalanceHistory {
+    /// Records the balance if it differs from the last recorded balance.
+    /// Returns `true` if the history has been updated.
+    pub fn record(
+        &self,
+        wallet: &str,
+        network: Network,
+        timestamp: u64,
+        balance_sompi: u64,
+    ) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let last = inner
+            .iter()
+            .rposition(|record| record.wallet == wallet && record.network == network);
+
+        if let Some(index) = last {
+            let record = &inner[index];
+            if record.balance_sompi == balance_sompi {
+                return false;
+            }
+            if timestamp.saturating_sub(record.timestamp) < BALANCE_HISTORY_RESOLUTION {
+                inner.remove(index);
+            }
+        }
+
+        inner.push(BalanceRecord {
+            wallet: wallet.to_string(),
+            network,
+            timestamp,
+            balance_sompi,
+        });
+
+        let count = inner
+            .iter()
+            .filter(|record| record.wallet == wallet && record.network == network)
+            .count();
+        if count > BALANCE_HISTORY_LIMIT {
+            if let Some(index) = inner
+                .iter()
+                .position(|record| record.wallet == wallet && record.network == network)
+            {
+                inner.remove(index);
+            }
+        }
+
+        true
+    }
+
+    /// Balance records of a wallet on the given network as `(timestamp, balance)` pairs.
+    pub fn series(&self, wallet: &str, network: Network) -> Vec<(u64, u64)> {
+        self.inner
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|record| record.wallet == wallet && record.network == network)
+            .map(|record| (record.timestamp, record.balance_sompi))
+            .collect()
+    }
+
+    pub fn store(&self) {
+        let history = self.inner.lock().unwrap().clone();
+        spawn(async move {
+            let storage = balance_history_storage()?;
+            storage.ensure_dir().await?;
+            workflow_store::fs::write_json(storage.filename(), &history).await?;
+            Ok(())
+        });
+    }
+
+    pub async fn load(&self) -> Result<()> {
+        use workflow_store::fs::read_json;
+
+        let storage = balance_history_storage()?;
+        if storage.exists().await.unwrap_or(false) {
+            match read_json::<Vec<BalanceRecord>>(storage.filename()).await {
+                Ok(history) => {
+                    *self.inner.lock().unwrap() = history;
+                }
+                Err(error) => {
+                    log_warn!("BalanceHistory::load() error: {}", error);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Value of the wallet at each candle close, given balance records sorted
+/// by time. Candles preceding the first record are skipped.
+pub fn portfolio_series(balances: &[(u64, u64)], ohlc: &Ohlc) -> Vec<[f64; 2]> {
+    let mut series = Vec::with_capacity(ohlc.candles.len());
+    let mut index = 0;
+    let mut balance = None;
+    for candle in ohlc.candles.iter() {
+        while index < balances.len() && balances[index].0 as f64 <= candle.timestamp {
+            balance = Some(balances[index].1);
+            index += 1;
+        }
+        if let Some(balance) = balance {
+            series.push([candle.timestamp, sompi_to_kaspa(balance) * candle.close]);
+        }
+    }
+    series
+}
+
 #[derive(Default, Debug)]
 pub struct Market {
     pub price: Option<Arc<MarketDataMap>>,
@@ -4,6 +4,8 @@ use modules::wallet_create::WalletCreate;
 use crate::imports::*;
 use kaspa_wallet_core::storage::AssocPrvKeyDataIds;
 use kaspa_wallet_core::deterministic::bip32::BIP32_ACCOUNT_KIND;
+use kaspa_wallet_core::wallet::args::AccountCreateArgsBip32Watch;
+use kaspa_bip32::{ExtendedPublicKey, secp256k1};
 
 #[derive(Clone)]
 pub enum CreateAccountKind {
@@ -12,16 +14,31 @@ pub enum CreateAccountKind {
     Legacy,
     MultiSig,
     Keypair,
+    Watch,
     // Keypair,
     // MultiSig,
 }
 
+/// Derivation scheme of the extended public keys supplied
+/// for a watch-only account.
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum WatchDerivation {
+    /// Single account-level key at `m/44'/111111'/n'`
+    #[default]
+    Bip44,
+    /// Cosigner keys of a multi-signature account at `m/45'/111111'/n'`
+    MultiSig,
+}
+
 
 #[derive(Clone)]
 pub enum State {
     Start,
     KeySelection,
     StartImport,
+    ImportWatch,
+    MultiSigSetup,
+    MultiSigCosignerKey,
     ImportMnemonic,
     ImportMnemonicWithEditor,
     PrivateKeyCreate,
@@ -62,6 +79,8 @@ enum Focus {
     WalletSecret,
     PaymentSecret,
     WalletMnemonic,
+    WatchXpubKeys,
+    MultiSigXpubKeys,
 }
 
 #[derive(Clone, Default)]
@@ -80,15 +99,112 @@ struct Context {
     import_legacy: bool,
     import_with_bip39_passphrase: bool,
     import_private_key_mnemonic: String,
+    import_watch: bool,
+    watch_derivation: WatchDerivation,
+    watch_xpub_keys: String,
+    watch_minimum_signatures: String,
+    watch_error: Option<String>,
+    import_multisig: bool,
+    multisig_xpub_keys: String,
+    multisig_minimum_signatures: String,
+    multisig_export_xpub: bool,
+    multisig_cosigner_key: Option<std::result::Result<String, String>>,
+    multisig_error: Option<String>,
     prv_keys: Vec<Arc<PrvKeyDataInfo>>,
 }
 
+fn parse_xpub_keys(text : &str) -> Vec<String> {
+    text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|key| !key.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn validate_xpub_keys(xpub_keys : &[String]) -> std::result::Result<(), String> {
+    for xpub_key in xpub_keys.iter() {
+        if let Err(err) = ExtendedPublicKey::<secp256k1::PublicKey>::from_str(xpub_key) {
+            return Err(i18n_args("Invalid extended public key {key}: {err}", &[("key", format_partial_string(xpub_key, Some(12))), ("err", err.to_string())]));
+        }
+    }
+    Ok(())
+}
+
+fn validate_minimum_signatures(text : &str, cosigners : usize) -> std::result::Result<u16, String> {
+    let minimum_signatures = text.trim().parse::<u16>()
+        .map_err(|_| i18n("Please enter the number of required signatures").to_string())?;
+    if minimum_signatures == 0 || minimum_signatures as usize > cosigners {
+        return Err(i18n_args("The number of required signatures must be between 1 and {count}", &[("count", cosigners.to_string())]));
+    }
+    Ok(minimum_signatures)
+}
+
+impl Context {
+    fn watch_xpub_keys(&self) -> Vec<String> {
+        parse_xpub_keys(&self.watch_xpub_keys)
+    }
+
+    fn validate_watch_keys(&self) -> std::result::Result<(), String> {
+        let xpub_keys = self.watch_xpub_keys();
+        if xpub_keys.is_empty() {
+            return Err(i18n("Please enter an extended public key").to_string());
+        }
+
+        validate_xpub_keys(&xpub_keys)?;
+
+        match self.watch_derivation {
+            WatchDerivation::Bip44 => {
+                if xpub_keys.len() != 1 {
+                    return Err(i18n("BIP-44 watch-only accounts require a single extended public key").to_string());
+                }
+            }
+            WatchDerivation::MultiSig => {
+                validate_minimum_signatures(&self.watch_minimum_signatures, xpub_keys.len())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn multisig_xpub_keys(&self) -> Vec<String> {
+        parse_xpub_keys(&self.multisig_xpub_keys)
+    }
+
+    fn validate_multisig(&self) -> std::result::Result<(), String> {
+        if self.prv_key_data_info.is_none() {
+            return Err(i18n("Please select your private key").to_string());
+        }
+
+        let xpub_keys = self.multisig_xpub_keys();
+        if xpub_keys.is_empty() {
+            return Err(i18n("Please enter cosigner extended public keys").to_string());
+        }
+
+        validate_xpub_keys(&xpub_keys)?;
+        // the local private key is an additional cosigner
+        validate_minimum_signatures(&self.multisig_minimum_signatures, xpub_keys.len() + 1)?;
+
+        Ok(())
+    }
+
+    /// State following the wallet secret (and BIP39 passphrase) entry.
+    fn secret_destination(&self) -> State {
+        if self.multisig_export_xpub {
+            State::MultiSigCosignerKey
+        } else {
+            State::AddAccount
+        }
+    }
+}
+
 impl Zeroize for Context {
     fn zeroize(&mut self) {
         self.account_name.zeroize();
         self.wallet_secret.zeroize();
         self.payment_secret.zeroize();
         self.import_private_key_mnemonic.zeroize();
+        self.watch_xpub_keys.zeroize();
+        self.multisig_xpub_keys.zeroize();
     }
 }
 
@@ -180,11 +296,29 @@ impl ModuleT for AccountCreate {
                         }
                         ui.label(i18n("Create an account by importing a private key."));
 
+                        ui.add(ui.create_separator(Some(32.0), 0.5, Some(true)));
+
+                        if ui.large_button(i18n("Watch-Only Account")).clicked(){
+                            this.state = State::ImportWatch;
+                            this.focus.next(Focus::WatchXpubKeys);
+                        }
+                        ui.label(i18n("Monitor balances and history using an extended public key (xpub/kpub)."));
+
+                        ui.add(ui.create_separator(Some(32.0), 0.5, Some(true)));
+
+                        if ui.large_button_enabled(!no_keys, i18n("Multi-Signature Account")).clicked(){
+                            this.state = State::MultiSigSetup;
+                            this.focus.next(Focus::MultiSigXpubKeys);
+                        }
+                        ui.label(i18n("Create an m-of-n account shared with other cosigners."));
+
                     })
                     .render(ui);
             }
             State::KeySelection => {
                 self.context.import_mnemonic = false;
+                self.context.import_watch = false;
+                self.context.import_multisig = false;
                 Panel::new(self)
                     .with_caption("Create Account")
                     .with_back_enabled(core.has_stack(), |this| {
@@ -231,8 +365,233 @@ impl ModuleT for AccountCreate {
                     })
                     .render(ui);
             }
+            State::ImportWatch => {
+                self.context.import_watch = true;
+                self.context.import_multisig = false;
+                self.context.import_mnemonic = false;
+                self.context.prv_key_data_info = None;
+
+                Panel::new(self)
+                    .with_caption(i18n("Watch-Only Account"))
+                    .with_back(|this| {
+                        this.context.import_watch = false;
+                        this.context.watch_error = None;
+                        this.state = State::Start;
+                    })
+                    .with_close_enabled(false, |_|{
+                    })
+                    .with_header(|_ctx,ui| {
+                        ui.label(i18n("Watch-only accounts can track balances and transaction history,"));
+                        ui.label(i18n("but can not sign or send transactions."));
+                        ui.label(" ");
+                    })
+                    .with_body(|this,ui| {
+
+                        ui.label(i18n("Key derivation"));
+                        ui.add_space(4.);
+                        ui.horizontal_wrapped(|ui| {
+                            ui.selectable_value(&mut this.context.watch_derivation, WatchDerivation::Bip44, i18n("BIP-44 (m/44'/111111'/n')"));
+                            ui.selectable_value(&mut this.context.watch_derivation, WatchDerivation::MultiSig, i18n("Multi-Signature (m/45'/111111'/n')"));
+                        });
+                        ui.add_space(8.);
+
+                        let caption = match this.context.watch_derivation {
+                            WatchDerivation::Bip44 => i18n("Enter account extended public key (xpub/kpub)"),
+                            WatchDerivation::MultiSig => i18n("Enter cosigner extended public keys, one per line"),
+                        };
+
+                        TextEditor::new(
+                            &mut this.context.watch_xpub_keys,
+                            &mut this.focus,
+                            Focus::WatchXpubKeys,
+                            |ui, text| {
+                                ui.label(RichText::new(caption).size(12.).raised());
+                                ui.add_sized(theme_style().panel_editor_size * vec2(1.,2.), TextEdit::multiline(text)
+                                    .font(FontId::monospace(12.)))
+                            },
+                        )
+                        .build(ui);
+
+                        if this.context.watch_derivation == WatchDerivation::MultiSig {
+                            ui.add_space(8.);
+                            ui.label(RichText::new(i18n("Required signatures")).size(12.).raised());
+                            ui.add_sized(theme_style().panel_editor_size, TextEdit::singleline(&mut this.context.watch_minimum_signatures)
+                                .vertical_align(Align::Center));
+                        }
+
+                        if let Some(error) = this.context.watch_error.as_ref() {
+                            ui.label(" ");
+                            ui.label(RichText::new(error).color(theme_color().error_color));
+                        }
+                    })
+                    .with_footer(|this,ui| {
+                        if ui.large_button_enabled(this.context.watch_xpub_keys.is_not_empty(), i18n("Continue")).clicked() {
+                            match this.context.validate_watch_keys() {
+                                Ok(()) => {
+                                    this.context.watch_error = None;
+                                    this.context.account_kind = Some(CreateAccountKind::Watch);
+                                    this.state = State::AccountName;
+                                    this.focus.next(Focus::AccountName);
+                                }
+                                Err(err) => {
+                                    this.context.watch_error = Some(err);
+                                }
+                            }
+                        }
+                    })
+                    .render(ui);
+            }
+
+            State::MultiSigSetup => {
+                self.context.import_multisig = true;
+                self.context.import_watch = false;
+                self.context.import_mnemonic = false;
+                self.context.multisig_export_xpub = false;
+
+                Panel::new(self)
+                    .with_caption(i18n("Multi-Signature Account"))
+                    .with_back(|this| {
+                        this.context.import_multisig = false;
+                        this.context.multisig_error = None;
+                        this.context.prv_key_data_info = None;
+                        this.state = State::Start;
+                    })
+                    .with_close_enabled(false, |_|{
+                    })
+                    .with_header(|_ctx,ui| {
+                        ui.label(i18n("Each cosigner must create this account in their own wallet"));
+                        ui.label(i18n("using the same set of cosigner keys and required signatures."));
+                        ui.label(" ");
+                    })
+                    .with_body(|this,ui| {
+
+                        ui.label(i18n("Your private key"));
+                        ui.add_space(4.);
+                        for prv_key_data_info in this.context.prv_keys.clone() {
+                            let selected = this.context.prv_key_data_info.as_ref().map(|info| info.id() == prv_key_data_info.id()).unwrap_or(false);
+                            if ui.large_selected_button(selected, prv_key_data_info.name_or_id()).clicked() {
+                                this.context.prv_key_data_info = Some(prv_key_data_info.clone());
+                            }
+                        }
+                        ui.add_space(8.);
+
+                        TextEditor::new(
+                            &mut this.context.multisig_xpub_keys,
+                            &mut this.focus,
+                            Focus::MultiSigXpubKeys,
+                            |ui, text| {
+                                ui.label(RichText::new(i18n("Enter extended public keys of other cosigners, one per line")).size(12.).raised());
+                                ui.add_sized(theme_style().panel_editor_size * vec2(1.,2.), TextEdit::multiline(text)
+                                    .font(FontId::monospace(12.)))
+                            },
+                        )
+                        .build(ui);
+
+                        ui.add_space(8.);
+                        let cosigners = this.context.multisig_xpub_keys().len() + 1;
+                        ui.label(RichText::new(i18n_args("Required signatures (out of {count} cosigners)", &[("count", cosigners.to_string())])).size(12.).raised());
+                        ui.add_sized(theme_style().panel_editor_size, TextEdit::singleline(&mut this.context.multisig_minimum_signatures)
+                            .vertical_align(Align::Center));
+
+                        if let Some(error) = this.context.multisig_error.as_ref() {
+                            ui.label(" ");
+                            ui.label(RichText::new(error).color(theme_color().error_color));
+                        }
+                    })
+                    .with_footer(|this,ui| {
+                        let has_key = this.context.prv_key_data_info.is_some();
+                        if ui.large_button_enabled(has_key, i18n("Show My Cosigner Key")).clicked() {
+                            this.context.multisig_export_xpub = true;
+                            this.context.multisig_error = None;
+                            this.state = State::WalletSecret;
+                            this.focus.next(Focus::WalletSecret);
+                        }
+
+                        if ui.large_button_enabled(has_key && this.context.multisig_xpub_keys.is_not_empty(), i18n("Continue")).clicked() {
+                            match this.context.validate_multisig() {
+                                Ok(()) => {
+                                    this.context.multisig_error = None;
+                                    this.context.account_kind = Some(CreateAccountKind::MultiSig);
+                                    this.state = State::AccountName;
+                                    this.focus.next(Focus::AccountName);
+                                }
+                                Err(err) => {
+                                    this.context.multisig_error = Some(err);
+                                }
+                            }
+                        }
+                    })
+                    .render(ui);
+            }
+
+            State::MultiSigCosignerKey => {
+
+                let cosigner_key_result = Payload::<Result<String>>::new("multisig_cosigner_key_result");
+                if self.context.multisig_cosigner_key.is_none() && !cosigner_key_result.is_pending() && self.context.wallet_secret.is_not_empty() {
+                    let wallet_secret = Secret::from(self.context.wallet_secret.as_str());
+                    let payment_secret = self.context.prv_key_data_info.as_ref().and_then(|info| {
+                        info.requires_bip39_passphrase().then_some(Secret::from(self.context.payment_secret.as_str()))
+                    });
+                    let prv_key_data_id = *self.context.prv_key_data_info.as_ref().unwrap().id();
+                    self.context.wallet_secret.zeroize();
+                    self.context.payment_secret.zeroize();
+
+                    spawn_with_result(&cosigner_key_result, async move {
+                        let wallet = runtime().wallet().downcast_arc::<CoreWallet>().map_err(|_| Error::WalletIsNotLocal)?;
+                        let xpub_key = next_multisig_cosigner_key(&wallet, &wallet_secret, &prv_key_data_id, payment_secret.as_ref()).await?;
+                        runtime().request_repaint();
+                        Ok(xpub_key)
+                    });
+                }
+
+                if let Some(result) = cosigner_key_result.take() {
+                    self.context.multisig_cosigner_key = Some(result.map_err(|err| err.to_string()));
+                }
+
+                Panel::new(self)
+                    .with_caption(i18n("Cosigner Key"))
+                    .with_back(|this| {
+                        this.context.multisig_cosigner_key = None;
+                        this.state = State::MultiSigSetup;
+                    })
+                    .with_close_enabled(false, |_|{
+                    })
+                    .with_header(|_ctx,ui| {
+                        ui.label(i18n("Share this extended public key with other cosigners."));
+                        ui.label(" ");
+                    })
+                    .with_body(|this,ui| {
+                        match this.context.multisig_cosigner_key.as_ref() {
+                            Some(Ok(xpub_key)) => {
+                                ui.label(RichText::new(xpub_key).font(FontId::monospace(12.)));
+                                ui.label(" ");
+                                if ui.medium_button(format!("{} {}", egui_phosphor::light::CLIPBOARD_TEXT, i18n("Copy to clipboard"))).clicked() {
+                                    ui.ctx().copy_text(xpub_key.clone());
+                                    runtime().notify_clipboard(i18n("Cosigner key copied to clipboard"));
+                                }
+                            }
+                            Some(Err(err)) => {
+                                ui.label(RichText::new(err).color(theme_color().error_color));
+                            }
+                            None => {
+                                ui.add_space(32.);
+                                ui.add(egui::Spinner::new().size(64.));
+                            }
+                        }
+                    })
+                    .with_footer(|this,ui| {
+                        if ui.large_button(i18n("Continue")).clicked() {
+                            this.context.multisig_cosigner_key = None;
+                            this.state = State::MultiSigSetup;
+                        }
+                    })
+                    .render(ui);
+            }
+
             State::StartImport => {
                 self.context.import_mnemonic = true;
+                self.context.import_watch = false;
+                self.context.import_multisig = false;
                 self.context.import_private_key_mnemonic.zeroize();
                 self.context.wallet_secret.zeroize();
                 self.context.payment_secret.zeroize();
@@ -283,7 +642,11 @@ impl ModuleT for AccountCreate {
                 Panel::new(self)
                     .with_caption(i18n("Account Name"))
                     .with_back(|this| {
-                        if this.context.import_mnemonic{
+                        if this.context.import_watch {
+                            this.state = State::ImportWatch;
+                        } else if this.context.import_multisig {
+                            this.state = State::MultiSigSetup;
+                        } else if this.context.import_mnemonic{
                             this.state = State::StartImport;
                         }else{
                             this.state = State::Start;
@@ -331,7 +694,11 @@ impl ModuleT for AccountCreate {
                 Panel::new(self)
                     .with_caption(i18n("Wallet Secret"))
                     .with_back(|this| {
-                        this.state = State::AccountName;
+                        if this.context.multisig_export_xpub {
+                            this.state = State::MultiSigSetup;
+                        } else {
+                            this.state = State::AccountName;
+                        }
                     })
                     .with_close_enabled(false, |_|{
                     })
@@ -369,7 +736,7 @@ impl ModuleT for AccountCreate {
                         self.state = State::PaymentSecret;
                         self.focus.next(Focus::PaymentSecret);
                     } else {
-                        self.state = State::AddAccount;
+                        self.state = self.context.secret_destination();
                     }
                 }
             }
@@ -398,7 +765,7 @@ impl ModuleT for AccountCreate {
                             },
                         ).submit(|text,focus| {
                             if !text.is_empty() {
-                                this.state = State::AddAccount;
+                                this.state = this.context.secret_destination();
                                 focus.clear()
                             }
                         })
@@ -407,7 +774,7 @@ impl ModuleT for AccountCreate {
                     .with_footer(|this,ui| {
                         let enabled = !this.context.payment_secret.is_empty();
                         if ui.large_button_enabled(enabled,i18n("Continue")).clicked() {
-                            this.state = State::AddAccount;
+                            this.state = this.context.secret_destination();
                         }
                     })
                     .render(ui);
@@ -420,7 +787,7 @@ impl ModuleT for AccountCreate {
             }
 
             State::AddAccount => {
-                let caption = if self.context.import_mnemonic {i18n("Importing Account")}else{i18n("Creating Account")};
+                let caption = if self.context.import_mnemonic || self.context.import_watch {i18n("Importing Account")}else{i18n("Creating Account")};
 
                 Panel::new(self)
                     .with_caption(caption)
@@ -445,7 +812,41 @@ impl ModuleT for AccountCreate {
                         sleep(Duration::from_secs(2)).await;
                         let account_name = args.account_name.trim();
                         let account_name = account_name.is_not_empty().then_some(account_name.to_string());
-                        let wallet_secret = Secret::from(args.wallet_secret);
+                        let wallet_secret = Secret::from(args.wallet_secret.as_str());
+
+                        if args.import_watch {
+                            let xpub_keys = args.watch_xpub_keys();
+                            let account_create_args = match args.watch_derivation {
+                                WatchDerivation::Bip44 => {
+                                    AccountCreateArgs::Bip32Watch {
+                                        account_args: AccountCreateArgsBip32Watch { account_name, xpub_keys },
+                                    }
+                                }
+                                WatchDerivation::MultiSig => {
+                                    let minimum_signatures = args.watch_minimum_signatures.trim().parse::<u16>().unwrap_or(1);
+                                    AccountCreateArgs::new_multisig(vec![], xpub_keys, account_name, minimum_signatures)
+                                }
+                            };
+                            let account_descriptor = wallet.accounts_create(wallet_secret, account_create_args).await?;
+                            return Ok(account_descriptor);
+                        }
+
+                        if args.import_multisig {
+                            let prv_key_data_info = args.prv_key_data_info.as_ref().ok_or(Error::AccountCreationError)?;
+                            let payment_secret = prv_key_data_info.requires_bip39_passphrase().then_some(Secret::from(args.payment_secret.as_str()));
+                            let minimum_signatures = args.multisig_minimum_signatures.trim().parse::<u16>().unwrap_or(1);
+                            let wallet = wallet.downcast_arc::<CoreWallet>().map_err(|_| Error::WalletIsNotLocal)?;
+                            let account_descriptor = create_multisig_account(
+                                &wallet,
+                                &wallet_secret,
+                                *prv_key_data_info.id(),
+                                payment_secret.as_ref(),
+                                args.multisig_xpub_keys(),
+                                account_name,
+                                minimum_signatures,
+                            ).await?;
+                            return Ok(account_descriptor);
+                        }
 
                         let payment_secret;
 
@@ -463,7 +864,7 @@ impl ModuleT for AccountCreate {
                             wallet.clone().prv_key_data_create(wallet_secret.clone(), prv_key_data_args).await?
                         }else{
                             payment_secret = args.prv_key_data_info.as_ref().and_then(|key| {
-                                key.requires_bip39_passphrase().then_some(Secret::from(args.payment_secret))
+                                key.requires_bip39_passphrase().then_some(Secret::from(args.payment_secret.as_str()))
                             });
                             *args.prv_key_data_info.as_ref().unwrap().id()
                         };
@@ -508,7 +909,11 @@ impl ModuleT for AccountCreate {
                     ui.label(RichText::new(err.to_string()).color(egui::Color32::from_rgb(255, 120, 120)));
 
                     if ui.large_button(i18n("Restart")).clicked() {
-                        if this.context.import_mnemonic{
+                        if this.context.import_watch {
+                            this.state = State::ImportWatch;
+                        } else if this.context.import_multisig {
+                            this.state = State::MultiSigSetup;
+                        } else if this.context.import_mnemonic{
                             this.state = State::StartImport;
                         }else{
                             this.state = State::Start;
@@ -34,8 +34,7 @@ impl<'context> BalancePane<'context> {
             if core.settings.market_monitor && (core.settings.node.network == Network::Mainnet || core.settings.developer.market_monitor_on_testnet)
                 && let Some(market) = core.market.as_ref()
                     && let Some(price_list) = market.price.as_ref() {
-                        let mut symbols = price_list.keys().collect::<Vec<_>>();
-                        symbols.sort();
+                        let symbols = core.settings.market.sorted_symbols(price_list.keys());
                         ui.vertical_centered(|ui| {
                             let text = symbols.into_iter().filter_map(|symbol| {
                                     price_list.get(symbol).map(|data| {
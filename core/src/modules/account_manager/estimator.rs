@@ -13,7 +13,7 @@ impl<'context> Estimator<'context> {
     pub fn render(&mut self, core : &mut Core, ui: &mut Ui, rc : &RenderContext) -> bool {
 
 
-        use egui_phosphor::light::{CHECK, X};
+        use egui_phosphor::light::{CHECK, EXPORT, X};
 
         let RenderContext { network_type, .. } = rc;
         let network_id = NetworkId::from(core.network());
@@ -108,10 +108,11 @@ impl<'context> Estimator<'context> {
         // };
 
 
-        let usd_rate = if core.settings.market_monitor {
+        let primary_currency = core.settings.market.primary_currency.clone();
+        let fiat_rate = if core.settings.market_monitor {
             core.market.as_ref().and_then(|market| {
                 market.price.as_ref().and_then(|price_list| {
-                    price_list.get("usd").map(|market_data| market_data.price)
+                    price_list.get(primary_currency.as_str()).map(|market_data| (market_data.price, market_data.precision))
                 })
             })
         } else { None };
@@ -147,7 +148,8 @@ impl<'context> Estimator<'context> {
             let seconds = if is_send_amount_zero || error.is_some() { "---".to_string() } else { format_duration_estimate_i18n(seconds) };
             let total_kas = feerate * aggregate_mass as f64 * 1e-8;
             let total_sompi = (feerate * aggregate_mass as f64) as u64;
-            let total_usd = usd_rate.map(|rate| total_kas * rate);
+            let total_fiat = fiat_rate.map(|(rate, precision)| (total_kas * rate, precision));
+            let symbol = primary_currency.to_uppercase();
             fee_selection = fee_selection.add_icon_less(mode, i18n(mode.to_string().as_str()), seconds, move |ui| {
                 // icon
                 let icon = if mode == fee_mode {
@@ -158,9 +160,8 @@ impl<'context> Estimator<'context> {
                 ui.label(icon);
                 
                 ui.label(RichText::new(sompi_to_kaspa_string_with_suffix(total_sompi, &network_type)).strong());
-                if let Some(usd) = total_usd {
-                    let usd = format_currency(usd, 6);
-                    ui.label(RichText::new(format!("~{} USD", usd)).strong());
+                if let Some((amount, precision)) = total_fiat {
+                    ui.label(RichText::new(format!("~{}", format_currency_with_symbol(amount, precision, symbol.as_str()))).strong());
                 }
                 ui.label(format!("{} SOMPI/g", format_with_precision(feerate)));
             });
@@ -214,10 +215,15 @@ impl<'context> Estimator<'context> {
 
         core.apply_mobile_style(ui);
 
+        let watch_only = rc.account.is_watch_only();
+        let can_export = ready_to_send && self.context.transaction_kind == Some(TransactionKind::Send);
+
         if request_send {
-            if ready_to_send {
+            if ready_to_send && !watch_only {
                 self.context.action = Action::Sending;
                 self.context.focus.next(Focus::WalletSecret);
+            } else if can_export && watch_only {
+                self.context.action = Action::Exporting;
             } else if self.context.address_status != AddressStatus::Valid {
                 self.context.focus.next(Focus::Address);
             }
@@ -227,10 +233,13 @@ impl<'context> Estimator<'context> {
             ui.vertical_centered(|ui|{
                 ui.horizontal(|ui| {
                     CenterLayoutBuilder::new()
-                        .add_enabled(ready_to_send, Button::new(format!("{CHECK} {}", i18n("Send"))).min_size(theme_style().medium_button_size()), |this: &mut Estimator<'_>| {
+                        .add_enabled(ready_to_send && !watch_only, Button::new(format!("{CHECK} {}", i18n("Send"))).min_size(theme_style().medium_button_size()), |this: &mut Estimator<'_>| {
                             this.context.action = Action::Sending;
                             this.context.focus.next(Focus::WalletSecret);
                         })
+                        .add_enabled(can_export, Button::new(format!("{EXPORT} {}", i18n("Export Unsigned"))).min_size(theme_style().medium_button_size()), |this: &mut Estimator<'_>| {
+                            this.context.action = Action::Exporting;
+                        })
                         .add(Button::new(format!("{X} {}", i18n("Cancel"))).min_size(theme_style().medium_button_size()), |this| {
                             this.context.reset_send_state();
                         })
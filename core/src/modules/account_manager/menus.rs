@@ -251,7 +251,7 @@ impl ToolsMenu {
     pub fn new() -> Self {
         Self { }
     }
-    pub fn render(&mut self, core: &mut Core, ui : &mut Ui, _account_manager : &mut AccountManager, _rc : &RenderContext, max_height: f32) {
+    pub fn render(&mut self, core: &mut Core, ui : &mut Ui, _account_manager : &mut AccountManager, rc : &RenderContext, max_height: f32) {
 
         PopupPanel::new(PopupPanel::id(ui,"tools_popup"),|ui|{ ui.add(Label::new(format!("{} ⏷", i18n("Tools"))).sense(Sense::click())) }, |ui, _| {
 
@@ -268,6 +268,16 @@ impl ToolsMenu {
                     if ui.large_button(i18n("Address derivation scan")).clicked() {
                         core.select::<modules::Scanner>();
                     }
+                    if ui.large_button(i18n("Partially Signed Transactions")).clicked() {
+                        core.get_mut::<modules::Pskt>().select(&rc.account);
+                        core.select::<modules::Pskt>();
+                    }
+                    if ui.large_button(i18n("Verify Message")).clicked() {
+                        core.select::<modules::VerifyMessage>();
+                    }
+                    if ui.large_button(i18n("Connected Sites")).clicked() {
+                        core.select::<modules::ConnectedSites>();
+                    }
                 });
 
         })
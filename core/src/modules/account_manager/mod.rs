@@ -64,6 +64,7 @@ enum Action {
     None,
     Estimating,
     Sending,
+    Exporting,
     // Reset,
     Processing,
     Error(Arc<Error>),
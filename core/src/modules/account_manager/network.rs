@@ -28,6 +28,16 @@ impl<'context> NetworkState<'context> {
                 ui.add_space(ICON_SPACING);
                 
                 ui.label(i18n("You are currently not connected to the Kaspa node."));
+
+                if core.settings.node.node_kind == KaspadNodeKind::Disable && !rc.account.is_watch_only() {
+                    ui.add_space(16.);
+                    ui.label(i18n("In offline mode you can sign transactions exported from an online kaspa-ng instance."));
+                    ui.add_space(8.);
+                    if ui.large_button(i18n("Sign Offline Transaction")).clicked() {
+                        core.get_mut::<modules::Pskt>().sign(&rc.account);
+                        core.select::<modules::Pskt>();
+                    }
+                }
             } else if !core.state().is_synced() {
                 
                 ui.add_space(ICON_SPACING);
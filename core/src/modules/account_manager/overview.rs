@@ -15,7 +15,7 @@ impl<'manager> Overview<'manager> {
     }
 
     pub fn render(&mut self, core: &mut Core, ui : &mut Ui, rc : &RenderContext) {
-        use egui_phosphor::light::{ARROW_CIRCLE_UP,ARROWS_DOWN_UP,QR_CODE};
+        use egui_phosphor::light::{ARROW_CIRCLE_UP,ARROWS_DOWN_UP,EYE,QR_CODE};
 
         core.apply_mobile_style(ui);
 
@@ -36,8 +36,17 @@ impl<'manager> Overview<'manager> {
                         return;
                     }
 
+                    let watch_only = rc.account.is_watch_only();
+
                     match self.context.action.clone() {
-                        Action::Sending | Action::Estimating | Action::Processing => {
+                        // watch-only accounts can not sign and may only export unsigned send transactions
+                        Action::Sending | Action::Processing if watch_only => {
+                            self.context.reset_send_state();
+                        }
+                        Action::Estimating | Action::Exporting if watch_only && self.context.transaction_kind != Some(TransactionKind::Send) => {
+                            self.context.reset_send_state();
+                        }
+                        Action::Sending | Action::Estimating | Action::Exporting | Action::Processing => {
                             Processor::new(self.context).render(core, ui, rc);
                             // self.render_send_ui(core, ui, rc);
                         }
@@ -66,6 +75,12 @@ impl<'manager> Overview<'manager> {
                             ui.vertical_centered(|ui|{
                             
                                 ui.add_space(8.);
+
+                                if watch_only {
+                                    ui.label(RichText::new(i18n_args("{eyeIcon} Watch-only account: transactions are exported for offline signing", &[("eyeIcon", EYE)])).color(theme_color().warning_color));
+                                    ui.add_space(8.);
+                                }
+
                                 ui.horizontal(|ui| {
 
                                     let mut layout = CenterLayoutBuilder::new();
@@ -77,7 +92,7 @@ impl<'manager> Overview<'manager> {
                                     });
 
                                     if core.account_collection().as_ref().map(|collection|collection.len()).unwrap_or(0) > 1 {
-                                        layout = layout.add(Button::new(i18n_args("{arrowsDownUpIcon} Transfer", &[("arrowsDownUpIcon", ARROWS_DOWN_UP)])).min_size(theme_style().medium_button_size()), |(this,_)| {
+                                        layout = layout.add_enabled(!watch_only, Button::new(i18n_args("{arrowsDownUpIcon} Transfer", &[("arrowsDownUpIcon", ARROWS_DOWN_UP)])).min_size(theme_style().medium_button_size()), |(this,_)| {
                                             this.context.action = Action::Estimating;
                                             this.context.transaction_kind = Some(TransactionKind::Transfer);
                                             this.context.focus.next(Focus::Amount);
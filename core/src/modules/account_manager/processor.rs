@@ -10,6 +10,15 @@ impl<'context> Processor<'context> {
         Self { context }
     }
 
+    /// Returns to the estimation step, reporting an invalid destination address.
+    fn invalid_address(&mut self, err : impl std::fmt::Display) {
+        self.context.address_status = AddressStatus::Invalid(err.to_string());
+        self.context.wallet_secret.zeroize();
+        self.context.payment_secret.zeroize();
+        self.context.action = Action::Estimating;
+        self.context.focus.next(Focus::Address);
+    }
+
     pub fn render(&mut self, core : &mut Core, ui: &mut Ui, rc : &RenderContext) {
 
         let RenderContext { account, network_type, .. } = rc;
@@ -103,7 +112,13 @@ impl<'context> Processor<'context> {
                     match self.context.transaction_kind.unwrap() {
                         TransactionKind::Send => {
 
-                            let address = Address::try_from(self.context.destination_address_string.as_str()).expect("invalid address");
+                            let address = match Address::try_from(self.context.destination_address_string.as_str()) {
+                                Ok(address) => address,
+                                Err(err) => {
+                                    self.invalid_address(err);
+                                    return;
+                                }
+                            };
                             let account_id = account.id();
                             let send_amount_sompi = self.context.send_amount_sompi;
                             let payment_output = PaymentOutput {
@@ -162,6 +177,51 @@ impl<'context> Processor<'context> {
                 }
 
             }
+            Action::Exporting => {
+                ui.add_space(16.);
+                ui.add(egui::Spinner::new().size(92.));
+
+                let export_result = Payload::<Result<String>>::new("unsigned_export_result");
+                if !export_result.is_pending() {
+                    let address = match Address::try_from(self.context.destination_address_string.as_str()) {
+                        Ok(address) => address,
+                        Err(err) => {
+                            self.invalid_address(err);
+                            return;
+                        }
+                    };
+                    let account_id = account.id();
+                    let send_amount_sompi = self.context.send_amount_sompi;
+                    let priority_fee_sompi = self.context.priority_fees_sompi;
+                    let payment_output = PaymentOutput {
+                        address,
+                        amount: send_amount_sompi,
+                        covenant: None,
+                    };
+
+                    spawn_with_result(&export_result, async move {
+                        let fee_rate = calculate_fee_rate(network_type, account_id, send_amount_sompi, priority_fee_sompi).await;
+                        let pskb = modules::pskt::create_unsigned_bundle(account_id, payment_output.into(), Some(fee_rate), Fees::SenderPays(0)).await?;
+                        runtime().request_repaint();
+                        Ok(pskb)
+                    });
+                }
+
+                if let Some(result) = export_result.take() {
+                    self.context.reset_send_state();
+                    match result {
+                        Ok(pskb) => {
+                            self.context.action = Action::None;
+                            core.get_mut::<modules::Pskt>().export(account, pskb);
+                            core.select::<modules::Pskt>();
+                        }
+                        Err(error) => {
+                            log_error!("Unsigned transaction export error: {error}");
+                            self.context.action = Action::Error(Arc::new(error));
+                        }
+                    }
+                }
+            }
             Action::Processing => {
                 ui.add_space(16.);
                 ui.add(egui::Spinner::new().size(92.));
@@ -6,6 +6,12 @@ use egui_plot::{
     // Legend,
     // Corner
 };
+use kaspa_rpc_core::RpcBlock;
+use chrono::DateTime;
+use crate::runtime::services::BlockDagPlayback;
+use crate::runtime::services::blockdag_monitor::DEFAULT_HISTORY_RETENTION_MINUTES;
+
+const REPLAY_SPEEDS: &[f64] = &[0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
 
 #[derive(Clone)]
 pub struct Preset {
@@ -52,6 +58,18 @@ const PRESETS: &[Preset] = &[
     },
 ];
 
+#[derive(Default)]
+struct Selection {
+    /// Hash of the most recently requested block
+    hash : Option<KaspaHash>,
+    block : Option<Arc<RpcBlock>>,
+}
+
+enum InspectorAction {
+    Close,
+    Select(KaspaHash),
+}
+
 impl From<Network> for Preset {
     fn from(network: Network) -> Self {
         match network {
@@ -69,6 +87,9 @@ pub struct BlockDag {
     running : bool,
     plot_bounds : PlotBounds,
     bezier : bool,
+    consensus_colors : bool,
+    show_merge_sets : bool,
+    show_legend : bool,
     parent_levels : usize,
     parent_threshold : usize,
     daa_offset : f64,
@@ -78,6 +99,11 @@ pub struct BlockDag {
     settings: BlockDagGraphSettings,
     background : Arc<AtomicBool>,
     network : Network,
+    selection : Arc<Mutex<Selection>>,
+    history_minutes : u64,
+    replay_speed : f64,
+    /// Timeline position while the scrubber is being dragged
+    scrub : Option<f64>,
 }
 
 impl BlockDag {
@@ -94,6 +120,9 @@ impl BlockDag {
             running : false,
             plot_bounds : PlotBounds::NOTHING,
             bezier : true,
+            consensus_colors : true,
+            show_merge_sets : true,
+            show_legend : true,
             daa_offset : preset.daa_offset,
             daa_range : preset.daa_range,
             block_scale : preset.block_scale,
@@ -103,6 +132,10 @@ impl BlockDag {
             settings,
             background : Arc::new(AtomicBool::new(false)),
             network : Network::Mainnet,
+            selection : Arc::new(Mutex::new(Selection::default())),
+            history_minutes : DEFAULT_HISTORY_RETENTION_MINUTES,
+            replay_speed : 1.0,
+            scrub : None,
         }
     }
 
@@ -126,6 +159,269 @@ impl BlockDag {
         self.running = false;
         self.daa_cursor = 0.0; 
         self.last_daa_score = 0; 
+        *self.selection.lock().unwrap() = Selection::default();
+    }
+
+    fn selected(&self) -> Option<Arc<RpcBlock>> {
+        self.selection.lock().unwrap().block.clone()
+    }
+
+    fn select(&self, block : Arc<RpcBlock>) {
+        // blocks received via notifications may not carry
+        // verbose data (merge set) or transactions
+        let complete = block.verbose_data.is_some() && !block.transactions.is_empty();
+        let hash = block.header.hash;
+        *self.selection.lock().unwrap() = Selection { hash : Some(hash), block : Some(block) };
+        if !complete {
+            self.fetch(hash);
+        }
+    }
+
+    fn select_hash(&self, hash : KaspaHash) {
+        let block = self.runtime.block_dag_monitor_service().chain.lock().unwrap()
+            .values()
+            .flat_map(|bucket| bucket.blocks.iter())
+            .find(|block| block.data.header.hash == hash)
+            .map(|block| block.data.clone());
+
+        if let Some(block) = block {
+            self.select(block);
+        } else {
+            self.selection.lock().unwrap().hash = Some(hash);
+            self.fetch(hash);
+        }
+    }
+
+    fn fetch(&self, hash : KaspaHash) {
+        let Some(rpc_api) = self.runtime.block_dag_monitor_service().rpc_api() else {
+            return;
+        };
+
+        let selection = self.selection.clone();
+        spawn(async move {
+            let block = rpc_api.get_block(hash, true).await?;
+            let mut selection = selection.lock().unwrap();
+            // ignore the response if another block has been selected meanwhile
+            if selection.hash == Some(hash) {
+                selection.block = Some(Arc::new(block));
+                runtime().request_repaint();
+            }
+            Ok(())
+        });
+    }
+
+    fn render_legend(ui : &mut Ui) {
+        let theme_color = theme_color();
+        let items = [
+            (theme_color.block_dag_chain_block_fill_color, i18n("Selected Parent Chain")),
+            (theme_color.block_dag_blue_block_fill_color, i18n("Blue")),
+            (theme_color.block_dag_red_block_fill_color, i18n("Red")),
+            (theme_color.block_dag_block_fill_color, i18n("Not Merged")),
+            (theme_color.block_dag_new_block_fill_color, i18n("Tips")),
+        ];
+
+        for (color, text) in items {
+            let (rect, _) = ui.allocate_exact_size(vec2(10.0, 10.0), Sense::hover());
+            ui.painter().rect_filled(rect, 2.0, color);
+            ui.label(RichText::new(text).small());
+        }
+    }
+
+    fn render_timeline(&mut self, ui : &mut Ui) {
+        let service = self.runtime.block_dag_monitor_service().clone();
+        let Some((start, end)) = service.history_range() else {
+            return;
+        };
+        let playback = service.playback();
+
+        ui.horizontal(|ui| {
+            match playback {
+                BlockDagPlayback::Live => {
+                    if ui.button(egui_phosphor::light::PAUSE).on_hover_text(i18n("Pause")).clicked() {
+                        service.set_playback(BlockDagPlayback::Paused { cursor : end });
+                    }
+                }
+                BlockDagPlayback::Paused { cursor } => {
+                    if ui.button(egui_phosphor::light::PLAY).on_hover_text(i18n("Play")).clicked() {
+                        service.set_playback(BlockDagPlayback::Replay { cursor, speed : self.replay_speed });
+                    }
+                }
+                BlockDagPlayback::Replay { cursor, .. } => {
+                    if ui.button(egui_phosphor::light::PAUSE).on_hover_text(i18n("Pause")).clicked() {
+                        service.set_playback(BlockDagPlayback::Paused { cursor });
+                    }
+                }
+            }
+
+            if ui.button(egui_phosphor::light::SKIP_BACK).on_hover_text(i18n("Replay from the beginning")).clicked() {
+                service.set_playback(BlockDagPlayback::Replay { cursor : start, speed : self.replay_speed });
+            }
+
+            if ui.add_enabled(!playback.is_live(), Button::new(format!("{} {}", egui_phosphor::light::RECORD, i18n("Live")))).clicked() {
+                service.set_playback(BlockDagPlayback::Live);
+            }
+
+            let replay_speed = self.replay_speed;
+            egui::ComboBox::from_id_salt("block_dag_replay_speed")
+                .selected_text(format!("{}x", self.replay_speed))
+                .width(64.0)
+                .show_ui(ui, |ui| {
+                    for speed in REPLAY_SPEEDS {
+                        ui.selectable_value(&mut self.replay_speed, *speed, format!("{speed}x"));
+                    }
+                });
+            if replay_speed != self.replay_speed && let BlockDagPlayback::Replay { cursor, .. } = playback {
+                service.set_playback(BlockDagPlayback::Replay { cursor, speed : self.replay_speed });
+            }
+
+            let mut cursor = self.scrub.or(playback.cursor()).unwrap_or(end);
+            let offset = ((end - cursor) / 1000.0).max(0.0) as u64;
+            ui.label(format!("-{}:{:02}", offset / 60, offset % 60));
+
+            ui.spacing_mut().slider_width = (ui.available_width() - 8.0).max(64.0);
+            let response = ui.add(Slider::new(&mut cursor, start..=end).show_value(false));
+            if response.dragged() {
+                self.scrub = Some(cursor);
+            }
+            if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                self.scrub = None;
+                let playback = match playback {
+                    BlockDagPlayback::Replay { speed, .. } => BlockDagPlayback::Replay { cursor, speed },
+                    _ => BlockDagPlayback::Paused { cursor },
+                };
+                service.set_playback(playback);
+            }
+        });
+    }
+
+    fn render_inspector(&self, ui : &mut Ui, block : &RpcBlock) -> Option<InspectorAction> {
+        let mut action = None;
+        let theme_color = theme_color();
+        let hash = block.header.hash.to_string();
+
+        ui.horizontal(|ui| {
+            ui.heading(i18n("Block"));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.small_button(egui_phosphor::light::X).on_hover_text(i18n("Close")).clicked() {
+                    action = Some(InspectorAction::Close);
+                }
+            });
+        });
+        ui.separator();
+
+        ui.horizontal_wrapped(|ui| {
+            if ui.button(format!("{} {}", egui_phosphor::light::CLIPBOARD_TEXT, i18n("Copy Hash"))).clicked() {
+                ui.ctx().copy_text(hash.clone());
+                runtime().notify_clipboard(i18n("Copied to clipboard"));
+            }
+            if ui.button(format!("{} {}", egui_phosphor::light::ARROW_SQUARE_OUT, i18n("Open in Explorer"))).clicked() {
+                let explorer = match self.network {
+                    Network::Mainnet => MAINNET_EXPLORER,
+                    Network::Testnet10 => TESTNET10_EXPLORER,
+                };
+                ui.ctx().open_url(egui::OpenUrl::new_tab(format!("{explorer}/blocks/{hash}")));
+            }
+        });
+        ui.space();
+
+        let field = |ui : &mut Ui, title : &str, value : String| {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(RichText::new(format!("{title}:")).color(theme_color.default_color));
+                ui.label(RichText::new(value).color(theme_color.strong_color));
+            });
+        };
+
+        // hashes are clickable and select the corresponding block
+        let hash_list = |ui : &mut Ui, hashes : &[KaspaHash], action : &mut Option<InspectorAction>| {
+            for hash in hashes.iter() {
+                if ui.add(Label::new(RichText::new(hash.to_string()).monospace().small()).sense(Sense::click()))
+                    .on_hover_cursor(CursorIcon::PointingHand)
+                    .clicked() {
+                    *action = Some(InspectorAction::Select(*hash));
+                }
+            }
+        };
+
+        ScrollArea::vertical()
+            .id_salt("block_dag_inspector")
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                ui.label(RichText::new(&hash).monospace().color(theme_color.strong_color));
+                ui.space();
+
+                let header = &block.header;
+                field(ui, i18n("DAA Score"), header.daa_score.separated_string());
+                field(ui, i18n("Blue Score"), header.blue_score.separated_string());
+                let timestamp = DateTime::<chrono::Utc>::from_timestamp_millis(header.timestamp as i64)
+                    .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+                    .unwrap_or_else(|| header.timestamp.to_string());
+                field(ui, i18n("Timestamp"), timestamp);
+
+                let transactions = if !block.transactions.is_empty() {
+                    Some(block.transactions.len())
+                } else {
+                    block.verbose_data.as_ref().map(|verbose_data| verbose_data.transaction_ids.len())
+                };
+                if let Some(transactions) = transactions {
+                    field(ui, i18n("Transactions"), transactions.separated_string());
+                }
+
+                if let Some(verbose_data) = block.verbose_data.as_ref() {
+                    field(ui, i18n("Chain Block"), if verbose_data.is_chain_block { i18n("Yes") } else { i18n("No") }.to_string());
+                    ui.label(RichText::new(format!("{}:", i18n("Selected Parent"))).color(theme_color.default_color));
+                    hash_list(ui, &[verbose_data.selected_parent_hash], &mut action);
+                }
+
+                ui.space();
+                CollapsingHeader::new(i18n("Parents by Level"))
+                    .id_salt("block_dag_inspector_parents")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        for (level, parents) in header.parents_by_level.iter().enumerate() {
+                            CollapsingHeader::new(format!("{} {level} ({})", i18n("Level"), parents.len()))
+                                .id_salt(("block_dag_inspector_level", level))
+                                .default_open(level == 0)
+                                .show(ui, |ui| {
+                                    hash_list(ui, parents, &mut action);
+                                });
+                        }
+                    });
+
+                CollapsingHeader::new(i18n("Merge Set"))
+                    .id_salt("block_dag_inspector_merge_set")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        if let Some(verbose_data) = block.verbose_data.as_ref() {
+                            ui.label(format!("{} ({})", i18n("Blues"), verbose_data.merge_set_blues_hashes.len()));
+                            hash_list(ui, &verbose_data.merge_set_blues_hashes, &mut action);
+                            ui.label(format!("{} ({})", i18n("Reds"), verbose_data.merge_set_reds_hashes.len()));
+                            hash_list(ui, &verbose_data.merge_set_reds_hashes, &mut action);
+                        } else {
+                            ui.label(i18n("Loading..."));
+                        }
+                    });
+
+                CollapsingHeader::new(i18n("Coinbase"))
+                    .id_salt("block_dag_inspector_coinbase")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        if let Some(coinbase) = block.transactions.first() {
+                            if let Some(payload) = CoinbasePayload::try_parse(&coinbase.payload) {
+                                field(ui, i18n("Subsidy"), sompi_to_kaspa_string_with_suffix(payload.subsidy, &self.network.into()));
+                                let extra_data = payload.extra_data_text();
+                                if !extra_data.is_empty() {
+                                    field(ui, i18n("Extra Data"), extra_data);
+                                }
+                            }
+                            ui.label(RichText::new(format!("{}:", i18n("Payload"))).color(theme_color.default_color));
+                            ui.label(RichText::new(coinbase.payload.to_hex()).monospace().small());
+                        } else {
+                            ui.label(i18n("Loading..."));
+                        }
+                    });
+            });
+
+        action
     }
 
 }
@@ -144,6 +440,8 @@ impl ModuleT for BlockDag {
             ui.label(RichText::new(i18n("Please wait for the node to sync...")).color(theme_color().warning_color));
         } else {
             ui.label(i18n("Double click on the graph to re-center..."));
+            ui.separator();
+            ui.label(i18n("Click on a block to inspect it..."));
         }
     }
 
@@ -169,6 +467,11 @@ impl ModuleT for BlockDag {
         ui.horizontal(|ui| {
             ui.heading(i18n("Block DAG"));
 
+            if self.show_legend && self.consensus_colors {
+                ui.space();
+                Self::render_legend(ui);
+            }
+
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 PopupPanel::new(PopupPanel::id(ui,"block_dag_settings"),|ui|{ ui.add(Label::new(format!("{} ⏷", i18n("Settings"))).sense(Sense::click())) }, |ui, _| {
 
@@ -214,6 +517,21 @@ impl ModuleT for BlockDag {
                             ui.space();
                         });
 
+                    CollapsingHeader::new(i18n("History"))
+                        .open(Some(true))
+                        .show(ui, |ui| {
+                            ui.space();
+                            let response = ui.add(
+                                Slider::new(&mut self.history_minutes, 1..=30)
+                                    .clamping(SliderClamping::Always)
+                                    .text(i18n("Replay (minutes)"))
+                            );
+                            if response.changed() {
+                                runtime().block_dag_monitor_service().set_history_retention(Duration::from_secs(self.history_minutes * 60));
+                            }
+                            ui.space();
+                        });
+
                     CollapsingHeader::new(i18n("Parents"))
                         .open(Some(true))
                         // .default_open(true)
@@ -252,6 +570,12 @@ impl ModuleT for BlockDag {
                         ui.space();
                         ui.checkbox(&mut self.bezier, i18n("Bezier Curves"));
                         ui.space();
+                        ui.checkbox(&mut self.consensus_colors, i18n("Consensus Colors"));
+                        ui.space();
+                        ui.checkbox(&mut self.show_merge_sets, i18n("Show Merge Sets"));
+                        ui.space();
+                        ui.checkbox(&mut self.show_legend, i18n("Show Legend"));
+                        ui.space();
 
                         if core.settings.node.node_kind.is_local() {
                             let background_flag = self.background.load(Ordering::SeqCst);
@@ -289,12 +613,24 @@ impl ModuleT for BlockDag {
         });
         ui.separator();
 
+        self.render_timeline(ui);
+
         if y_dist != self.settings.y_dist || noise != self.settings.noise || vspc_center != self.settings.center_vspc {
             runtime().block_dag_monitor_service().update_settings(self.settings.clone());
         }
 
         let mut reset_plot = false;
-        let current_daa_score = core.state().current_daa_score().unwrap_or_default();
+        let current_daa_score = if runtime().block_dag_monitor_service().playback().is_live() {
+            core.state().current_daa_score().unwrap_or_default()
+        } else {
+            runtime().block_dag_monitor_service().daa_score()
+        };
+
+        // re-center on jumps in time (rewind or return to live)
+        if self.running && (current_daa_score as f64 - self.daa_cursor).abs() > self.daa_range {
+            self.running = false;
+        }
+
         if self.last_daa_score != current_daa_score {
 
             if !self.running {
@@ -328,7 +664,18 @@ impl ModuleT for BlockDag {
         let step = (1.0 + step).powf(2.0) - 1.0;
         self.daa_cursor += step;
         
-        let graph_width = ui.available_width();
+        let selected = self.selected();
+        let selected_hash = selected.as_ref().map(|block| block.header.hash);
+        let inspector_width = if selected.is_some() {
+            (ui.available_width() * 0.35).clamp(240.0, 380.0)
+        } else {
+            0.0
+        };
+        let graph_width = if inspector_width > 0.0 {
+            ui.available_width() - inspector_width - ui.spacing().item_spacing.x
+        } else {
+            ui.available_width()
+        };
         let graph_height = ui.available_height();
         let default_daa_max = self.daa_cursor + self.daa_offset;
         let default_daa_min = default_daa_max - self.daa_range;
@@ -380,6 +727,7 @@ impl ModuleT for BlockDag {
         let mut graph_settled = true;
         let mut lines_parent = Vec::new();
         let mut lines_vspc = Vec::new();
+        let mut lines_merge = Vec::new();
 
         let daa_range = self.plot_bounds.max()[0] - self.plot_bounds.min()[0];
         let daa_margin = daa_range.min(128.0).max(32.0);
@@ -402,16 +750,33 @@ impl ModuleT for BlockDag {
         //     return;
         // };
 
+        let d = 1.5 * self.block_scale;
+
         let parent_levels = self.parent_levels.max(1);
-        let block_map : AHashMap<KaspaHash,(PlotPoint,bool)> = blocks.clone().into_iter().map(|(block, plot_point,vspc, _)|(block.header.hash,(plot_point,vspc))).collect();
+        let block_map : AHashMap<KaspaHash,(PlotPoint,bool)> = blocks.iter().map(|view|(view.block.header.hash,(view.point,view.vspc))).collect();
         let new_blocks = self.runtime.block_dag_monitor_service().new_blocks().clone();
-        let polygons = blocks.iter().map(|(block, point, current_vspc, block_settled)| {
+        let polygons = blocks.iter().map(|view| {
+            let DagBlockView { block, point, vspc : current_vspc, merge, settled : block_settled } = view;
             if !block_settled {
                 graph_settled = false;
             }
 
             let PlotPoint { x, y } = *point;
 
+            // links from chain blocks to the blocks they merge
+            if self.show_merge_sets && *current_vspc && let Some(verbose_data) = block.verbose_data.as_ref() {
+                let merge_set = verbose_data.merge_set_blues_hashes.iter().chain(verbose_data.merge_set_reds_hashes.iter());
+                for hash in merge_set {
+                    if *hash == verbose_data.selected_parent_hash {
+                        continue;
+                    }
+                    if let Some((PlotPoint { x: merged_x, y: merged_y }, _)) = block_map.get(hash) {
+                        let points = [[x, y], [*merged_x, *merged_y]].into_iter().map(|pt|pt.into()).collect::<Vec<_>>();
+                        lines_merge.push(Line::new("", PlotPoints::Owned(points)).color(theme_color.block_dag_merge_connect_color).style(LineStyle::Dotted { spacing: 4.0 }));
+                    }
+                }
+            }
+
             for (level,parent_level) in block.header.parents_by_level.iter().enumerate() {
                 if level >= parent_levels {
                     break;
@@ -445,7 +810,6 @@ impl ModuleT for BlockDag {
                 }
             }
 
-            let d = 1.5 * self.block_scale;
             let points: PlotPoints<'_> = [
                 [x+d*0.2, y+d],
                 [x-d*0.2, y+d],
@@ -453,16 +817,28 @@ impl ModuleT for BlockDag {
                 [x+d*0.2, y-d],
             ].to_vec().into();
         
-            let fill_color = if new_blocks.contains(&block.header.hash) {
+            let fill_color = if self.consensus_colors && *current_vspc {
+                theme_color.block_dag_chain_block_fill_color
+            } else if self.consensus_colors && *merge == MergeState::Blue {
+                theme_color.block_dag_blue_block_fill_color
+            } else if self.consensus_colors && *merge == MergeState::Red {
+                theme_color.block_dag_red_block_fill_color
+            } else if new_blocks.contains(&block.header.hash) {
                 theme_color.block_dag_new_block_fill_color
             } else {
                 theme_color.block_dag_block_fill_color
             };
 
+            let stroke = if selected_hash == Some(block.header.hash) {
+                Stroke::new(3.0, theme_color.block_dag_selected_block_stroke_color)
+            } else {
+                Stroke::new(1.0, theme_color.block_dag_block_stroke_color)
+            };
+
             Polygon::new("polygon1", points)
                 .name(block.header.hash.to_string())
                 .fill_color(fill_color)
-                .stroke(Stroke::new(1.0, theme_color.block_dag_block_stroke_color))
+                .stroke(stroke)
                 .style(LineStyle::Solid)
 
             
@@ -477,20 +853,56 @@ impl ModuleT for BlockDag {
         //     Line::new("", points).color(theme_color.block_dag_separator_color).style(LineStyle::Dotted { spacing: 0.75 })
         // }).collect::<Vec<_>>();
 
-        let plot_response = plot.show(ui, |plot_ui| {
-            // lines_separators.into_iter().for_each(|line| {
-            //     plot_ui.line(line);
-            // });
-            lines_parent.into_iter().for_each(|line| {
-                plot_ui.line(line);
-            });
-            lines_vspc.into_iter().for_each(|line| {
-                plot_ui.line(line);
+        let (plot_response, action) = ui.horizontal_top(|ui| {
+            let plot_response = plot.show(ui, |plot_ui| {
+                // lines_separators.into_iter().for_each(|line| {
+                //     plot_ui.line(line);
+                // });
+                lines_parent.into_iter().for_each(|line| {
+                    plot_ui.line(line);
+                });
+                lines_merge.into_iter().for_each(|line| {
+                    plot_ui.line(line);
+                });
+                lines_vspc.into_iter().for_each(|line| {
+                    plot_ui.line(line);
+                });
+                polygons.into_iter().for_each(|polygon| {
+                    plot_ui.polygon(polygon);
+                });
             });
-            polygons.into_iter().for_each(|polygon| {
-                plot_ui.polygon(polygon);
+
+            let action = selected.as_ref().and_then(|block| {
+                ui.vertical(|ui| {
+                    ui.set_width(inspector_width);
+                    self.render_inspector(ui, block)
+                }).inner
             });
-        });
+
+            (plot_response, action)
+        }).inner;
+
+        match action {
+            Some(InspectorAction::Close) => {
+                *self.selection.lock().unwrap() = Selection::default();
+            }
+            Some(InspectorAction::Select(hash)) => {
+                self.select_hash(hash);
+            }
+            None => {}
+        }
+
+        if plot_response.response.clicked() && let Some(pointer) = plot_response.response.interact_pointer_pos() {
+            let PlotPoint { x, y } = plot_response.transform.value_from_position(pointer);
+            let distance = |point : &PlotPoint| (point.x - x).powi(2) + (point.y - y).powi(2);
+            let block = blocks.iter()
+                .filter(|view| (view.point.x - x).abs() <= d * 0.2 && (view.point.y - y).abs() <= d)
+                .min_by(|a, b| distance(&a.point).total_cmp(&distance(&b.point)))
+                .map(|view| view.block.clone());
+            if let Some(block) = block {
+                self.select(block);
+            }
+        }
 
         if daa_diff > 0.001 || !graph_settled {
             runtime().request_repaint();
@@ -0,0 +1,112 @@
+use crate::imports::*;
+use crate::interop::OriginPermission;
+use workflow_core::time::unixtime_to_locale_string;
+
+pub struct ConnectedSites {
+    #[allow(dead_code)]
+    runtime: Runtime,
+}
+
+impl ConnectedSites {
+    pub fn new(runtime: Runtime) -> Self {
+        Self {
+            runtime,
+        }
+    }
+
+    fn render_permission(core : &Core, ui : &mut Ui, permission : &OriginPermission) -> bool {
+        let mut revoke = false;
+
+        CollapsingHeader::new(permission.origin.as_str())
+            .id_salt(permission.origin.as_str())
+            .default_open(false)
+            .show(ui, |ui| {
+                let kinds = permission.kinds.iter().map(|kind| kind.to_string()).collect::<Vec<_>>().join(", ");
+                ui.label(i18n_args("Permissions: {kinds}", &[("kinds", kinds)]));
+
+                let accounts = permission.accounts.iter().map(|account_id| {
+                    core.account_collection()
+                        .as_ref()
+                        .and_then(|collection| collection.get(account_id))
+                        .map(|account| account.name_or_id())
+                        .unwrap_or_else(|| account_id.short())
+                }).collect::<Vec<_>>().join(", ");
+                ui.label(i18n_args("Accounts: {accounts}", &[("accounts", accounts)]));
+
+                ui.label(i18n_args("Connected: {time}", &[("time", unixtime_to_locale_string(permission.created))]));
+                match permission.expires {
+                    Some(expires) => {
+                        ui.label(i18n_args("Expires: {time}", &[("time", unixtime_to_locale_string(expires))]));
+                    }
+                    None => {
+                        ui.label(i18n("Expires: never"));
+                    }
+                }
+
+                ui.add_space(4.);
+                if ui.medium_button(i18n("Revoke")).clicked() {
+                    revoke = true;
+                }
+            });
+
+        revoke
+    }
+}
+
+impl ModuleT for ConnectedSites {
+
+    fn style(&self) -> ModuleStyle {
+        ModuleStyle::Mobile
+    }
+
+    fn modal(&self) -> bool {
+        true
+    }
+
+    fn render(
+        &mut self,
+        core: &mut Core,
+        _ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        ui: &mut egui::Ui,
+    ) {
+
+        let back = Rc::new(RefCell::new(false));
+        let permissions = core.permissions.clone();
+        let list = permissions.list();
+
+        Panel::new(self)
+            .with_caption(i18n("Connected Sites"))
+            .with_back(|_this| {
+                *back.borrow_mut() = true;
+            })
+            .with_close_enabled(false, |_|{
+            })
+            .with_header(|_this, ui| {
+                ui.label(i18n("Web sites that have been granted access to your wallet."));
+                ui.label(" ");
+            })
+            .with_body(|_this, ui| {
+                if list.is_empty() {
+                    ui.label(i18n("No sites are connected."));
+                    return;
+                }
+
+                for permission in list.iter() {
+                    if Self::render_permission(core, ui, permission) {
+                        permissions.revoke(permission.origin.as_str());
+                    }
+                }
+            })
+            .with_footer(|_this, ui| {
+                if ui.large_button_enabled(list.is_not_empty(), i18n("Revoke All")).clicked() {
+                    permissions.revoke_all();
+                }
+            })
+            .render(ui);
+
+        if *back.borrow() {
+            core.back();
+        }
+    }
+}
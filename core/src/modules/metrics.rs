@@ -1,7 +1,8 @@
 use crate::imports::*;
-use crate::runtime::services::metrics_monitor::MAX_METRICS_SAMPLES;
 use egui_extras::{StripBuilder, Size};
 use kaspa_metrics_core::{Metric,MetricGroup, MetricsSnapshot};
+use crate::runtime::services::metrics_monitor::{MetricsMark, compare_marks};
+use crate::settings::{MetricsDashboard, MetricsGraph};
 use chrono::DateTime;
 use egui_plot::{
     Legend,
@@ -9,19 +10,23 @@ use egui_plot::{
     LineStyle,
     Plot,
     PlotPoints, uniform_grid_spacer, CoordinatesFormatter, Corner,
+    AxisHints, HPlacement,
 };
 
-const METRICS_SAMPLES_START : isize = -(MAX_METRICS_SAMPLES as isize);
 const MIN_RANGE : isize = 15;
 
 pub struct Metrics {
     #[allow(dead_code)]
     runtime: Runtime,
+    /// Snapshots of all metric values taken for comparison (at most 2)
+    marks: Vec<MetricsMark>,
+    /// Name entered in the dashboard editor
+    dashboard_name: String,
 }
 
 impl Metrics {
     pub fn new(runtime: Runtime) -> Self {
-        Self { runtime }
+        Self { runtime, marks: Vec::new(), dashboard_name: String::new() }
     }
 }
 
@@ -47,16 +52,19 @@ impl ModuleT for Metrics {
         let mut graph_range_from = core.settings.user_interface.metrics.graph_range_from;
         let mut graph_range_to = core.settings.user_interface.metrics.graph_range_to;
 
-        if graph_range_from < METRICS_SAMPLES_START {
-            graph_range_from = METRICS_SAMPLES_START;
+        // the range slider spans the stored history (at least one day)
+        let metrics_samples_start = -(self.runtime.metrics_service().history_span() as isize);
+
+        if graph_range_from < metrics_samples_start {
+            graph_range_from = metrics_samples_start;
         }
         
         if graph_range_to > 0 {
             graph_range_to = 0;
         }
 
-        if graph_range_to < METRICS_SAMPLES_START {
-            graph_range_to = METRICS_SAMPLES_START;
+        if graph_range_to < metrics_samples_start {
+            graph_range_to = metrics_samples_start;
         }
 
         if graph_range_to > 0 {
@@ -135,10 +143,63 @@ impl ModuleT for Metrics {
                 .with_close_button(true)
                 .build(ui);
 
+                PopupPanel::new(PopupPanel::id(ui,"metrics_dashboards"),|ui|{ ui.add(Label::new(format!("{} ⏷", i18n("Dashboards"))).sense(Sense::click())) }, |ui, _| {
+                    if self.render_dashboard_editor(core, ui) {
+                        store_settings = true;
+                    }
+                })
+                .with_min_width(280.)
+                .with_max_height(screen_rect_height * 0.8)
+                .with_caption(i18n("Dashboards"))
+                .with_close_button(true)
+                .build(ui);
+
+                let mut dashboard = core.settings.user_interface.dashboard.clone();
+                egui::ComboBox::from_id_salt("metrics_dashboard_selector")
+                    .selected_text(dashboard.clone().unwrap_or_else(|| i18n("All Metrics").to_string()))
+                    .show_ui(ui, |ui| {
+                        ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                        ui.selectable_value(&mut dashboard, None, i18n("All Metrics"));
+                        for item in core.settings.user_interface.dashboards.iter() {
+                            ui.selectable_value(&mut dashboard, Some(item.name.clone()), item.name.as_str());
+                        }
+                    });
+                if dashboard != core.settings.user_interface.dashboard {
+                    core.settings.user_interface.dashboard = dashboard;
+                    store_settings = true;
+                }
+
+                ui.separator();
+
+                if ui.button(i18n("Export CSV")).on_hover_text(i18n("Export selected metrics over the current range")).clicked() {
+                    let mut metrics = Vec::new();
+                    for graph in Self::graphs(core) {
+                        for metric in std::iter::once(graph.metric).chain(graph.overlay) {
+                            if !metrics.contains(&metric) {
+                                metrics.push(metric);
+                            }
+                        }
+                    }
+                    let csv = self.runtime.metrics_service().export_csv(&metrics, graph_range_from..graph_range_to);
+                    self.export_csv(ui, csv);
+                }
+
+                let mark_label = if self.marks.len() == 1 { i18n("Mark B") } else { i18n("Mark A") };
+                if ui.add_enabled(core.metrics().is_some(), Button::new(mark_label))
+                    .on_hover_text(i18n("Capture all metric values for comparison"))
+                    .clicked()
+                    && let Some(snapshot) = core.metrics().as_ref()
+                {
+                    if self.marks.len() >= 2 {
+                        self.marks.clear();
+                    }
+                    self.marks.push(MetricsMark::new(snapshot));
+                }
+
                 ui.separator();
 
                 ui.add(
-                    Slider::new(&mut graph_range_to, (METRICS_SAMPLES_START+MIN_RANGE)..=0)
+                    Slider::new(&mut graph_range_to, (metrics_samples_start+MIN_RANGE)..=0)
                         .logarithmic(true)
                         .orientation(SliderOrientation::Horizontal)
                         .show_value(false)
@@ -147,7 +208,7 @@ impl ModuleT for Metrics {
                         // })
                 );
                 ui.add(
-                    Slider::new(&mut graph_range_from, METRICS_SAMPLES_START..=-MIN_RANGE)
+                    Slider::new(&mut graph_range_from, metrics_samples_start..=-MIN_RANGE)
                         .logarithmic(true)
                         .orientation(SliderOrientation::Horizontal)
                         .show_value(false)
@@ -199,6 +260,11 @@ impl ModuleT for Metrics {
 
         ui.separator();
 
+        if !self.marks.is_empty() {
+            self.render_comparison(ui);
+            ui.separator();
+        }
+
         if let Some(metrics) = core.metrics().as_ref() {
 
             egui::ScrollArea::vertical()
@@ -215,15 +281,16 @@ impl ModuleT for Metrics {
                     };
 
 
-                        let mut metric_iter = Metric::iter().filter(|metric| !core.settings.user_interface.metrics.disabled.contains(metric));
+                        let graphs = Self::graphs(core);
+                        let mut graph_iter = graphs.iter();
                         let mut draw = true;
                         while draw {
                             ui.horizontal(|ui| {
                                 for _ in 0..columns {
-                                    if let Some(metric) = metric_iter.next() {
+                                    if let Some(graph) = graph_iter.next() {
                                         let range_from = core.settings.user_interface.metrics.graph_range_from;
                                         let range_to = core.settings.user_interface.metrics.graph_range_to;
-                                        self.render_metric(ui, *metric, metrics,range_from..range_to,graph_width,graph_height);
+                                        self.render_metric(ui, graph, metrics,range_from..range_to,graph_width,graph_height);
                                     } else {
                                         draw = false;
                                     }
@@ -255,17 +322,232 @@ impl ModuleT for Metrics {
 
 impl Metrics {
 
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_csv(&self, _ui : &mut Ui, csv : String) {
+        if let Some(path) = rfd::FileDialog::new().add_filter("csv", &["csv"]).set_file_name("metrics.csv").save_file() {
+            if let Err(err) = std::fs::write(path, csv.as_bytes()) {
+                runtime().error(err.to_string());
+            } else {
+                runtime().notify(UserNotification::success(i18n("Metrics exported")).short());
+            }
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn export_csv(&self, ui : &mut Ui, csv : String) {
+        ui.ctx().copy_text(csv);
+        runtime().notify_clipboard(i18n("Metrics copied to clipboard as CSV"));
+    }
+
+    fn render_comparison(&mut self, ui : &mut Ui) {
+        let format_time = |timestamp : f64| {
+            DateTime::<chrono::Utc>::from_timestamp_millis(timestamp as i64)
+                .map(|time| time.with_timezone(&chrono::Local).format("%H:%M:%S").to_string())
+                .unwrap_or_default()
+        };
+
+        let mut clear = false;
+        CollapsingHeader::new(i18n("Comparison"))
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    let first = &self.marks[0];
+                    ui.label(format!("{} {}", i18n("Mark A:"), format_time(first.timestamp)));
+                    if let Some(second) = self.marks.get(1) {
+                        ui.label(format!("{} {}", i18n("Mark B:"), format_time(second.timestamp)));
+                    } else {
+                        ui.label(i18n("Press 'Mark B' to capture the second snapshot"));
+                    }
+                    if ui.small_button(i18n("Clear")).clicked() {
+                        clear = true;
+                    }
+                });
+
+                let [first, second] = self.marks.as_slice() else {
+                    return;
+                };
+
+                ScrollArea::vertical()
+                    .id_salt("metrics_comparison")
+                    .max_height(ui.ctx().content_rect().height() * 0.35)
+                    .show(ui, |ui| {
+                        Grid::new("metrics_comparison_grid")
+                            .num_columns(5)
+                            .spacing([16.0,4.0])
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong(i18n("Metric"));
+                                ui.strong(i18n("Mark A"));
+                                ui.strong(i18n("Mark B"));
+                                ui.strong(i18n("Change"));
+                                ui.strong("%");
+                                ui.end_row();
+
+                                for delta in compare_marks(first, second) {
+                                    let metric = delta.metric;
+                                    let color = theme_color().metrics_text_color;
+                                    let sign = if delta.delta < 0.0 { "-" } else if delta.delta > 0.0 { "+" } else { "" };
+
+                                    ui.label(i18n(metric.title().0));
+                                    ui.label(metric.format(delta.first, true, false));
+                                    ui.label(metric.format(delta.second, true, false));
+                                    ui.colored_label(color, format!("{sign}{}", metric.format(delta.delta.abs(), true, false)));
+                                    ui.colored_label(color, delta.percent.map(|percent| format!("{percent:+.2}%")).unwrap_or_default());
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+
+        if clear {
+            self.marks.clear();
+        }
+    }
+
+    /// Graphs of the active dashboard or of all enabled metrics.
+    fn graphs(core : &Core) -> Vec<MetricsGraph> {
+        if let Some(dashboard) = core.settings.user_interface.active_dashboard() {
+            dashboard.graphs.clone()
+        } else {
+            Metric::into_iter()
+                .filter(|metric| !core.settings.user_interface.metrics.disabled.contains(metric))
+                .map(MetricsGraph::new)
+                .collect()
+        }
+    }
+
+    /// Returns `true` if the dashboards have been modified.
+    fn render_dashboard_editor(&mut self, core : &mut Core, ui : &mut Ui) -> bool {
+        let mut changed = false;
+        let name = self.dashboard_name.trim().to_string();
+        let is_unique = !name.is_empty() && !core.settings.user_interface.dashboards.iter().any(|dashboard| dashboard.name == name);
+
+        ui.horizontal(|ui| {
+            ui.add(TextEdit::singleline(&mut self.dashboard_name).hint_text(i18n("Dashboard name")).desired_width(140.));
+
+            if ui.add_enabled(is_unique, Button::new(i18n("Create")))
+                .on_hover_text(i18n("Create a dashboard from the currently displayed metrics"))
+                .clicked()
+            {
+                let metrics = Self::graphs(core).into_iter().map(|graph| graph.metric);
+                core.settings.user_interface.dashboards.push(MetricsDashboard::new(name.clone(), metrics));
+                core.settings.user_interface.dashboard = Some(name.clone());
+                self.dashboard_name.clear();
+                changed = true;
+            }
+
+            if core.settings.user_interface.dashboard.is_some()
+                && ui.add_enabled(is_unique, Button::new(i18n("Rename"))).clicked()
+                && let Some(dashboard) = core.settings.user_interface.active_dashboard_mut()
+            {
+                dashboard.name = name.clone();
+                core.settings.user_interface.dashboard = Some(name.clone());
+                self.dashboard_name.clear();
+                changed = true;
+            }
+        });
+
+        let Some(dashboard) = core.settings.user_interface.active_dashboard_mut() else {
+            ui.label(i18n("Select a dashboard to edit its graphs"));
+            return changed;
+        };
+
+        ui.separator();
+
+        let count = dashboard.graphs.len();
+        let mut move_graph = None;
+        let mut remove = None;
+        ScrollArea::vertical()
+            .id_salt("metrics_dashboard_graphs")
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                for (index, graph) in dashboard.graphs.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(index > 0, Button::new(egui_phosphor::light::ARROW_UP).small()).clicked() {
+                            move_graph = Some((index, -1));
+                        }
+                        if ui.add_enabled(index + 1 < count, Button::new(egui_phosphor::light::ARROW_DOWN).small()).clicked() {
+                            move_graph = Some((index, 1));
+                        }
+                        if ui.small_button(egui_phosphor::light::X).on_hover_text(i18n("Remove")).clicked() {
+                            remove = Some(index);
+                        }
+
+                        ui.label(i18n(graph.metric.title().0));
+
+                        let mut overlay = graph.overlay;
+                        egui::ComboBox::from_id_salt(("metrics_dashboard_overlay", index))
+                            .selected_text(overlay.map(|metric| i18n(metric.title().0).to_string()).unwrap_or_else(|| i18n("No overlay").to_string()))
+                            .show_ui(ui, |ui| {
+                                ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                                ui.selectable_value(&mut overlay, None, i18n("No overlay"));
+                                for metric in Metric::into_iter().filter(|metric| *metric != graph.metric) {
+                                    ui.selectable_value(&mut overlay, Some(metric), i18n(metric.title().0));
+                                }
+                            });
+                        if overlay != graph.overlay {
+                            graph.overlay = overlay;
+                            changed = true;
+                        }
+                    });
+                }
+            });
+
+        if let Some((index, offset)) = move_graph {
+            dashboard.move_graph(index, offset);
+            changed = true;
+        }
+
+        if let Some(index) = remove {
+            dashboard.graphs.remove(index);
+            changed = true;
+        }
+
+        let mut delete = false;
+        ui.horizontal(|ui| {
+            let mut add = None;
+            egui::ComboBox::from_id_salt("metrics_dashboard_add")
+                .selected_text(i18n("Add graph"))
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                    for metric in Metric::into_iter().filter(|metric| !dashboard.contains(metric)) {
+                        if ui.selectable_label(false, i18n(metric.title().0)).clicked() {
+                            add = Some(metric);
+                        }
+                    }
+                });
+            if let Some(metric) = add {
+                dashboard.graphs.push(MetricsGraph::new(metric));
+                changed = true;
+            }
+
+            if ui.button(i18n("Delete Dashboard")).clicked() {
+                delete = true;
+            }
+        });
+
+        if delete {
+            let name = dashboard.name.clone();
+            core.settings.user_interface.dashboards.retain(|dashboard| dashboard.name != name);
+            core.settings.user_interface.dashboard = None;
+            changed = true;
+        }
+
+        changed
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn render_metric(
         &mut self, 
         ui : &mut Ui, 
-        metric : Metric, 
+        graph : &MetricsGraph, 
         metrics : &MetricsSnapshot, 
         range : std::ops::Range<isize>,
         graph_width : f32, 
         graph_height : f32
     ) {
 
+        let metric = graph.metric;
         let group = MetricGroup::from(metric);
         let graph_color = group.to_color();
 
@@ -278,26 +560,29 @@ impl Metrics {
                         ui.add_space(8.);
                         ui.horizontal(|ui|{
                             ui.with_layout(Layout::right_to_left(egui::Align::Min), |ui| {
+                                if let Some(overlay) = graph.overlay {
+                                    ui.colored_label(theme_color().metrics_text_color, format!("{}: {}", i18n(overlay.title().0), overlay.format(metrics.get(&overlay), true, false)));
+                                    ui.label("|");
+                                }
                                 ui.colored_label(theme_color().metrics_text_color, format!("{}: {}", i18n(metric.title().0), metric.format(metrics.get(&metric), true, false)));
                             });
                         });
 
                         // ---
-                        let graph_data = {
-                            let metrics_data = self.runtime.metrics_service().metrics_data();
-                            let data = metrics_data.get(&metric).unwrap();
-                            let mut start = range.start.clamp(METRICS_SAMPLES_START, 0).unsigned_abs();
-                            let mut end = range.end.clamp(METRICS_SAMPLES_START, 0).unsigned_abs();
-                            if start > data.len() {
-                                start = data.len();
-                            }
-                            if end > data.len() {
-                                end = data.len();
-                            }
-                            data[data.len()-start..data.len()-end].to_vec()
-                        };
+                        let time_format = if range.start < -(60 * 60 * 24) { "%b %d %H:%M" } else { "%H:%M:%S" };
+                        let graph_data = self.runtime.metrics_service().graph_data(metric, range.clone());
+
+                        // the overlay is mapped onto the primary axis and labelled by the right axis
+                        let overlay = graph.overlay.map(|overlay| {
+                            let overlay_data = self.runtime.metrics_service().graph_data(overlay, range.clone());
+                            let scale = AxisScale::new(&graph_data, &overlay_data);
+                            let overlay_data = overlay_data.into_iter().map(|PlotPoint { x, y }| PlotPoint { x, y: scale.to_primary(y) }).collect::<Vec<_>>();
+                            (overlay, scale, overlay_data)
+                        });
+                        let overlay_name = graph.overlay.map(|overlay| i18n(overlay.title().0).to_string()).unwrap_or_default();
+                        let overlay_scale = overlay.as_ref().map(|(overlay, scale, _)| (*overlay, *scale));
 
-                        let mut plot = Plot::new(metric.as_str())
+                        let mut plot = Plot::new((metric.as_str(), graph.overlay.map(|overlay| overlay.as_str())))
                         // .link_axis(id, true, false)
                         // .allow_boxed_zoom(true)
                         // .allow_double_click_reset(true)
@@ -324,7 +609,7 @@ impl Metrics {
                                 DateTime::<chrono::Utc>::from_timestamp((grid.value / 1000.0) as i64, 0)
                                     .expect("could not parse timestamp")
                                     .with_timezone(&chrono::Local)
-                                    .format("%H:%M:%S")
+                                    .format(time_format)
                                     .to_string()
                             })
                             .x_grid_spacer(
@@ -335,13 +620,18 @@ impl Metrics {
                                     calculate_grid_lines(base_step_size)
                                 })
                             )
-                            .label_formatter(move |_name, point| {
+                            .label_formatter(move |name, point| {
                                 let PlotPoint { x, y } = point;
 
-                                format!("{} @ {}", metric.format(*y, true, true), DateTime::<chrono::Utc>::from_timestamp((*x / 1000.0) as i64, 0)
+                                let value = match overlay_scale {
+                                    Some((overlay, scale)) if !name.is_empty() && name == overlay_name => overlay.format(scale.to_overlay(*y), true, true),
+                                    _ => metric.format(*y, true, true),
+                                };
+
+                                format!("{} @ {}", value, DateTime::<chrono::Utc>::from_timestamp((*x / 1000.0) as i64, 0)
                                     .expect("could not parse timestamp")
                                     .with_timezone(&chrono::Local)
-                                    .format("%H:%M:%S")
+                                    .format(time_format)
                                 )
                             })                                                    
                             .coordinates_formatter(Corner::LeftTop, CoordinatesFormatter::new(move |point,_| {
@@ -350,28 +640,56 @@ impl Metrics {
                             }))
                             ;
 
-                        if [Metric::NodeCpuUsage].contains(&metric) {
-                            plot = plot.include_y(100.);
-                        }
+                        if let Some((overlay, scale)) = overlay_scale {
+                            plot = plot.custom_y_axes(vec![
+                                AxisHints::new_y().formatter(move |grid, _range| {
+                                    match metric {
+                                        Metric::NetworkPastMedianTime => String::default(),
+                                        metric => metric.format(grid.value, true, true)
+                                    }
+                                }),
+                                AxisHints::new_y().placement(HPlacement::Right).formatter(move |grid, _range| {
+                                    match overlay {
+                                        Metric::NetworkPastMedianTime => String::default(),
+                                        overlay => overlay.format(scale.to_overlay(grid.value), true, true)
+                                    }
+                                }),
+                            ]);
+                        } else {
+                            // fixed minimums would compress the overlay scale
+                            if [Metric::NodeCpuUsage].contains(&metric) {
+                                plot = plot.include_y(100.);
+                            }
                 
-                        if [
-                            Metric::NodeResidentSetSizeBytes, 
-                            Metric::NodeVirtualMemorySizeBytes,
-                            Metric::NodeFileHandlesCount,
-                            Metric::NodeDiskIoReadPerSec,
-                            Metric::NodeDiskIoWritePerSec,
-                            Metric::NetworkTransactionsPerSecond,
-                        ].contains(&metric) {
-                            plot = plot.include_y(100.);
+                            if [
+                                Metric::NodeResidentSetSizeBytes, 
+                                Metric::NodeVirtualMemorySizeBytes,
+                                Metric::NodeFileHandlesCount,
+                                Metric::NodeDiskIoReadPerSec,
+                                Metric::NodeDiskIoWritePerSec,
+                                Metric::NetworkTransactionsPerSecond,
+                            ].contains(&metric) {
+                                plot = plot.include_y(100.);
+                            }
                         }
-                
-                        let line = Line::new("", PlotPoints::Owned(graph_data))
+
+                        let primary_name = if overlay.is_some() { i18n(metric.title().0).to_string() } else { String::new() };
+                        let line = Line::new(primary_name, PlotPoints::Owned(graph_data))
                             .color(graph_color)
                             .style(LineStyle::Solid)
                             .fill(0.0);
+
+                        let overlay_line = overlay.map(|(overlay, _, overlay_data)| {
+                            Line::new(i18n(overlay.title().0), PlotPoints::Owned(overlay_data))
+                                .color(theme_color().metrics_text_color)
+                                .style(LineStyle::dashed_loose())
+                        });
                 
                         plot.show(ui, |plot_ui| {
                             plot_ui.line(line);
+                            if let Some(overlay_line) = overlay_line {
+                                plot_ui.line(overlay_line);
+                            }
                         });
                     });
                 });
@@ -379,6 +697,46 @@ impl Metrics {
     }
 }
 
+/// Linear mapping between the value range of an overlay metric and
+/// the value range of the primary metric sharing the same plot.
+#[derive(Debug, Clone, Copy)]
+struct AxisScale {
+    primary_min : f64,
+    primary_span : f64,
+    overlay_min : f64,
+    overlay_span : f64,
+}
+
+impl AxisScale {
+    fn new(primary : &[PlotPoint], overlay : &[PlotPoint]) -> Self {
+        let (primary_min, primary_span) = Self::bounds(primary);
+        let (overlay_min, overlay_span) = Self::bounds(overlay);
+        Self { primary_min, primary_span, overlay_min, overlay_span }
+    }
+
+    fn bounds(points : &[PlotPoint]) -> (f64, f64) {
+        let (min, max) = points.iter()
+            .map(|point| point.y)
+            .filter(|y| y.is_finite())
+            .fold((f64::MAX, f64::MIN), |(min, max), y| (min.min(y), max.max(y)));
+        if min > max {
+            (0.0, 1.0)
+        } else if max - min <= f64::EPSILON {
+            (min - 0.5, 1.0)
+        } else {
+            (min, max - min)
+        }
+    }
+
+    fn to_primary(&self, y : f64) -> f64 {
+        (y - self.overlay_min) / self.overlay_span * self.primary_span + self.primary_min
+    }
+
+    fn to_overlay(&self, y : f64) -> f64 {
+        (y - self.primary_min) / self.primary_span * self.overlay_span + self.overlay_min
+    }
+}
+
 fn calculate_grid_lines(base_step_size : f64) -> [f64; 3] {
     let mut small_grid = 15.*1000_f64;
     let mut medium_grid = 30.*1000_f64;
@@ -7,15 +7,18 @@ kaspa_ng_macros::register_modules!(
     [
         account_create,
         account_manager,
+        connected_sites,
         donations,
         export,
         import,
         overview,
         private_key_create,
+        pskt,
         request,
         scanner,
         settings,
         testing,
+        verify_message,
         wallet_create,
         wallet_open,
         wallet_secret,
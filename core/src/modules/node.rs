@@ -1,16 +1,180 @@
-use kaspa_rpc_core::RpcPeerInfo;
+use kaspa_rpc_core::{RpcContextualPeerAddress, RpcIpAddress, RpcPeerInfo};
 
 use crate::imports::*;
+use crate::runtime::services::peer_monitor::{PEER_GEOLOCATION_URL, PeerSortColumn, country_histogram, sort_peers, version_histogram};
 use crate::utils::format_duration;
 
 pub struct Node {
     #[allow(dead_code)]
     runtime: Runtime,
+    sort_column : PeerSortColumn,
+    sort_ascending : bool,
+    peer_address : String,
+    peer_permanent : bool,
+    ban_address : String,
 }
 
 impl Node {
     pub fn new(runtime: Runtime) -> Self {
-        Self { runtime }
+        Self {
+            runtime,
+            sort_column : PeerSortColumn::default(),
+            sort_ascending : true,
+            peer_address : String::new(),
+            peer_permanent : false,
+            ban_address : String::new(),
+        }
+    }
+
+    fn render_actions(&mut self, ui : &mut Ui) {
+        let error_color = theme_color().error_color;
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(i18n("Peer address:"));
+            ui.add(TextEdit::singleline(&mut self.peer_address).hint_text("ip:port").desired_width(200.));
+            ui.checkbox(&mut self.peer_permanent, i18n("Permanent"));
+
+            let address = self.peer_address.trim().parse::<RpcContextualPeerAddress>().ok();
+            let add = ui.add_enabled(address.is_some(), Button::new(i18n("Add Peer"))).clicked();
+            if let Some(address) = address {
+                if add {
+                    self.add_peer(address, self.peer_permanent);
+                    self.peer_address.clear();
+                }
+            } else if !self.peer_address.trim().is_empty() {
+                ui.colored_label(error_color, i18n("Invalid address"));
+            }
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(i18n("IP address:"));
+            ui.add(TextEdit::singleline(&mut self.ban_address).desired_width(200.));
+
+            let ip = self.ban_address.trim().parse::<std::net::IpAddr>().ok().map(RpcIpAddress::from);
+            let ban = ui.add_enabled(ip.is_some(), Button::new(i18n("Ban"))).clicked();
+            let unban = ui.add_enabled(ip.is_some(), Button::new(i18n("Unban"))).clicked();
+            if let Some(ip) = ip {
+                if ban {
+                    self.ban(ip);
+                    self.ban_address.clear();
+                } else if unban {
+                    self.unban(ip);
+                    self.ban_address.clear();
+                }
+            } else if !self.ban_address.trim().is_empty() {
+                ui.colored_label(error_color, i18n("Invalid IP address"));
+            }
+        });
+    }
+
+    fn sort_header(&mut self, ui : &mut Ui, column : PeerSortColumn, title : &str) {
+        let text = if self.sort_column == column {
+            format!("{title} {}", if self.sort_ascending { "⏶" } else { "⏷" })
+        } else {
+            title.to_string()
+        };
+
+        if ui.add(Label::new(RichText::new(text).strong()).sense(Sense::click())).clicked() {
+            if self.sort_column == column {
+                self.sort_ascending = !self.sort_ascending;
+            } else {
+                self.sort_column = column;
+                self.sort_ascending = true;
+            }
+        }
+    }
+
+    fn render_peers(&mut self, ui : &mut Ui, peers : &[RpcPeerInfo], peer_management : bool) {
+        let color = theme_color().node_data_color;
+
+        let mut peers = peers.iter().collect::<Vec<_>>();
+        sort_peers(&mut peers, self.sort_column, self.sort_ascending);
+
+        Grid::new("peer_info_grid")
+            .num_columns(if peer_management { 7 } else { 6 })
+            .spacing([16.0,4.0])
+            .striped(true)
+            .show(ui, |ui| {
+
+                self.sort_header(ui, PeerSortColumn::Address, i18n("Address"));
+                self.sort_header(ui, PeerSortColumn::UserAgent, i18n("User Agent"));
+                self.sort_header(ui, PeerSortColumn::ProtocolVersion, i18n("Protocol"));
+                self.sort_header(ui, PeerSortColumn::Direction, i18n("Direction"));
+                self.sort_header(ui, PeerSortColumn::Ping, i18n("Ping"));
+                self.sort_header(ui, PeerSortColumn::TimeConnected, i18n("Connected"));
+                if peer_management {
+                    ui.label("");
+                }
+                ui.end_row();
+
+                for peer in peers {
+                    ui.colored_label(color, peer.address.to_string())
+                        .on_hover_text(format!(
+                            "{} {}\n{} {}\n{} {}",
+                            i18n("Id:"), peer.id,
+                            i18n("Time Offset:"), peer.time_offset,
+                            i18n("IBD:"), peer.is_ibd_peer,
+                        ));
+                    ui.colored_label(color, peer.user_agent.to_string());
+                    ui.colored_label(color, format!("v{}", peer.advertised_protocol_version));
+                    ui.colored_label(color, if peer.is_outbound { i18n("Outbound") } else { i18n("Inbound") });
+                    ui.colored_label(color, format_duration(peer.last_ping_duration));
+                    ui.colored_label(color, format_duration(peer.time_connected));
+                    if peer_management && ui.small_button(i18n("Ban")).on_hover_text(i18n("Ban the IP address of this peer")).clicked() {
+                        self.ban(peer.address.ip);
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn add_peer(&self, address : RpcContextualPeerAddress, is_permanent : bool) {
+        let peer_monitor_service = self.runtime.peer_monitor_service().clone();
+        let text = address.to_string();
+        spawn(async move {
+            match peer_monitor_service.add_peer(address, is_permanent).await {
+                Ok(()) => runtime().notify(UserNotification::success(i18n_args("Connecting to peer {address}", &[("address", text)])).short()),
+                Err(err) => runtime().toast(UserNotification::error(i18n_args("Unable to add peer {address}: {error}", &[("address", text), ("error", err.to_string())]))),
+            }
+            Ok(())
+        });
+    }
+
+    fn render_countries(&self, ui : &mut Ui, peers : &[RpcPeerInfo]) {
+        let peer_monitor_service = self.runtime.peer_monitor_service();
+        if !peer_monitor_service.is_geolocation_enabled() {
+            ui.label(RichText::new(i18n_args("Peer locations are resolved by {service}, which receives the IP addresses of your peers.", &[("service", PEER_GEOLOCATION_URL)])).size(12.).raised());
+            if ui.medium_button(i18n("Locate Peers")).clicked() {
+                peer_monitor_service.enable_geolocation();
+            }
+            return;
+        }
+
+        let countries = peer_monitor_service.countries();
+        let histogram = country_histogram(peers.iter().map(|peer| *peer.address.ip), &countries);
+        render_histogram(ui, "peer_country_grid", histogram);
+    }
+
+    fn ban(&self, ip : RpcIpAddress) {
+        let peer_monitor_service = self.runtime.peer_monitor_service().clone();
+        spawn(async move {
+            match peer_monitor_service.ban(ip).await {
+                Ok(()) => runtime().notify(UserNotification::success(i18n_args("Banned {ip}", &[("ip", ip.to_string())])).short()),
+                Err(err) => runtime().toast(UserNotification::error(i18n_args("Unable to ban {ip}: {error}", &[("ip", ip.to_string()), ("error", err.to_string())]))),
+            }
+            Ok(())
+        });
+    }
+
+    fn unban(&self, ip : RpcIpAddress) {
+        let peer_monitor_service = self.runtime.peer_monitor_service().clone();
+        spawn(async move {
+            match peer_monitor_service.unban(ip).await {
+                Ok(()) => runtime().notify(UserNotification::success(i18n_args("Unbanned {ip}", &[("ip", ip.to_string())])).short()),
+                Err(err) => runtime().toast(UserNotification::error(i18n_args("Unable to unban {ip}: {error}", &[("ip", ip.to_string()), ("error", err.to_string())]))),
+            }
+            Ok(())
+        });
     }
 }
 
@@ -49,24 +213,50 @@ impl ModuleT for Node {
                         ui.vertical(|ui| {
 
                             if let Some(peers) = self.runtime.peer_monitor_service().peer_info() {
-                                let (outbound, inbound) : (Vec<_>,Vec<_>) = peers.iter().partition(|peer| peer.is_outbound);
+                                let outbound = peers.iter().filter(|peer| peer.is_outbound).count();
+                                ui.label(i18n_args("Peers: {count} (inbound: {inbound}, outbound: {outbound})", &[
+                                    ("count", peers.len().to_string()),
+                                    ("inbound", (peers.len() - outbound).to_string()),
+                                    ("outbound", outbound.to_string()),
+                                ]));
+                                ui.space();
 
-                                CollapsingHeader::new(i18n("Inbound"))
+                                // peer management requires unsafe RPC, which is enabled
+                                // only on local nodes that do not expose their RPC
+                                let peer_management = core.settings.node.unsafe_rpc_enabled();
+                                if peer_management {
+                                    CollapsingHeader::new(i18n("Peer Management"))
+                                        .default_open(false)
+                                        .show(ui, |ui| {
+                                            self.render_actions(ui);
+                                        });
+                                } else if core.settings.node.node_kind.is_local() {
+                                    let reason = if core.settings.node.enable_unsafe_rpc {
+                                        i18n("Peer management is not available while the node RPC is publicly accessible.")
+                                    } else {
+                                        i18n("Peer management can be enabled in the node settings.")
+                                    };
+                                    ui.label(RichText::new(reason).size(12.).raised());
+                                    ui.space();
+                                }
+
+                                CollapsingHeader::new(i18n("Versions"))
                                     .default_open(true)
                                     .show(ui, |ui| {
+                                        let histogram = version_histogram(peers.iter().map(|peer| peer.user_agent.as_str()));
+                                        render_histogram(ui, "peer_version_grid", histogram);
+                                    });
 
-                                        inbound.iter().for_each(|peer| {
-                                            render_peer(ui, peer);
-                                        });
+                                CollapsingHeader::new(i18n("Countries"))
+                                    .default_open(false)
+                                    .show(ui, |ui| {
+                                        self.render_countries(ui, &peers);
                                     });
 
-                                CollapsingHeader::new(i18n("Outbound"))
+                                CollapsingHeader::new(i18n("Peers"))
                                     .default_open(true)
                                     .show(ui, |ui| {
-
-                                        outbound.iter().for_each(|peer| {
-                                            render_peer(ui, peer);
-                                        });
+                                        self.render_peers(ui, &peers, peer_management);
                                     });
                             } else if core.state().metrics().as_ref().map(|m| m.data.node_active_peers).unwrap_or_default() > 0 {
                                 ui.horizontal(|ui| {
@@ -94,45 +284,22 @@ impl ModuleT for Node {
 
 }
 
-fn render_peer(ui : &mut Ui, peer: &RpcPeerInfo) {
-
+fn render_histogram(ui : &mut Ui, id : &str, histogram : Vec<(String, usize)>) {
     let color = theme_color().node_data_color;
+    let max = histogram.first().map(|(_, count)| *count).unwrap_or(1).max(1);
 
-    CollapsingHeader::new(peer.id.to_string())
-        .default_open(true)
+    Grid::new(id)
+        .num_columns(2)
+        .spacing([16.0,4.0])
         .show(ui, |ui| {
-
-            Grid::new("peer_info_grid")
-                .num_columns(2)
-                .spacing([16.0,4.0])
-                .show(ui, |ui| {
-
-                    ui.label(i18n("User Agent"));
-                    ui.colored_label(color, peer.user_agent.to_string());
-                    ui.end_row();
-
-                    ui.label(i18n("Connection"));
-                    ui.horizontal(|ui| {
-                        ui.label(i18n("Address:"));
-                        ui.colored_label(color, peer.address.to_string());
-                        ui.label(i18n("Protocol:"));
-                        ui.colored_label(color, format!("v{}", peer.advertised_protocol_version));
-                        ui.label(i18n("IBD:"));
-                        ui.colored_label(color, peer.is_ibd_peer.to_string());
-                    });
-                    ui.end_row();
-
-                    ui.label(i18n("Metrics"));
-                    ui.horizontal(|ui|{
-                        ui.label(i18n("Ping:"));
-                        ui.colored_label(color, format_duration(peer.last_ping_duration));
-                        ui.label(i18n("Time Offset:"));
-                        ui.colored_label(color, peer.time_offset.to_string());
-                        ui.label(i18n("Uptime:"));
-                        ui.colored_label(color, format_duration(peer.time_connected));
-                    });
-                    ui.end_row();
-                });
-
+            for (label, count) in histogram {
+                ui.colored_label(color, label);
+                ui.add(
+                    ProgressBar::new(count as f32 / max as f32)
+                        .desired_width(200.)
+                        .text(count.to_string())
+                );
+                ui.end_row();
+            }
         });
-}
\ No newline at end of file
+}
@@ -5,6 +5,8 @@ use egui::load::Bytes;
 #[cfg(not(feature = "lean"))]
 use kaspa_metrics_core::{Metric,MetricGroup};
 #[cfg(not(feature = "lean"))]
+use chrono::DateTime;
+#[cfg(not(feature = "lean"))]
 use egui_plot::{
     Legend,
     Line,
@@ -14,6 +16,7 @@ use egui_plot::{
 };
 
 use crate::imports::*;
+use crate::market::*;
 
 pub struct Overview {
     #[allow(dead_code)]
@@ -137,8 +140,7 @@ impl Overview {
                             .show(ui, |ui| {
 
                                 if let Some(price_list) = market.price.as_ref() {
-                                    let mut symbols = price_list.keys().collect::<Vec<_>>();
-                                    symbols.sort();
+                                    let symbols = core.settings.market.sorted_symbols(price_list.keys());
                                     symbols.into_iter().for_each(|symbol| {
                                         if let Some(data) = price_list.get(symbol) {
                                             let symbol = symbol.to_uppercase();
@@ -175,6 +177,9 @@ impl Overview {
                                         }
                                     })
                                 }
+
+                                #[cfg(not(feature = "lean"))]
+                                Self::render_market_charts(core, ui, market);
                             });
                         }
 
@@ -580,6 +585,84 @@ impl Overview {
     }
 }
 
+#[cfg(not(feature = "lean"))]
+impl Overview {
+
+    fn render_market_charts(core : &Core, ui : &mut Ui, market : &Market) {
+        let currency = core.settings.market.primary_currency.as_str();
+        let Some(ohlc) = market.ohlc.as_ref().and_then(|ohlc| ohlc.get(currency)) else {
+            return;
+        };
+
+        if ohlc.candles.len() < 2 {
+            return;
+        }
+
+        let symbol = currency.to_uppercase();
+        let precision = precision_from_symbol(currency);
+
+        CollapsingHeader::new(i18n_args("Price History ({symbol})", &[("symbol", symbol.as_str())]))
+            .default_open(true)
+            .show(ui, |ui| {
+                let points = ohlc.candles.iter().map(|candle| [candle.timestamp, candle.close]).collect::<Vec<_>>();
+                Self::render_history_chart(ui, "market_price_chart", points, symbol.as_str(), precision, theme_color().market_default_color);
+            });
+
+        let history = core.wallet_descriptor.as_ref().map(|descriptor| {
+            core.balance_history.series(descriptor.filename.as_str(), core.settings.node.network)
+        });
+
+        if let Some(history) = history {
+            let mut points = portfolio_series(&history, ohlc);
+            // extend the series with the current balance at the latest price
+            if let (Some(balance), Some(price)) = (core.wallet_balance(), ohlc.last_close()) {
+                points.push([unixtime_as_millis_f64(), sompi_to_kaspa(balance) * price]);
+            }
+
+            if points.len() >= 2 {
+                CollapsingHeader::new(i18n_args("Wallet Value ({symbol})", &[("symbol", symbol.as_str())]))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        Self::render_history_chart(ui, "market_portfolio_chart", points, symbol.as_str(), precision, theme_color().market_up_color);
+                    });
+            }
+        }
+    }
+
+    fn render_history_chart(ui : &mut Ui, id : &str, points : Vec<[f64;2]>, symbol : &str, precision : usize, color : Color32) {
+        let format_date = |timestamp : f64| {
+            DateTime::<chrono::Utc>::from_timestamp((timestamp / 1000.0) as i64, 0)
+                .map(|date| date.with_timezone(&chrono::Local).format("%b %d").to_string())
+                .unwrap_or_default()
+        };
+
+        let label_symbol = symbol.to_string();
+        let plot = Plot::new(id)
+            .height(128.)
+            .auto_bounds([true, true])
+            .set_margin_fraction(vec2(0.0,0.1))
+            .y_axis_min_width(4.0 * 12.0)
+            .show_axes(true)
+            .show_grid(true)
+            .allow_drag([false, false])
+            .allow_scroll(false)
+            .allow_zoom(false)
+            .x_axis_formatter(move |grid, _range| format_date(grid.value))
+            .label_formatter(move |_name, point| {
+                format!("{} @ {}", format_currency_with_symbol(point.y, precision, label_symbol.as_str()), format_date(point.x))
+            });
+
+        let line = Line::new("", PlotPoints::Owned(points.into_iter().map(|[x, y]| PlotPoint::new(x, y)).collect()))
+            .color(color)
+            .style(LineStyle::Solid)
+            .fill(0.0);
+
+        plot.show(ui, |plot_ui| {
+            plot_ui.line(line);
+        });
+    }
+}
+
 #[cfg(not(feature = "lean"))]
 const METRICS : &[Metric] = &[
     Metric::NodeCpuUsage,
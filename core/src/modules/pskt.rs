@@ -0,0 +1,912 @@
+use crate::imports::*;
+use egui_phosphor::light::{CARET_LEFT, CARET_RIGHT, CLIPBOARD_TEXT, FLOPPY_DISK, FOLDER_OPEN, QR_CODE};
+use kaspa_consensus_core::tx::{TransactionOutpoint, UtxoEntry};
+use kaspa_txscript::{multisig_redeem_script, multisig_redeem_script_ecdsa};
+use kaspa_wallet_core::account::{MULTISIG_ACCOUNT_KIND, multisig::Payload as MultisigPayload};
+use kaspa_wallet_core::api::{AccountsPskbBroadcastRequest, AccountsPskbSignRequest};
+use kaspa_wallet_core::derivation::{AddressDerivationManagerTrait, WalletDerivationManagerTrait, gen1::WalletDerivationManager};
+use kaspa_wallet_core::tx::{Fees, Generator, GeneratorSettings, PaymentDestination, PaymentOutput, PendingTransaction};
+use kaspa_wallet_pskt::prelude::{Bundle, Combiner, Creator, InputBuilder, OutputBuilder, Signer, PSKT};
+use std::{borrow::Cow, collections::hash_map::Entry};
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Maximum number of bundle characters carried by a single QR frame.
+const QR_FRAME_SIZE : usize = 240;
+const QR_FRAME_PREFIX : &str = "pskb";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Operation {
+    /// Create a new transaction bundle signed by the local cosigner
+    Create,
+    /// Add local signatures to an imported bundle
+    Sign,
+    /// Combine signatures from one or more bundles and submit to the network
+    Broadcast,
+}
+
+#[derive(Clone)]
+pub enum State {
+    Select,
+    Create,
+    Import { operation : Operation },
+    WalletSecret { operation : Operation },
+    Processing { operation : Operation },
+    Export { pskb : Arc<String> },
+    Finish { transaction_ids : Arc<Vec<TransactionId>> },
+    Error { error : Arc<Error> },
+}
+
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+enum Focus {
+    #[default]
+    None,
+    Address,
+    Amount,
+    WalletSecret,
+    PaymentSecret,
+}
+
+#[derive(Clone)]
+enum PsktResult {
+    Bundle(String),
+    Broadcast(Vec<TransactionId>),
+}
+
+#[derive(Default)]
+struct Context {
+    destination_address_string : String,
+    send_amount_text : String,
+    priority_fees_text : String,
+    pskb : Vec<String>,
+    wallet_secret : String,
+    payment_secret : String,
+    error : Option<String>,
+    show_qr : bool,
+}
+
+impl Zeroize for Context {
+    fn zeroize(&mut self) {
+        self.destination_address_string.zeroize();
+        self.send_amount_text.zeroize();
+        self.priority_fees_text.zeroize();
+        self.pskb.clear();
+        self.wallet_secret.zeroize();
+        self.payment_secret.zeroize();
+        self.error = None;
+        self.show_qr = false;
+    }
+}
+
+/// Summary of signatures collected within a serialized transaction bundle.
+pub struct BundleInfo {
+    pub transactions : usize,
+    pub inputs : usize,
+    pub signatures : usize,
+}
+
+impl BundleInfo {
+    pub fn try_new(pskb : &str) -> Result<Self> {
+        let bundle = Bundle::deserialize(pskb.trim())?;
+        let transactions = bundle.iter().count();
+        let inputs = bundle.iter().map(|inner| inner.inputs.len()).sum();
+        let signatures = bundle.iter().flat_map(|inner| inner.inputs.iter()).map(|input| input.partial_sigs.len()).sum();
+        Ok(Self { transactions, inputs, signatures })
+    }
+}
+
+impl std::fmt::Display for BundleInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}  •  {}  •  {}",
+            i18n_args("Transactions: {number}", &[("number", self.transactions.to_string())]),
+            i18n_args("Inputs: {number}", &[("number", self.inputs.to_string())]),
+            i18n_args("Signatures: {number}", &[("number", self.signatures.to_string())]),
+        )
+    }
+}
+
+/// Merges partial signatures of bundles produced by different cosigners
+/// from the same unsigned transaction set.
+pub fn combine_bundles(bundles : &[String]) -> Result<Bundle> {
+    let mut bundles = bundles.iter().map(|pskb| Bundle::deserialize(pskb.trim())).collect::<std::result::Result<Vec<_>,_>>()?.into_iter();
+
+    let first = bundles.next().ok_or_else(|| Error::custom(i18n("No transaction bundles supplied")))?;
+    let mut combined = first.iter().cloned().map(|inner| PSKT::<Signer>::from(inner).combiner()).collect::<Vec<PSKT<Combiner>>>();
+
+    for bundle in bundles {
+        if bundle.iter().count() != combined.len() {
+            return Err(Error::custom(i18n("Transaction bundles do not match")));
+        }
+
+        combined = combined
+            .into_iter()
+            .zip(bundle.iter().cloned())
+            .map(|(pskt, inner)| (pskt + PSKT::<Signer>::from(inner)).map_err(|err| Error::custom(err.to_string())))
+            .collect::<Result<Vec<_>>>()?;
+    }
+
+    let mut bundle = Bundle::new();
+    combined.into_iter().for_each(|pskt| bundle.add_pskt(pskt));
+    Ok(bundle)
+}
+
+/// Splits a serialized bundle into QR frames formatted as `pskb <n>/<total> <data>`.
+pub fn bundle_to_qr_frames(pskb : &str) -> Vec<String> {
+    let chunks = pskb.as_bytes().chunks(QR_FRAME_SIZE).map(|chunk| String::from_utf8_lossy(chunk).to_string()).collect::<Vec<_>>();
+    let total = chunks.len();
+    chunks.into_iter().enumerate().map(|(index, chunk)| format!("{QR_FRAME_PREFIX} {}/{total} {chunk}", index + 1)).collect()
+}
+
+/// Reassembles a bundle from pasted QR frames. Text that does not
+/// consist of QR frames is returned unchanged.
+pub fn bundle_from_qr_frames(text : &str) -> Result<String> {
+    let lines = text.lines().map(str::trim).filter(|line| line.is_not_empty()).collect::<Vec<_>>();
+    if !lines.first().map(|line| line.starts_with(QR_FRAME_PREFIX)).unwrap_or(false) {
+        return Ok(text.trim().to_string());
+    }
+
+    let mut frames = lines.into_iter().map(|line| {
+        let mut parts = line.splitn(3, ' ');
+        let (Some(QR_FRAME_PREFIX), Some(position), Some(data)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(Error::custom(i18n("Invalid QR frame")));
+        };
+        let (index, total) = position.split_once('/').ok_or_else(|| Error::custom(i18n("Invalid QR frame")))?;
+        let index = index.parse::<usize>().map_err(|_| Error::custom(i18n("Invalid QR frame")))?;
+        let total = total.parse::<usize>().map_err(|_| Error::custom(i18n("Invalid QR frame")))?;
+        Ok((index, total, data))
+    }).collect::<Result<Vec<_>>>()?;
+
+    frames.sort_by_key(|(index, _, _)| *index);
+    frames.dedup_by_key(|(index, _, _)| *index);
+
+    let total = frames.first().map(|(_, total, _)| *total).unwrap_or_default();
+    if frames.len() != total || frames.iter().enumerate().any(|(n, (index, frame_total, _))| *index != n + 1 || *frame_total != total) {
+        return Err(Error::custom(i18n_args("Incomplete QR sequence: {received} of {total} frames", &[("received", frames.len().to_string()), ("total", total.to_string())])));
+    }
+
+    Ok(frames.into_iter().map(|(_, _, data)| data).collect())
+}
+
+/// Derives redeem scripts for the addresses of a multisig account so that
+/// multisig inputs of a bundle can be signed and finalized by cosigners.
+struct MultisigRedeemScripts {
+    derivation : Arc<dyn AddressDerivationManagerTrait>,
+    derivators : Vec<WalletDerivationManager>,
+    minimum_signatures : usize,
+    ecdsa : bool,
+}
+
+impl MultisigRedeemScripts {
+    fn try_new(account : &Arc<dyn CoreAccount>) -> Result<Option<Self>> {
+        if account.account_kind() != MULTISIG_ACCOUNT_KIND {
+            return Ok(None);
+        }
+
+        let MultisigPayload { xpub_keys, cosigner_index, minimum_signatures, ecdsa } = MultisigPayload::try_load(&account.to_storage()?)?;
+        let derivators = xpub_keys.iter().map(|xpub_key| {
+            WalletDerivationManager::from_extended_public_key(xpub_key.clone(), cosigner_index.map(u32::from))
+        }).collect::<std::result::Result<Vec<_>,_>>()?;
+
+        Ok(Some(Self {
+            derivation : account.clone().as_derivation_capable()?.derivation(),
+            derivators,
+            minimum_signatures : minimum_signatures as usize,
+            ecdsa,
+        }))
+    }
+
+    fn redeem_script(&self, address : &Address) -> Result<Vec<u8>> {
+        let (receive, change) = self.derivation.addresses_indexes(&[address])?;
+        let keys = if let Some((_, index)) = receive.first() {
+            self.derivators.iter().map(|derivator| derivator.derive_receive_pubkey(*index)).collect::<std::result::Result<Vec<_>,_>>()?
+        } else if let Some((_, index)) = change.first() {
+            self.derivators.iter().map(|derivator| derivator.derive_change_pubkey(*index)).collect::<std::result::Result<Vec<_>,_>>()?
+        } else {
+            return Err(Error::custom(i18n("Unknown multisig input address")));
+        };
+
+        let redeem_script = if self.ecdsa {
+            multisig_redeem_script_ecdsa(keys.iter().map(|key| key.serialize()), self.minimum_signatures)
+        } else {
+            multisig_redeem_script(keys.iter().map(|key| key.x_only_public_key().0.serialize()), self.minimum_signatures)
+        };
+
+        redeem_script.map_err(|err| Error::custom(err.to_string()))
+    }
+}
+
+fn pskt_from_pending_transaction(pending : &PendingTransaction, redeem_scripts : Option<&MultisigRedeemScripts>) -> Result<PSKT<Signer>> {
+    let transaction = pending.transaction();
+    let utxo_entries = pending.utxo_entries().iter().map(|utxo| (TransactionOutpoint::from(utxo.outpoint()), utxo)).collect::<HashMap<_,_>>();
+
+    let mut pskt = PSKT::<Creator>::default().constructor();
+
+    for input in transaction.inputs.iter() {
+        let utxo = utxo_entries.get(&input.previous_outpoint).ok_or_else(|| Error::custom(i18n("Missing UTXO entry for transaction input")))?;
+        let mut builder = InputBuilder::default();
+        builder
+            .utxo_entry(UtxoEntry::new(utxo.amount(), utxo.script_public_key().clone(), utxo.block_daa_score(), utxo.is_coinbase()))
+            .previous_outpoint(input.previous_outpoint)
+            .sig_op_count(input.sig_op_count);
+
+        if let Some(redeem_scripts) = redeem_scripts {
+            let address = utxo.utxo.address.as_ref().ok_or_else(|| Error::custom(i18n("Missing address for multisig transaction input")))?;
+            builder.redeem_script(redeem_scripts.redeem_script(address)?);
+        }
+
+        let input = builder.build().map_err(|err| Error::custom(err.to_string()))?;
+        pskt = pskt.input(input);
+    }
+
+    for output in transaction.outputs.iter() {
+        let output = OutputBuilder::default()
+            .amount(output.value)
+            .script_public_key(output.script_public_key.clone())
+            .build()
+            .map_err(|err| Error::custom(err.to_string()))?;
+        pskt = pskt.output(output);
+    }
+
+    Ok(pskt.no_more_inputs().no_more_outputs().signer())
+}
+
+/// Runs the transaction generator for the given account without signing
+/// and serializes the resulting transactions as a bundle for offline signing.
+pub async fn create_unsigned_bundle(account_id : AccountId, destination : PaymentDestination, fee_rate : Option<f64>, priority_fee_sompi : Fees) -> Result<String> {
+    let account = load_local_account(&account_id).await?;
+    let redeem_scripts = MultisigRedeemScripts::try_new(&account)?;
+
+    let settings = GeneratorSettings::try_new_with_account(account, destination, fee_rate, priority_fee_sompi, None)?;
+    let abortable = Abortable::default();
+    let generator = Generator::try_new(settings, None, Some(&abortable))?;
+
+    let mut bundle = Bundle::new();
+    let stream = generator.stream();
+    pin_mut!(stream);
+    while let Some(pending) = stream.next().await {
+        bundle.add_pskt(pskt_from_pending_transaction(&pending?, redeem_scripts.as_ref())?);
+    }
+
+    Ok(bundle.serialize()?)
+}
+
+pub struct Pskt {
+    #[allow(dead_code)]
+    runtime: Runtime,
+    account : Option<Account>,
+    context : Context,
+    state : State,
+    focus : FocusManager<Focus>,
+    qr_frame : usize,
+    qr_frame_total : usize,
+    qr : HashMap<String, (String,load::Bytes)>,
+}
+
+impl Pskt {
+    pub fn new(runtime: Runtime) -> Self {
+        Self {
+            runtime,
+            account : None,
+            context : Context::default(),
+            state : State::Select,
+            focus : FocusManager::default(),
+            qr_frame : 0,
+            qr_frame_total : 0,
+            qr : HashMap::default(),
+        }
+    }
+
+    pub fn select(&mut self, account : &Account) {
+        self.account = Some(account.clone());
+        self.context.zeroize();
+        self.state = State::Select;
+    }
+
+    /// Opens the signing step directly, used by offline instances.
+    pub fn sign(&mut self, account : &Account) {
+        self.select(account);
+        self.context.pskb = vec![String::default()];
+        self.state = State::Import { operation : Operation::Sign };
+    }
+
+    /// Presents a bundle produced elsewhere (e.g. the send flow) for export.
+    pub fn export(&mut self, account : &Account, pskb : String) {
+        self.select(account);
+        self.qr_frame = 0;
+        self.state = State::Export { pskb : Arc::new(pskb) };
+    }
+
+    fn qr(&mut self, frame : &str) -> (String,load::Bytes) {
+        let hash = format!("{:x}",xxh3_64(format!("{frame}{}", theme_color().name).as_bytes()));
+        let (qr_uri,qr_bytes) = match self.qr.entry(hash.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let uri = format!("bytes://{hash}.svg");
+                let qr = render_qrcode_with_version(frame, 256, 256, qrcode::Version::Normal(10));
+                entry.insert((uri, qr.as_bytes().to_vec().into()))
+            },
+        };
+
+        (qr_uri.clone(),qr_bytes.clone())
+    }
+
+    fn render_qr_frames(&mut self, ui : &mut Ui, pskb : &str) {
+        let frames = bundle_to_qr_frames(pskb);
+        let total = frames.len();
+        if total == 0 {
+            return;
+        }
+
+        // restart the sequence if the bundle (and the frame count) has changed
+        if total != self.qr_frame_total {
+            self.qr_frame_total = total;
+            self.qr_frame = 0;
+        }
+        self.qr_frame = self.qr_frame.min(total - 1);
+        let (qr_uri, qr_bytes) = self.qr(frames[self.qr_frame].as_str());
+
+        ui.add(
+            Image::new(ImageSource::Bytes { uri : Cow::Owned(qr_uri), bytes: qr_bytes })
+            .fit_to_original_size(1.0)
+            .texture_options(TextureOptions::NEAREST)
+        );
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(self.qr_frame > 0, Button::new(CARET_LEFT)).clicked() {
+                self.qr_frame -= 1;
+            }
+            ui.label(i18n_args("Frame {index} of {total}", &[("index", (self.qr_frame + 1).to_string()), ("total", total.to_string())]));
+            if ui.add_enabled(self.qr_frame + 1 < total, Button::new(CARET_RIGHT)).clicked() {
+                self.qr_frame += 1;
+            }
+        });
+    }
+
+    fn spawn_operation(&mut self, core : &Core, operation : Operation) {
+        let Some(account) = self.account.as_ref() else { return; };
+
+        let account_id = account.id();
+        let wallet_secret = Secret::from(self.context.wallet_secret.as_str());
+        let payment_secret = account.requires_bip39_passphrase(core).then_some(Secret::from(self.context.payment_secret.as_str()));
+        self.context.wallet_secret.zeroize();
+        self.context.payment_secret.zeroize();
+
+        let pskt_result = Payload::<Result<PsktResult>>::new("pskt_result");
+
+        match operation {
+            Operation::Create => {
+                let address = Address::try_from(self.context.destination_address_string.trim());
+                let amount = try_kaspa_str_to_sompi(self.context.send_amount_text.as_str());
+                let priority_fee_sompi = try_kaspa_str_to_sompi(self.context.priority_fees_text.as_str());
+
+                spawn_with_result(&pskt_result, async move {
+                    let address = address?;
+                    let amount = amount?.ok_or_else(|| Error::custom(i18n("Please enter an amount")))?;
+                    let priority_fee_sompi = priority_fee_sompi?.unwrap_or_default();
+
+                    let account = load_local_account(&account_id).await?;
+
+                    let payment_output = PaymentOutput {
+                        address,
+                        amount,
+                        covenant: None,
+                    };
+
+                    let abortable = Abortable::default();
+                    let bundle = account.pskb_from_send_generator(
+                        payment_output.into(),
+                        None,
+                        Fees::SenderPays(priority_fee_sompi),
+                        None,
+                        wallet_secret,
+                        payment_secret,
+                        &abortable,
+                    ).await?;
+
+                    runtime().request_repaint();
+                    Ok(PsktResult::Bundle(bundle.serialize()?))
+                });
+            }
+            Operation::Sign => {
+                let pskb = self.context.pskb.first().cloned().unwrap_or_default();
+
+                spawn_with_result(&pskt_result, async move {
+                    let request = AccountsPskbSignRequest {
+                        account_id,
+                        pskb : pskb.trim().to_string(),
+                        wallet_secret,
+                        payment_secret,
+                        sign_for_address : None,
+                    };
+
+                    let pskb = runtime().wallet().accounts_pskb_sign_call(request).await?.pskb;
+                    runtime().request_repaint();
+                    Ok(PsktResult::Bundle(pskb))
+                });
+            }
+            Operation::Broadcast => {
+                let bundles = self.context.pskb.iter().filter(|pskb| pskb.is_not_empty()).cloned().collect::<Vec<_>>();
+
+                spawn_with_result(&pskt_result, async move {
+                    let bundle = combine_bundles(&bundles)?;
+
+                    let request = AccountsPskbBroadcastRequest {
+                        account_id,
+                        pskb : bundle.serialize()?,
+                    };
+
+                    let transaction_ids = runtime().wallet().accounts_pskb_broadcast_call(request).await?.transaction_ids;
+                    runtime().request_repaint();
+                    Ok(PsktResult::Broadcast(transaction_ids))
+                });
+            }
+        }
+    }
+
+    fn render_bundle_editor(ui : &mut Ui, index : usize, pskb : &mut String) {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(i18n_args("Transaction bundle #{index}", &[("index", (index + 1).to_string())])).size(12.).raised());
+
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui.small_button(format!("{FOLDER_OPEN} {}", i18n("Load"))).clicked() {
+                if let Some(path) = rfd::FileDialog::new().add_filter("pskb", &["pskb", "txt"]).pick_file() {
+                    match std::fs::read_to_string(path) {
+                        Ok(data) => *pskb = data.trim().to_string(),
+                        Err(err) => runtime().error(err.to_string()),
+                    }
+                }
+            }
+        });
+
+        ui.add_sized(theme_style().panel_editor_size * vec2(1.,3.), TextEdit::multiline(pskb)
+            .font(FontId::monospace(10.)));
+
+        if pskb.is_not_empty() {
+            match bundle_from_qr_frames(pskb).and_then(|pskb| BundleInfo::try_new(&pskb)) {
+                Ok(info) => { ui.label(info.to_string()); },
+                Err(err) => { ui.label(RichText::new(err.to_string()).color(theme_color().error_color)); },
+            }
+        }
+    }
+}
+
+impl ModuleT for Pskt {
+
+    fn style(&self) -> ModuleStyle {
+        ModuleStyle::Mobile
+    }
+
+    fn modal(&self) -> bool {
+        true
+    }
+
+    fn secure(&self) -> bool {
+        true
+    }
+
+    fn reset(&mut self, _core: &mut Core) {
+        self.account = None;
+        self.context.zeroize();
+        self.state = State::Select;
+    }
+
+    fn render(
+        &mut self,
+        core: &mut Core,
+        _ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        ui: &mut egui::Ui,
+    ) {
+
+        let Some(account) = self.account.clone() else {
+            core.select::<modules::AccountManager>();
+            return;
+        };
+
+        let network_type = core.network().into();
+
+        match self.state.clone() {
+
+            State::Select => {
+
+                let back = Rc::new(RefCell::new(false));
+
+                Panel::new(self)
+                    .with_caption(i18n("Partially Signed Transactions"))
+                    .with_back(|_this| {
+                        *back.borrow_mut() = true;
+                    })
+                    .with_close_enabled(false, |_|{
+                    })
+                    .with_header(|_this,ui| {
+                        ui.label(i18n_args("Account: {account}", &[("account", account.name_or_id())]));
+                        ui.label(" ");
+                    })
+                    .with_body(|this, ui| {
+
+                        let watch_only = account.is_watch_only();
+
+                        if ui.large_button_enabled(!watch_only, i18n("Create Transaction")).clicked() {
+                            this.context.zeroize();
+                            this.state = State::Create;
+                            this.focus.next(Focus::Address);
+                        }
+                        ui.label(i18n("Create a transaction and sign it with your key."));
+
+                        ui.add(ui.create_separator(Some(32.0), 0.5, Some(true)));
+
+                        if ui.large_button_enabled(!watch_only, i18n("Sign Transaction")).clicked() {
+                            this.context.zeroize();
+                            this.context.pskb = vec![String::default()];
+                            this.state = State::Import { operation : Operation::Sign };
+                        }
+                        ui.label(i18n("Add your signature to a transaction created by another cosigner."));
+
+                        ui.add(ui.create_separator(Some(32.0), 0.5, Some(true)));
+
+                        if ui.large_button(i18n("Combine & Broadcast")).clicked() {
+                            this.context.zeroize();
+                            this.context.pskb = vec![String::default()];
+                            this.state = State::Import { operation : Operation::Broadcast };
+                        }
+                        ui.label(i18n("Merge signatures from cosigners and submit the transaction."));
+
+                    }).render(ui);
+
+                if *back.borrow() {
+                    self.context.zeroize();
+                    core.back();
+                }
+            }
+
+            State::Create => {
+
+                Panel::new(self)
+                    .with_caption(i18n("Create Transaction"))
+                    .with_back(|this| {
+                        this.state = State::Select;
+                    })
+                    .with_close_enabled(false, |_|{
+                    })
+                    .with_body(|this, ui| {
+
+                        TextEditor::new(
+                            &mut this.context.destination_address_string,
+                            &mut this.focus,
+                            Focus::Address,
+                            |ui, text| {
+                                ui.label(RichText::new(i18n("Enter destination address")).size(12.).raised());
+                                ui.add_sized(theme_style().panel_editor_size, TextEdit::singleline(text)
+                                    .vertical_align(Align::Center))
+                            },
+                        )
+                        .submit(|_, focus| {
+                            focus.next(Focus::Amount);
+                        })
+                        .build(ui);
+
+                        TextEditor::new(
+                            &mut this.context.send_amount_text,
+                            &mut this.focus,
+                            Focus::Amount,
+                            |ui, text| {
+                                ui.add_space(8.);
+                                ui.label(RichText::new(i18n_args("Enter {suffix} amount to send", &[("suffix", kaspa_suffix(&network_type))])).size(12.).raised());
+                                ui.add_sized(theme_style().panel_editor_size, TextEdit::singleline(text)
+                                    .vertical_align(Align::Center))
+                            },
+                        )
+                        .build(ui);
+
+                        ui.add_space(8.);
+                        ui.label(RichText::new(i18n("Enter priority fees")).size(12.).raised());
+                        ui.add_sized(theme_style().panel_editor_size, TextEdit::singleline(&mut this.context.priority_fees_text)
+                            .vertical_align(Align::Center));
+
+                        if let Some(error) = this.context.error.as_ref() {
+                            ui.label(" ");
+                            ui.label(RichText::new(error).color(theme_color().error_color));
+                        }
+                    })
+                    .with_footer(|this, ui| {
+                        if ui.large_button(i18n("Continue")).clicked() {
+                            let address = Address::try_from(this.context.destination_address_string.trim());
+                            let amount = try_kaspa_str_to_sompi(this.context.send_amount_text.as_str());
+                            match (address, amount) {
+                                (Err(err), _) => {
+                                    this.context.error = Some(i18n_args("Invalid address: {err}", &[("err", err.to_string())]));
+                                }
+                                (_, Ok(None)) => {
+                                    this.context.error = Some(i18n("Please enter an amount").to_string());
+                                }
+                                (_, Err(err)) => {
+                                    this.context.error = Some(format!("{} {err}", i18n("Invalid amount:")));
+                                }
+                                _ => {
+                                    this.context.error = None;
+                                    this.state = State::WalletSecret { operation : Operation::Create };
+                                    this.focus.next(Focus::WalletSecret);
+                                }
+                            }
+                        }
+                    })
+                    .render(ui);
+            }
+
+            State::Import { operation } => {
+
+                let caption = match operation {
+                    Operation::Broadcast => i18n("Combine & Broadcast"),
+                    _ => i18n("Sign Transaction"),
+                };
+
+                Panel::new(self)
+                    .with_caption(caption)
+                    .with_back(|this| {
+                        this.state = State::Select;
+                    })
+                    .with_close_enabled(false, |_|{
+                    })
+                    .with_header(move |_this, ui| {
+                        match operation {
+                            Operation::Broadcast => {
+                                ui.label(i18n("Paste transaction bundles signed by each cosigner."));
+                            }
+                            _ => {
+                                ui.label(i18n("Paste the transaction bundle you have received."));
+                            }
+                        }
+                        ui.label(" ");
+                    })
+                    .with_body(move |this, ui| {
+
+                        for (index, pskb) in this.context.pskb.iter_mut().enumerate() {
+                            Self::render_bundle_editor(ui, index, pskb);
+                            ui.add_space(8.);
+                        }
+
+                        if operation == Operation::Broadcast && ui.medium_button(i18n("Add Bundle")).clicked() {
+                            this.context.pskb.push(String::default());
+                        }
+
+                        if let Some(error) = this.context.error.as_ref() {
+                            ui.label(" ");
+                            ui.label(RichText::new(error).color(theme_color().error_color));
+                        }
+                    })
+                    .with_footer(move |this, ui| {
+                        let enabled = this.context.pskb.iter().any(|pskb| pskb.is_not_empty());
+                        if ui.large_button_enabled(enabled, i18n("Continue")).clicked() {
+                            let invalid = this.context.pskb.iter_mut().filter(|pskb| pskb.is_not_empty()).find_map(|pskb| {
+                                match bundle_from_qr_frames(pskb) {
+                                    Ok(bundle) => {
+                                        *pskb = bundle;
+                                        BundleInfo::try_new(pskb).err()
+                                    }
+                                    Err(err) => Some(err),
+                                }
+                            });
+                            if let Some(err) = invalid {
+                                this.context.error = Some(err.to_string());
+                            } else if operation == Operation::Broadcast {
+                                this.context.error = None;
+                                this.state = State::Processing { operation };
+                            } else {
+                                this.context.error = None;
+                                this.state = State::WalletSecret { operation };
+                                this.focus.next(Focus::WalletSecret);
+                            }
+                        }
+                    })
+                    .render(ui);
+
+                if let State::Processing { operation } = self.state.clone() {
+                    self.spawn_operation(core, operation);
+                }
+            }
+
+            State::WalletSecret { operation } => {
+
+                let requires_payment_passphrase = account.requires_bip39_passphrase(core);
+                let submit = Rc::new(RefCell::new(false));
+
+                Panel::new(self)
+                    .with_caption(i18n("Wallet Secret"))
+                    .with_back(move |this| {
+                        this.context.wallet_secret.zeroize();
+                        this.context.payment_secret.zeroize();
+                        this.state = match operation {
+                            Operation::Create => State::Create,
+                            _ => State::Import { operation },
+                        };
+                    })
+                    .with_close_enabled(false, |_|{
+                    })
+                    .with_header(|_ctx,ui| {
+                        ui.label(i18n("Please enter the wallet secret"));
+                    })
+                    .with_body(|this,ui| {
+                        TextEditor::new(
+                            &mut this.context.wallet_secret,
+                            &mut this.focus,
+                            Focus::WalletSecret,
+                            |ui, text| {
+                                ui.label(RichText::new(i18n("Enter your wallet secret")).size(12.).raised());
+                                ui.add_sized(theme_style().panel_editor_size, TextEdit::singleline(text)
+                                    .vertical_align(Align::Center)
+                                    .password(true))
+                            },
+                        ).submit(|text,focus| {
+                            if requires_payment_passphrase {
+                                focus.next(Focus::PaymentSecret);
+                            } else if !text.is_empty() {
+                                *submit.borrow_mut() = true;
+                            }
+                        })
+                        .build(ui);
+
+                        if requires_payment_passphrase {
+                            TextEditor::new(
+                                &mut this.context.payment_secret,
+                                &mut this.focus,
+                                Focus::PaymentSecret,
+                                |ui, text| {
+                                    ui.label(RichText::new(i18n("Enter your BIP39 passphrase")).size(12.).raised());
+                                    ui.add_sized(theme_style().panel_editor_size, TextEdit::singleline(text)
+                                        .vertical_align(Align::Center)
+                                        .password(true))
+                                },
+                            ).submit(|text,_focus| {
+                                if !text.is_empty() {
+                                    *submit.borrow_mut() = true;
+                                }
+                            })
+                            .build(ui);
+                        }
+                    })
+                    .with_footer(|this,ui| {
+                        let enabled = !this.context.wallet_secret.is_empty() && (!requires_payment_passphrase || !this.context.payment_secret.is_empty());
+                        if ui.large_button_enabled(enabled,i18n("Continue")).clicked() {
+                            *submit.borrow_mut() = true;
+                        }
+                    })
+                    .render(ui);
+
+                if *submit.borrow() {
+                    self.focus.clear();
+                    self.state = State::Processing { operation };
+                    self.spawn_operation(core, operation);
+                }
+            }
+
+            State::Processing { operation } => {
+
+                let pskt_result = Payload::<Result<PsktResult>>::new("pskt_result");
+
+                Panel::new(self)
+                    .with_caption(i18n("Processing..."))
+                    .with_close_enabled(false, |_|{
+                    })
+                    .with_body(|_this, ui| {
+                        ui.add_space(64.);
+                        ui.add(egui::Spinner::new().size(92.));
+                    })
+                    .render(ui);
+
+                if let Some(result) = pskt_result.take() {
+                    self.state = match result {
+                        Ok(PsktResult::Bundle(pskb)) => {
+                            State::Export { pskb : Arc::new(pskb) }
+                        }
+                        Ok(PsktResult::Broadcast(transaction_ids)) => {
+                            State::Finish { transaction_ids : Arc::new(transaction_ids) }
+                        }
+                        Err(error) => {
+                            log_info!("PSKT {operation:?} error: {error}");
+                            State::Error { error : Arc::new(error) }
+                        }
+                    };
+                }
+            }
+
+            State::Export { pskb } => {
+
+                Panel::new(self)
+                    .with_caption(i18n("Transaction Bundle"))
+                    .with_close_enabled(false, |_|{
+                    })
+                    .with_header(|_this, ui| {
+                        ui.label(i18n("Send this transaction bundle to the signing device or remaining cosigners,"));
+                        ui.label(i18n("or combine and broadcast it once enough signatures are collected."));
+                        ui.label(" ");
+                    })
+                    .with_body(|this, ui| {
+                        match BundleInfo::try_new(pskb.as_str()) {
+                            Ok(info) => { ui.label(info.to_string()); },
+                            Err(err) => { ui.label(RichText::new(err.to_string()).color(theme_color().error_color)); },
+                        }
+                        ui.label(" ");
+
+                        ui.label(RichText::new(format_partial_string(pskb.as_str(), Some(64))).font(FontId::monospace(10.)));
+                        ui.label(" ");
+
+                        let mut show_qr = false;
+                        ui.horizontal(|ui| {
+                            if ui.medium_button(format!("{QR_CODE} {}", i18n("QR Sequence"))).clicked() {
+                                show_qr = true;
+                            }
+
+                            if ui.medium_button(format!("{CLIPBOARD_TEXT} {}", i18n("Copy to clipboard"))).clicked() {
+                                ui.ctx().copy_text(pskb.to_string());
+                                runtime().notify_clipboard(i18n("Transaction bundle copied to clipboard"));
+                            }
+
+                            #[cfg(not(target_arch = "wasm32"))]
+                            if ui.medium_button(format!("{FLOPPY_DISK} {}", i18n("Save to file"))).clicked() {
+                                if let Some(path) = rfd::FileDialog::new().set_file_name("transaction.pskb").save_file() {
+                                    if let Err(err) = std::fs::write(path, pskb.as_bytes()) {
+                                        runtime().error(err.to_string());
+                                    }
+                                }
+                            }
+                        });
+
+                        if show_qr {
+                            this.qr_frame = 0;
+                            this.context.show_qr = !this.context.show_qr;
+                        }
+
+                        if this.context.show_qr {
+                            ui.label(" ");
+                            this.render_qr_frames(ui, pskb.as_str());
+                        }
+                    })
+                    .with_footer(|this, ui| {
+                        if ui.large_button(i18n("Close")).clicked() {
+                            this.context.zeroize();
+                            this.state = State::Select;
+                        }
+                    })
+                    .render(ui);
+            }
+
+            State::Finish { transaction_ids } => {
+
+                Panel::new(self)
+                    .with_caption(i18n("Transaction Submitted"))
+                    .with_close_enabled(false, |_|{
+                    })
+                    .with_body(|_this, ui| {
+                        for transaction_id in transaction_ids.iter() {
+                            ui.label(RichText::new(transaction_id.to_string()).font(FontId::monospace(12.)));
+                        }
+                    })
+                    .with_footer(|this, ui| {
+                        if ui.large_button(i18n("Close")).clicked() {
+                            this.context.zeroize();
+                            this.state = State::Select;
+                            core.select::<modules::AccountManager>();
+                        }
+                    })
+                    .render(ui);
+            }
+
+            State::Error { error } => {
+
+                Panel::new(self)
+                    .with_caption(i18n("Error"))
+                    .with_close_enabled(false, |_|{
+                    })
+                    .with_body(|_this, ui| {
+                        ui.label(RichText::new(error.to_string()).color(theme_color().error_color));
+                    })
+                    .with_footer(|this, ui| {
+                        if ui.large_button(i18n("Restart")).clicked() {
+                            this.context.zeroize();
+                            this.state = State::Select;
+                        }
+                    })
+                    .render(ui);
+            }
+        }
+    }
+}
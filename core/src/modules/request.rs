@@ -1,5 +1,7 @@
 use crate::imports::*;
-use egui_phosphor::light::CLIPBOARD_TEXT;
+use egui_phosphor::light::{CLIPBOARD_TEXT, TRASH};
+use kaspa_wallet_core::api::NewAddressKind;
+use workflow_core::time::unixtime_to_locale_string;
 // use kaspa_rpc_core::hash;
 use std::{borrow::Cow, collections::hash_map::Entry};
 pub use xxhash_rust::xxh3::xxh3_64;
@@ -12,13 +14,7 @@ pub struct RequestUri {
 
 impl std::fmt::Display for RequestUri {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut uri = self.address.clone();
-        if let Some(amount_sompi) = self.amount_sompi {
-            uri = format!("{}?amount={}", uri, sompi_to_kaspa(amount_sompi));
-        }
-        if let Some(label) = self.label.as_ref() {
-            uri = format!("{}?label={}", uri, label);
-        }
+        let uri = crate::invoices::payment_request_uri(&self.address, self.amount_sompi, self.label.as_deref());
         write!(f, "{}", uri)
     }
 }
@@ -31,6 +27,8 @@ pub struct Request {
     amount : String,
     amount_sompi : Option<u64>,
     label : String,
+    expiry : InvoiceExpiry,
+    invoice : Option<Invoice>,
     error : Option<String>,
 }
 
@@ -43,6 +41,8 @@ impl Request {
             amount : String::default(),
             amount_sompi : None,
             label : String::default(),
+            expiry : InvoiceExpiry::default(),
+            invoice : None,
             error : None,
         }
     }
@@ -127,6 +127,111 @@ impl Request {
 
     pub fn select(&mut self, account : &Account) {
         self.account = Some(account.clone());
+        self.invoice = None;
+    }
+
+    fn create_invoice(&mut self, core : &mut Core) {
+        let Some(account) = self.account.as_ref() else { return; };
+
+        let account_id = account.id();
+        let network = core.network();
+        let amount_sompi = self.amount_sompi;
+        let label = self.label.is_not_empty().then_some(self.label.clone());
+        let expiry = self.expiry;
+        let invoices = core.invoices.clone();
+
+        let invoice_result = Payload::<Result<Invoice>>::new("invoice_create_result");
+        spawn_with_result(&invoice_result, async move {
+            let address = runtime()
+                .wallet()
+                .accounts_create_new_address(account_id, NewAddressKind::Receive)
+                .await
+                .map_err(|err|Error::custom(i18n_args("Failed to create new address: {err}",&[("err",err.to_string())])))?
+                .address;
+
+            let invoice = Invoice::new(account_id, network, &address, amount_sompi, label, expiry);
+            invoices.insert(invoice.clone());
+            runtime().request_repaint();
+            Ok(invoice)
+        });
+    }
+
+    fn render_invoice(&mut self, ui: &mut Ui, invoice : &Invoice) {
+        ui.label(RichText::new(invoice.name()).strong());
+        ui.label(format!("{}: {}", i18n("Status"), invoice.status));
+        if let Some(amount_sompi) = invoice.amount_sompi {
+            ui.label(i18n_args("Expected: {amount} KAS", &[("amount", sompi_to_kaspa_string(amount_sompi))]));
+        }
+        ui.label(i18n_args("Received: {amount} KAS", &[("amount", sompi_to_kaspa_string(invoice.received_sompi))]));
+        if let Some(expires) = invoice.expires {
+            ui.label(i18n_args("Expires: {time}", &[("time", unixtime_to_locale_string(expires))]));
+        }
+        ui.label(" ");
+
+        let request_uri = invoice.request_uri();
+        let address = invoice.address.clone();
+        let (qr_uri, qr_bytes) = self.qr(request_uri.as_str());
+
+        let response = ui.add(Label::new(format!("Address: {} {CLIPBOARD_TEXT}", format_address_string(address.as_str(), Some(12)))).sense(Sense::click()))
+        .on_hover_ui_at_pointer(|ui|{
+            ui.vertical(|ui|{
+                ui.label(i18n("Click to copy address to clipboard"));
+            });
+        });
+
+        if response.clicked() {
+            ui.ctx().copy_text(address);
+            runtime().notify_clipboard(i18n("Address copied to clipboard"));
+        }
+
+        ui.label(" ");
+
+        ui.add(
+            Image::new(ImageSource::Bytes { uri : Cow::Owned(qr_uri), bytes: qr_bytes })
+            .fit_to_original_size(1.0)
+            .texture_options(TextureOptions::NEAREST)
+        );
+    }
+
+    fn render_invoice_list(&mut self, core : &mut Core, ui: &mut Ui) {
+        let Some(account) = self.account.as_ref() else { return; };
+        let invoices = core.invoices.account_invoices(&account.id());
+        if invoices.is_empty() {
+            return;
+        }
+
+        let mut remove = None;
+
+        CollapsingHeader::new(i18n("Invoices"))
+            .default_open(true)
+            .show(ui, |ui| {
+                for invoice in invoices.iter() {
+                    let color = match invoice.status {
+                        InvoiceStatus::Pending => theme_color().default_color,
+                        InvoiceStatus::PartiallyPaid => theme_color().warning_color,
+                        InvoiceStatus::Paid => theme_color().ack_color,
+                        InvoiceStatus::Expired => theme_color().nack_color,
+                    };
+
+                    ui.horizontal(|ui| {
+                        let amount = invoice.amount_sompi.map(sompi_to_kaspa_string).unwrap_or_else(|| "---".to_string());
+                        let text = format!("{} • {} KAS • {}", invoice.name(), amount, invoice.status);
+                        if ui.add(Label::new(RichText::new(text).color(color)).sense(Sense::click())).clicked() {
+                            self.invoice = Some(invoice.clone());
+                        }
+                        if ui.small_button(TRASH).on_hover_text(i18n("Delete invoice")).clicked() {
+                            remove = Some(invoice.id.clone());
+                        }
+                    });
+                }
+            });
+
+        if let Some(id) = remove {
+            if self.invoice.as_ref().map(|invoice| invoice.id == id).unwrap_or(false) {
+                self.invoice = None;
+            }
+            core.invoices.remove(&id);
+        }
     }
 
 }
@@ -139,6 +244,7 @@ impl ModuleT for Request {
 
     fn deactivate(&mut self, _core: &mut Core) {
         self.account = None;
+        self.invoice = None;
         self.error = None;
         self.qr.clear();
     }
@@ -168,6 +274,33 @@ impl ModuleT for Request {
             .with_body(|this, ui| {
 
 
+                let invoice_result = Payload::<Result<Invoice>>::new("invoice_create_result");
+                if let Some(result) = invoice_result.take() {
+                    match result {
+                        Ok(invoice) => {
+                            this.invoice = Some(invoice);
+                            this.error = None;
+                        }
+                        Err(err) => {
+                            this.error = Some(err.to_string());
+                        }
+                    }
+                }
+
+                // refresh the displayed invoice so that status updates are reflected
+                if let Some(invoice) = this.invoice.as_ref().and_then(|invoice| core.invoices.get(&invoice.id)) {
+                    this.render_invoice(ui, &invoice);
+
+                    ui.label(" ");
+                    if ui.medium_button(i18n("New Request")).clicked() {
+                        this.invoice = None;
+                    }
+                    ui.label(" ");
+
+                    this.render_invoice_list(core, ui);
+                    return;
+                }
+
                 if let Some(account) = this.account.as_ref() {
                     let address = account.receive_address().to_string();
                     let label = this.label.is_not_empty().then_some(this.label.clone());
@@ -204,6 +337,22 @@ impl ModuleT for Request {
                     }
                 }
 
+                ui.label("");
+                ui.label(i18n("Enter a label (optional)"));
+                ui.add_sized(
+                    theme_style().panel_editor_size,
+                    TextEdit::singleline(&mut this.label)
+                        .vertical_align(Align::Center),
+                );
+
+                ui.label("");
+                ui.label(i18n("Invoice expires in"));
+                ui.horizontal_wrapped(|ui| {
+                    for expiry in InvoiceExpiry::list() {
+                        ui.selectable_value(&mut this.expiry, expiry, expiry.to_string());
+                    }
+                });
+
                 if let Some(error) = this.error.as_ref() {
                     ui.label("");
                     ui.colored_label(error_color(), error);
@@ -211,6 +360,14 @@ impl ModuleT for Request {
 
                 ui.label(" ");
 
+                if ui.medium_button_enabled(!invoice_result.is_pending() && this.error.is_none(), i18n("Create Invoice")).clicked() {
+                    this.create_invoice(core);
+                }
+
+                ui.label(" ");
+
+                this.render_invoice_list(core, ui);
+
             })
             .with_footer(|_ctx, ui| {
                 if ui.large_button(i18n("Close")).clicked() {
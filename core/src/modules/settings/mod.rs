@@ -1,4 +1,5 @@
 use crate::imports::*;
+use crate::runtime::services::{CoinMarketCapSettings, CustomEndpointSettings, MarketDataProviderKind};
 
 pub struct Settings {
     #[allow(dead_code)]
@@ -7,6 +8,10 @@ pub struct Settings {
     wrpc_borsh_network_interface : NetworkInterfaceEditor,
     wrpc_json_network_interface : NetworkInterfaceEditor,
     grpc_network_interface : NetworkInterfaceEditor,
+    coinmarketcap : CoinMarketCapSettings,
+    custom_endpoint : CustomEndpointSettings,
+    alert_editor : AlertEditor,
+    metric_rule_editor : MetricRuleEditor,
     reset_settings : bool,
 }
 
@@ -18,6 +23,10 @@ impl Settings {
             wrpc_borsh_network_interface : NetworkInterfaceEditor::default(),
             wrpc_json_network_interface : NetworkInterfaceEditor::default(),
             grpc_network_interface : NetworkInterfaceEditor::default(),
+            coinmarketcap : CoinMarketCapSettings::default(),
+            custom_endpoint : CustomEndpointSettings::default(),
+            alert_editor : AlertEditor::default(),
+            metric_rule_editor : MetricRuleEditor::default(),
             reset_settings : false,
         }
     }
@@ -28,12 +37,19 @@ impl Settings {
         self.wrpc_borsh_network_interface = NetworkInterfaceEditor::from(&self.settings.node.wrpc_borsh_network_interface);
         self.wrpc_json_network_interface = NetworkInterfaceEditor::from(&self.settings.node.wrpc_json_network_interface);
         self.grpc_network_interface = NetworkInterfaceEditor::from(&self.settings.node.grpc_network_interface);
+        self.coinmarketcap = self.settings.market.coinmarketcap.clone();
+        self.custom_endpoint = self.settings.market.custom.clone();
     }
 
     pub fn change_current_network(&mut self, network : Network) {
         self.settings.node.network = network;
     }
 
+    pub fn change_storage_root(&mut self, storage_root : &str) {
+        self.settings.node.kaspad_daemon_storage_folder_enable = true;
+        self.settings.node.kaspad_daemon_storage_folder = storage_root.to_string();
+    }
+
     pub fn render_remote_settings(_core: &mut Core, ui: &mut Ui, settings : &mut NodeSettings) -> Option<&'static str> {
 
         let mut node_settings_error = None;
@@ -369,6 +385,11 @@ impl Settings {
                                                         self.grpc_network_interface.ui(ui);
                                                     });
                                             }
+
+                                            ui.checkbox(&mut self.settings.node.enable_unsafe_rpc, i18n("Enable peer management (unsafe RPC)"));
+                                            if self.settings.node.enable_unsafe_rpc && self.settings.node.is_rpc_public() {
+                                                ui.colored_label(theme_color().warning_color, i18n("Unsafe RPC is not enabled while the node RPC is publicly accessible."));
+                                            }
                                         });
 
                                 });
@@ -387,6 +408,11 @@ impl Settings {
 
             }); // Kaspa p2p Network & Node Connection
 
+            #[cfg(not(target_arch = "wasm32"))]
+            if node_settings_error.is_none() && core.storage.is_relocating() && self.settings.node.compare(&core.settings.node).is_some() {
+                node_settings_error = Some(i18n("Node settings can not be changed while a data folder is being moved"));
+            }
+
             if let Some(error) = node_settings_error {
                 ui.add_space(4.);
                 ui.label(
@@ -584,6 +610,184 @@ impl Settings {
 
     }
 
+    fn render_market_settings(&mut self, core: &mut Core, ui: &mut Ui) {
+        let market_monitor_service = self.runtime.market_monitor_service().clone();
+        let market = &mut self.settings.market;
+
+        ui.horizontal(|ui| {
+            ui.label(i18n("Market Data Provider:"));
+            egui::ComboBox::from_id_salt("market_data_provider_selector")
+                .selected_text(market.provider.to_string())
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                    ui.set_min_width(60.0);
+                    MarketDataProviderKind::list().into_iter().for_each(|provider| {
+                        let text = provider.to_string();
+                        ui.selectable_value(&mut market.provider, provider, text);
+                    });
+                });
+        });
+
+        ui.checkbox(&mut market.failover, i18n("Use other providers if the selected provider is unavailable"));
+
+        if market.provider == MarketDataProviderKind::CoinMarketCap {
+            Self::render_coinmarketcap_settings(&mut self.coinmarketcap, &mut market.coinmarketcap, ui);
+        }
+
+        if market.provider == MarketDataProviderKind::Custom {
+            Self::render_custom_endpoint_settings(&mut self.custom_endpoint, &mut market.custom, ui);
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(i18n("Primary Currency:"));
+            egui::ComboBox::from_id_salt("market_primary_currency_selector")
+                .selected_text(market.primary_currency.to_uppercase())
+                .show_ui(ui, |ui| {
+                    ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                    ui.set_min_width(60.0);
+                    market.currencies.clone().into_iter().for_each(|currency| {
+                        let text = currency.to_uppercase();
+                        ui.selectable_value(&mut market.primary_currency, currency, text);
+                    });
+                });
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(i18n("Currencies:"));
+            let mut remove = None;
+            for currency in market.currencies.iter() {
+                let enabled = market.currencies.len() > 1 && *currency != market.primary_currency;
+                if ui.add_enabled(enabled, Button::new(format!("{} {}", currency.to_uppercase(), egui_phosphor::light::X))).clicked() {
+                    remove = Some(currency.clone());
+                }
+            }
+            if let Some(currency) = remove {
+                market.currencies.retain(|existing| *existing != currency);
+            }
+        });
+
+        match market_monitor_service.available_currencies() {
+            Some(available_currencies) => {
+                let mut add = None;
+                egui::ComboBox::from_id_salt("market_add_currency_selector")
+                    .selected_text(i18n("Add Currency"))
+                    .show_ui(ui, |ui| {
+                        ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                        ui.set_min_width(60.0);
+                        available_currencies.iter()
+                            .filter(|descriptor| !market.currencies.contains(&descriptor.id))
+                            .for_each(|descriptor| {
+                                if ui.selectable_label(false, descriptor.name.as_str()).clicked() {
+                                    add = Some(descriptor.id.clone());
+                                }
+                            });
+                    });
+                if let Some(currency) = add {
+                    market.currencies.push(currency);
+                }
+            }
+            None => {
+                if ui.medium_button(i18n("Load Available Currencies")).clicked() {
+                    market_monitor_service.fetch_available_currencies();
+                }
+            }
+        }
+
+        CollapsingHeader::new(i18n("Alerts"))
+            .default_open(false)
+            .show(ui, |ui| {
+                let currencies = market.currencies.clone();
+                self.alert_editor.ui(ui, core, &currencies, &mut market.alerts);
+
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.checkbox(&mut market.desktop_notifications, i18n("Show alerts as desktop notifications"));
+            });
+
+        if self.settings.market != core.settings.market {
+            let provider_changed = self.settings.market.provider != core.settings.market.provider;
+            core.settings.market = self.settings.market.clone();
+            market_monitor_service.update_settings(&core.settings.market);
+            if provider_changed {
+                market_monitor_service.fetch_available_currencies();
+            }
+            core.store_settings();
+        }
+    }
+
+    fn render_coinmarketcap_settings(editor : &mut CoinMarketCapSettings, coinmarketcap : &mut CoinMarketCapSettings, ui : &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label(i18n("CoinMarketCap API Key:"));
+            ui.add(TextEdit::singleline(&mut editor.api_key).password(true).desired_width(320.).font(FontId::monospace(12.)));
+        });
+
+        if !editor.is_configured() {
+            ui.label(RichText::new(i18n("CoinMarketCap requires an API key from pro.coinmarketcap.com.")).color(theme_color().warning_color));
+        }
+
+        if editor != coinmarketcap {
+            ui.add_space(4.);
+            if let Some(response) = ui.confirm_medium_apply_cancel(Align::Max) {
+                match response {
+                    Confirm::Ack => {
+                        *coinmarketcap = editor.clone();
+                    },
+                    Confirm::Nack => {
+                        *editor = coinmarketcap.clone();
+                    }
+                }
+            }
+        }
+    }
+
+    fn render_custom_endpoint_settings(editor : &mut CustomEndpointSettings, custom : &mut CustomEndpointSettings, ui : &mut Ui) {
+        CollapsingHeader::new(i18n("Custom Endpoint"))
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.label(RichText::new(i18n("URLs may contain {currencies}, {currency} and {days} placeholders. Fields are located using JSON pointers that may contain {currency}, for example /data/{currency}/price.")).size(12.).raised());
+                ui.add_space(4.);
+
+                Grid::new("market_custom_endpoint_grid")
+                    .num_columns(2)
+                    .spacing([8.0, 4.0])
+                    .show(ui, |ui| {
+                        let fields = [
+                            (i18n("Price URL:"), &mut editor.price_url),
+                            (i18n("Price:"), &mut editor.price),
+                            (i18n("Market Cap:"), &mut editor.market_cap),
+                            (i18n("Volume:"), &mut editor.volume),
+                            (i18n("24h Change:"), &mut editor.change),
+                            (i18n("Price History URL:"), &mut editor.ohlc_url),
+                            (i18n("Price History:"), &mut editor.ohlc),
+                            (i18n("Currencies URL:"), &mut editor.currencies_url),
+                            (i18n("Currencies:"), &mut editor.currencies),
+                        ];
+                        for (label, value) in fields {
+                            ui.label(label);
+                            ui.add(TextEdit::singleline(value).desired_width(320.).font(FontId::monospace(12.)));
+                            ui.end_row();
+                        }
+                    });
+
+                if !editor.is_configured() {
+                    ui.label(RichText::new(i18n("Price URL and price field are required.")).color(theme_color().warning_color));
+                }
+
+                if editor != custom {
+                    ui.add_space(4.);
+                    if let Some(response) = ui.confirm_medium_apply_cancel(Align::Max) {
+                        match response {
+                            Confirm::Ack => {
+                                *custom = editor.clone();
+                            },
+                            Confirm::Nack => {
+                                *editor = custom.clone();
+                            }
+                        }
+                    }
+                }
+            });
+    }
+
     fn render_settings(
         &mut self,
         core: &mut Core,
@@ -606,6 +810,24 @@ impl Settings {
                             self.runtime.market_monitor_service().enable(core.settings.market_monitor);
                             core.store_settings();
                         }
+
+                        if self.settings.market_monitor {
+                            ui.indent("market_monitor_settings", |ui| {
+                                self.render_market_settings(core, ui);
+                            });
+                        }
+                    });
+
+                CollapsingHeader::new(i18n("Node Alerts"))
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        ui.label(RichText::new(i18n("Rules are evaluated on each node metrics update once the node is synced. Raised alerts are shown in the status bar.")).size(12.).raised());
+                        ui.add_space(4.);
+                        if self.metric_rule_editor.ui(ui, &mut self.settings.metrics_alerts.rules) {
+                            core.settings.metrics_alerts = self.settings.metrics_alerts.clone();
+                            self.runtime.metrics_service().update_alert_settings(&core.settings.metrics_alerts);
+                            core.store_settings();
+                        }
                     });
 
                 #[cfg(not(target_arch = "wasm32"))]
@@ -617,7 +839,89 @@ impl Settings {
                             self.runtime.update_monitor_service().enable(core.settings.update_monitor);
                             core.store_settings();
                         }
-                    });    
+                    });
+
+                #[cfg(not(target_arch = "wasm32"))]
+                CollapsingHeader::new(i18n("Wallet Bridge"))
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        ui.checkbox(&mut self.settings.bridge.enabled, i18n("Allow local web apps and scripts to connect"))
+                            .on_hover_text_at_pointer(i18n("Accepts wallet requests over a WebSocket on the local host. Each request must be approved in the application."));
+
+                        if self.settings.bridge.enabled {
+                            ui.indent("wallet_bridge_settings", |ui| {
+                                ui.horizontal(|ui| {
+                                    ui.label(i18n("Port:"));
+                                    ui.add(DragValue::new(&mut self.settings.bridge.port).range(1024..=65535));
+                                });
+
+                                ui.label(i18n("Pairing token:"));
+                                ui.horizontal(|ui| {
+                                    ui.label(RichText::new(self.settings.bridge.token.as_str()).font(FontId::monospace(12.)));
+                                    if ui.small_button(i18n("Copy")).clicked() {
+                                        ui.ctx().copy_text(self.settings.bridge.token.clone());
+                                        runtime().notify_clipboard(i18n("Copied to clipboard"));
+                                    }
+                                    if ui.small_button(i18n("Regenerate")).clicked() {
+                                        self.settings.bridge.token = BridgeSettings::generate_token();
+                                    }
+                                });
+                            });
+                        }
+
+                        if self.settings.bridge != core.settings.bridge {
+                            ui.add_space(4.);
+                            ui.label(RichText::new(i18n("Application must be restarted for this setting to take effect.")).color(theme_color().warning_color));
+                            ui.add_space(4.);
+                            if let Some(response) = ui.confirm_medium_apply_cancel(Align::Max) {
+                                match response {
+                                    Confirm::Ack => {
+                                        core.settings.bridge = self.settings.bridge.clone();
+                                        core.store_settings();
+                                    },
+                                    Confirm::Nack => {
+                                        self.settings.bridge = core.settings.bridge.clone();
+                                    }
+                                }
+                            }
+                        }
+                    });
+
+                #[cfg(not(target_arch = "wasm32"))]
+                CollapsingHeader::new(i18n("Metrics Exporter"))
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        ui.checkbox(&mut self.settings.metrics_exporter.enabled, i18n("Expose metrics to Prometheus"))
+                            .on_hover_text_at_pointer(i18n("Serves node and application metrics in the OpenMetrics format on the local host."));
+
+                        if self.settings.metrics_exporter.enabled {
+                            ui.indent("metrics_exporter_settings", |ui| {
+                                ui.horizontal(|ui| {
+                                    ui.label(i18n("Port:"));
+                                    ui.add(DragValue::new(&mut self.settings.metrics_exporter.port).range(1024..=65535));
+                                });
+                                ui.label(RichText::new(format!("http://127.0.0.1:{}/metrics", self.settings.metrics_exporter.port)).font(FontId::monospace(12.)));
+                                ui.checkbox(&mut self.settings.metrics_exporter.wallet_balances, i18n("Include wallet account balances"));
+                            });
+                        }
+
+                        if self.settings.metrics_exporter != core.settings.metrics_exporter {
+                            ui.add_space(4.);
+                            ui.label(RichText::new(i18n("Application must be restarted for this setting to take effect.")).color(theme_color().warning_color));
+                            ui.add_space(4.);
+                            if let Some(response) = ui.confirm_medium_apply_cancel(Align::Max) {
+                                match response {
+                                    Confirm::Ack => {
+                                        core.settings.metrics_exporter = self.settings.metrics_exporter.clone();
+                                        core.store_settings();
+                                    },
+                                    Confirm::Nack => {
+                                        self.settings.metrics_exporter = core.settings.metrics_exporter.clone();
+                                    }
+                                }
+                            }
+                        }
+                    });
             });
 
         CollapsingHeader::new(i18n("Network Fee Estimator"))
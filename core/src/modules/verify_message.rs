@@ -0,0 +1,120 @@
+use crate::imports::*;
+
+#[derive(Default)]
+struct Context {
+    address : String,
+    message : String,
+    signature : String,
+    result : Option<std::result::Result<bool, String>>,
+}
+
+pub struct VerifyMessage {
+    #[allow(dead_code)]
+    runtime: Runtime,
+    context : Context,
+}
+
+impl VerifyMessage {
+    pub fn new(runtime: Runtime) -> Self {
+        Self {
+            runtime,
+            context : Context::default(),
+        }
+    }
+
+    fn verify(&mut self) {
+        self.context.result = Some(
+            Address::try_from(self.context.address.trim())
+                .map_err(|err| i18n_args("Invalid address: {err}", &[("err", err.to_string())]))
+                .and_then(|address| {
+                    verify_personal_message(&address, self.context.message.as_str(), self.context.signature.as_str())
+                        .map_err(|err| err.to_string())
+                })
+        );
+    }
+}
+
+impl ModuleT for VerifyMessage {
+
+    fn style(&self) -> ModuleStyle {
+        ModuleStyle::Mobile
+    }
+
+    fn modal(&self) -> bool {
+        true
+    }
+
+    fn reset(&mut self, _core: &mut Core) {
+        self.context = Context::default();
+    }
+
+    fn render(
+        &mut self,
+        core: &mut Core,
+        _ctx: &egui::Context,
+        _frame: &mut eframe::Frame,
+        ui: &mut egui::Ui,
+    ) {
+
+        let back = Rc::new(RefCell::new(false));
+
+        Panel::new(self)
+            .with_caption(i18n("Verify Message"))
+            .with_back(|_this| {
+                *back.borrow_mut() = true;
+            })
+            .with_close_enabled(false, |_|{
+            })
+            .with_header(|_this, ui| {
+                ui.label(i18n("Verify that a message was signed by the owner of an address."));
+                ui.label(" ");
+            })
+            .with_body(|this, ui| {
+                let mut changed = false;
+
+                ui.label(RichText::new(i18n("Address")).size(12.).raised());
+                changed |= ui.add_sized(theme_style().panel_editor_size, TextEdit::singleline(&mut this.context.address)
+                    .vertical_align(Align::Center)).changed();
+
+                ui.add_space(8.);
+                ui.label(RichText::new(i18n("Message")).size(12.).raised());
+                changed |= ui.add_sized(theme_style().panel_editor_size * vec2(1.,3.), TextEdit::multiline(&mut this.context.message)).changed();
+
+                ui.add_space(8.);
+                ui.label(RichText::new(i18n("Signature")).size(12.).raised());
+                changed |= ui.add_sized(theme_style().panel_editor_size, TextEdit::singleline(&mut this.context.signature)
+                    .font(FontId::monospace(10.))
+                    .vertical_align(Align::Center)).changed();
+
+                if changed {
+                    this.context.result = None;
+                }
+
+                ui.label(" ");
+                match this.context.result.as_ref() {
+                    Some(Ok(true)) => {
+                        ui.label(RichText::new(i18n("The signature is valid")).color(theme_color().ack_color));
+                    }
+                    Some(Ok(false)) => {
+                        ui.label(RichText::new(i18n("The signature is not valid")).color(theme_color().nack_color));
+                    }
+                    Some(Err(err)) => {
+                        ui.label(RichText::new(err).color(theme_color().error_color));
+                    }
+                    None => { }
+                }
+            })
+            .with_footer(|this, ui| {
+                let enabled = this.context.address.is_not_empty() && this.context.signature.is_not_empty();
+                if ui.large_button_enabled(enabled, i18n("Verify")).clicked() {
+                    this.verify();
+                }
+            })
+            .render(ui);
+
+        if *back.borrow() {
+            self.context = Context::default();
+            core.back();
+        }
+    }
+}
@@ -78,6 +78,7 @@ struct Context {
     import_with_bip39_passphrase : bool,
     import_legacy : bool,
     import_advanced : bool,
+    import_multisig : Option<MultisigWalletImport>,
     wallet_file_data: Option<WalletFileData>
 }
 
@@ -100,6 +101,9 @@ impl Zeroize for Context {
         self.decrypt_wallet_secret.zeroize();
         self.import_legacy.zeroize();
         self.import_advanced.zeroize();
+        if let Some(mut multisig) = self.import_multisig.take() {
+            multisig.zeroize();
+        }
         self.payment_secret_submitted = false;
     }
 }
@@ -1044,16 +1048,19 @@ impl ModuleT for WalletCreate {
                                 Ok(WalletFileDecryptedData::Legacy(key_data.mnemonic.clone()))
                             }
                             WalletFileData::GoWallet(data)=>{
-                                let mnemonic = match data {
+                                match data {
                                     WalletType::SingleV0(data)=>{
-                                        kaspa_wallet_core::compat::gen1::decrypt_mnemonic(
+                                        let mnemonic = kaspa_wallet_core::compat::gen1::decrypt_mnemonic(
                                             data.num_threads,
                                             data.encrypted_mnemonic,
                                             import_secret.as_ref()
-                                        )?
+                                        )?;
+                                        Ok(WalletFileDecryptedData::Core(mnemonic))
+                                    }
+                                    WalletType::MultiV0(data) | WalletType::MultiV1(data)=>{
+                                        Ok(WalletFileDecryptedData::Multisig(data.decrypt(&import_secret).await?))
                                     }
-                                };
-                                Ok(WalletFileDecryptedData::Core(mnemonic))
+                                }
                             }
                             WalletFileData::Core(_data)=>{
                                 Err(Error::custom("Core wallet import not supported yet."))
@@ -1094,6 +1101,12 @@ impl ModuleT for WalletCreate {
                                     }
                                     
                                 }
+                                WalletFileDecryptedData::Multisig(multisig)=>{
+                                    self.context.import_legacy = false;
+                                    self.context.import_with_bip39_passphrase = false;
+                                    self.context.import_multisig = Some(multisig);
+                                    self.state = State::WalletName;
+                                }
                             }
                         }
                         Err(err) => {
@@ -1108,6 +1121,8 @@ impl ModuleT for WalletCreate {
                 let import_legacy = self.context.import_legacy;
                 let caption = if import_legacy {
                     i18n("Importing Legacy Wallet")
+                }else if self.context.import_multisig.is_some() {
+                    i18n("Importing Multisig Wallet")
                 }else{
                     i18n("Importing Wallet")
                 };
@@ -1140,6 +1155,13 @@ impl ModuleT for WalletCreate {
 
                         let wallet_secret = Secret::from(args.wallet_secret.as_str());
                         let payment_secret = args.import_with_bip39_passphrase.then_some(Secret::from(args.payment_secret.as_str()));
+
+                        if let Some(multisig) = args.import_multisig.take() {
+                            let account_descriptor = import_multisig_wallet(&wallet, &args, wallet_secret, multisig).await;
+                            args.zeroize();
+                            return Ok(vec![account_descriptor?]);
+                        }
+
                         let mnemonic = Secret::from(sanitize_mnemonic(args.import_private_key_mnemonic.as_str()));
 
 
@@ -1404,3 +1426,51 @@ impl ModuleT for WalletCreate {
 
     }
 }
+
+/// Creates a new wallet holding the local cosigner keys of an imported
+/// multisig wallet file and a multisig account bound to all cosigner keys.
+async fn import_multisig_wallet(wallet : &Arc<dyn WalletApi>, args : &Context, wallet_secret : Secret, mut multisig : MultisigWalletImport) -> Result<AccountDescriptor> {
+
+    wallet.clone().batch().await?;
+
+    let wallet_args = WalletCreateArgs::new(
+        args.wallet_name.is_not_empty().then_some(args.wallet_name.clone()),
+        args.wallet_filename.is_not_empty().then_some(args.wallet_filename.clone()),
+        EncryptionKind::XChaCha20Poly1305,
+        args.enable_phishing_hint.then_some(args.phishing_hint.as_str().into()),
+        false
+    );
+
+    wallet.clone().wallet_create(wallet_secret.clone(), wallet_args).await?;
+
+    let mut prv_key_data_ids = Vec::with_capacity(multisig.mnemonics.len());
+    for mnemonic in multisig.mnemonics.iter() {
+        let prv_key_data_create_args = PrvKeyDataCreateArgs::new(
+            None,
+            None,
+            Secret::from(sanitize_mnemonic(mnemonic.as_str())),
+            PrvKeyDataVariantKind::Mnemonic,
+        );
+
+        let prv_key_data_id = wallet.clone().prv_key_data_create(wallet_secret.clone(), prv_key_data_create_args).await?;
+        prv_key_data_ids.push(prv_key_data_id);
+    }
+
+    // the account is created on the local wallet to preserve the
+    // cosigner index of the wallet file (see MultisigWalletImport)
+    let local_wallet = wallet.clone().downcast_arc::<CoreWallet>().map_err(|_| Error::WalletIsNotLocal)?;
+    let account = multisig.create_account(
+        &local_wallet,
+        args.account_name.is_not_empty().then_some(args.account_name.clone()),
+        prv_key_data_ids,
+    ).await?;
+
+    local_wallet.store().as_account_store()?.store_single(&account.to_storage()?, None).await?;
+    let account_descriptor = account.descriptor()?;
+
+    wallet.clone().flush(wallet_secret).await?;
+
+    multisig.zeroize();
+
+    Ok(account_descriptor)
+}
@@ -1,6 +1,8 @@
 use kaspa_wallet_core::account::{
-    BIP32_ACCOUNT_KIND, KEYPAIR_ACCOUNT_KIND, LEGACY_ACCOUNT_KIND, MULTISIG_ACCOUNT_KIND,
+    BIP32_ACCOUNT_KIND, BIP32_WATCH_ACCOUNT_KIND, KEYPAIR_ACCOUNT_KIND, LEGACY_ACCOUNT_KIND,
+    MULTISIG_ACCOUNT_KIND,
 };
+use kaspa_wallet_core::storage::AssocPrvKeyDataIds;
 
 use crate::imports::*;
 
@@ -136,6 +138,12 @@ impl Account {
         &self.inner.account_kind
     }
 
+    /// Watch-only accounts have no associated private key data
+    /// and can not sign transactions.
+    pub fn is_watch_only(&self) -> bool {
+        matches!(self.descriptor().prv_key_data_ids, AssocPrvKeyDataIds::None)
+    }
+
     pub fn balance(&self) -> Option<Balance> {
         self.inner.balance.lock().unwrap().clone()
     }
@@ -249,6 +257,7 @@ impl DescribeAccount for AccountKind {
             LEGACY_ACCOUNT_KIND => ("Legacy Account", "KDX, PWA (kaspanet.io)"),
             BIP32_ACCOUNT_KIND => ("Kaspa Core BIP32", "kaspawallet, kaspium"),
             MULTISIG_ACCOUNT_KIND => ("Multi-Signature", ""),
+            BIP32_WATCH_ACCOUNT_KIND => ("Watch-Only", "xpub, kpub"),
             KEYPAIR_ACCOUNT_KIND => ("Keypair", "secp256k1"),
             _ => ("", ""),
         }
@@ -41,6 +41,72 @@ impl BlockDagGraphSettings {
     }
 }
 
+/// GHOSTDAG classification of a block merged by the selected parent chain.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergeState {
+    /// Block is not (yet) merged by a chain block
+    #[default]
+    Unmerged,
+    /// Block is in the blue merge set of a chain block
+    Blue,
+    /// Block is in the red merge set of a chain block
+    Red,
+}
+
+/// Graph changes resulting from a virtual chain change.
+#[derive(Debug, Default, PartialEq)]
+pub struct ChainUpdate {
+    /// Chain block flag of blocks removed from or added to the selected parent chain
+    pub vspc: AHashMap<KaspaHash, bool>,
+    /// Merge state of blocks merged by the removed or added chain blocks
+    pub merge: AHashMap<KaspaHash, MergeState>,
+}
+
+impl ChainUpdate {
+    /// Blocks merged by removed chain blocks become unmerged unless an added
+    /// chain block merges them again; `merge_set` resolves the blue and red
+    /// merge sets of a chain block if the block is known.
+    pub fn new<'a>(
+        removed: &[KaspaHash],
+        added: &[KaspaHash],
+        merge_set: impl Fn(&KaspaHash) -> Option<(&'a [KaspaHash], &'a [KaspaHash])>,
+    ) -> Self {
+        let mut update = Self::default();
+
+        for hash in removed.iter() {
+            update.vspc.insert(*hash, false);
+            if let Some((blues, reds)) = merge_set(hash) {
+                blues.iter().chain(reds.iter()).for_each(|hash| {
+                    update.merge.insert(*hash, MergeState::Unmerged);
+                });
+            }
+        }
+
+        for hash in added.iter() {
+            update.vspc.insert(*hash, true);
+            if let Some((blues, reds)) = merge_set(hash) {
+                blues.iter().for_each(|hash| {
+                    update.merge.insert(*hash, MergeState::Blue);
+                });
+                reds.iter().for_each(|hash| {
+                    update.merge.insert(*hash, MergeState::Red);
+                });
+            }
+        }
+
+        update
+    }
+}
+
+/// Block position and state produced by [`DaaBucket::render`].
+pub struct DagBlockView {
+    pub block: Arc<RpcBlock>,
+    pub point: PlotPoint,
+    pub vspc: bool,
+    pub merge: MergeState,
+    pub settled: bool,
+}
+
 #[derive(Clone, Debug)]
 pub struct DagBlock {
     pub data: Arc<RpcBlock>,
@@ -48,6 +114,7 @@ pub struct DagBlock {
     pub dst_y: f64,
     pub offset_y: f64,
     vspc: bool,
+    merge: MergeState,
     settled: bool,
 }
 
@@ -60,6 +127,7 @@ impl DagBlock {
             dst_y: y,
             offset_y: y,
             vspc: false,
+            merge: MergeState::default(),
             settled: false,
         }
     }
@@ -106,6 +174,12 @@ impl DaaBucket {
         self.update(settings);
     }
 
+    pub fn update_merge(&mut self, hash: KaspaHash, merge: MergeState) {
+        if let Some(block) = self.blocks.iter_mut().find(|b| b.data.header.hash == hash) {
+            block.merge = merge;
+        }
+    }
+
     pub fn update(&mut self, settings: &BlockDagGraphSettings) {
         self.blocks
             // .sort_by(|a, b| a.src_y.partial_cmp(&b.src_y).unwrap());
@@ -183,7 +257,7 @@ impl DaaBucket {
         self.update(settings);
     }
 
-    pub fn render(&mut self) -> Vec<(Arc<RpcBlock>, PlotPoint, bool, bool)> {
+    pub fn render(&mut self) -> Vec<DagBlockView> {
         self.blocks
             .iter_mut()
             .map(|block| {
@@ -197,8 +271,147 @@ impl DaaBucket {
                         block.settled = true;
                     }
                 }
-                (block.data.clone(), [x, y].into(), block.vspc, block.settled)
+                DagBlockView {
+                    block: block.data.clone(),
+                    point: [x, y].into(),
+                    vspc: block.vspc,
+                    merge: block.merge,
+                    settled: block.settled,
+                }
             })
             .collect::<Vec<_>>()
     }
 }
+
+/// Decoded coinbase transaction payload.
+///
+/// The payload is serialized as `blue score (u64) | subsidy (u64) |
+/// script version (u16) | script length (u8) | script | extra data`,
+/// where the extra data typically carries the miner and node version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoinbasePayload {
+    pub blue_score: u64,
+    pub subsidy: u64,
+    pub script_version: u16,
+    pub script: Vec<u8>,
+    pub extra_data: Vec<u8>,
+}
+
+impl CoinbasePayload {
+    pub fn try_parse(payload: &[u8]) -> Option<Self> {
+        let blue_score = u64::from_le_bytes(payload.get(0..8)?.try_into().ok()?);
+        let subsidy = u64::from_le_bytes(payload.get(8..16)?.try_into().ok()?);
+        let script_version = u16::from_le_bytes(payload.get(16..18)?.try_into().ok()?);
+        let script_len = *payload.get(18)? as usize;
+        let script = payload.get(19..19 + script_len)?.to_vec();
+        let extra_data = payload[19 + script_len..].to_vec();
+
+        Some(Self {
+            blue_score,
+            subsidy,
+            script_version,
+            script,
+            extra_data,
+        })
+    }
+
+    /// Extra data as text with non-printable characters removed.
+    pub fn extra_data_text(&self) -> String {
+        String::from_utf8_lossy(&self.extra_data)
+            .chars()
+            .filter(|c| !c.is_control() && *c != char::REPLACEMENT_CHARACTER)
+            .collect()
+    }
+}
+
+/// Block DAG notification recorded for replay.
+#[derive(Clone, Debug)]
+pub enum DagEvent {
+    Block(Arc<RpcBlock>),
+    ChainChanged {
+        removed: Arc<Vec<KaspaHash>>,
+        added: Arc<Vec<KaspaHash>>,
+    },
+}
+
+/// Ring buffer of recent block DAG events keyed by the time of arrival
+/// (unix time in milliseconds), retaining events for a limited duration.
+pub struct DagHistory {
+    events: VecDeque<(f64, DagEvent)>,
+    retention: Duration,
+    capacity: usize,
+}
+
+impl DagHistory {
+    pub fn new(retention: Duration, capacity: usize) -> Self {
+        Self {
+            events: VecDeque::new(),
+            retention,
+            capacity,
+        }
+    }
+
+    pub fn retention(&self) -> Duration {
+        self.retention
+    }
+
+    pub fn set_retention(&mut self, retention: Duration) {
+        self.retention = retention;
+        if let Some((last, _)) = self.events.back() {
+            self.prune(*last);
+        }
+    }
+
+    pub fn push(&mut self, timestamp: f64, event: DagEvent) {
+        self.events.push_back((timestamp, event));
+        self.prune(timestamp);
+    }
+
+    fn prune(&mut self, timestamp: f64) {
+        let first = timestamp - self.retention.as_millis() as f64;
+        while let Some((oldest, _)) = self.events.front() {
+            if *oldest < first || self.events.len() > self.capacity {
+                self.events.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Timestamps of the oldest and the most recent event.
+    pub fn range(&self) -> Option<(f64, f64)> {
+        let (first, _) = self.events.front()?;
+        let (last, _) = self.events.back()?;
+        Some((*first, *last))
+    }
+
+    /// Events received after `from` and up to (including) `to`.
+    pub fn between(&self, from: f64, to: f64) -> impl Iterator<Item = &DagEvent> {
+        let start = self
+            .events
+            .partition_point(|(timestamp, _)| *timestamp <= from);
+        let end = self
+            .events
+            .partition_point(|(timestamp, _)| *timestamp <= to);
+        self.events
+            .range(start..end.max(start))
+            .map(|(_, event)| event)
+    }
+
+    /// Events received up to (including) `to`.
+    pub fn until(&self, to: f64) -> impl Iterator<Item = &DagEvent> {
+        self.between(f64::NEG_INFINITY, to)
+    }
+}
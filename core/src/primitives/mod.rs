@@ -3,6 +3,9 @@ pub use account::{Account, AccountCollection, AccountSelectorButtonExtension};
 pub mod transaction;
 pub use transaction::{Transaction, TransactionCollection};
 pub mod block;
-pub use block::{BlockDagGraphSettings, DaaBucket, DagBlock};
+pub use block::{
+    BlockDagGraphSettings, ChainUpdate, CoinbasePayload, DaaBucket, DagBlock, DagBlockView,
+    DagEvent, DagHistory, MergeState,
+};
 pub mod descriptor;
 pub use descriptor::*;
@@ -41,6 +41,8 @@ pub struct Inner {
 
     // #[cfg(not(feature = "lean"))]
     metrics_service: Arc<MetricsService>,
+    #[cfg(not(target_arch = "wasm32"))]
+    metrics_exporter_service: Arc<MetricsExporterService>,
     #[cfg(not(feature = "lean"))]
     block_dag_monitor_service: Arc<BlockDagMonitorService>,
 }
@@ -91,6 +93,11 @@ impl Runtime {
         ));
 
         let metrics_service = Arc::new(MetricsService::new(application_events.clone(), settings));
+        #[cfg(not(target_arch = "wasm32"))]
+        let metrics_exporter_service = Arc::new(MetricsExporterService::new(
+            application_events.clone(),
+            settings,
+        ));
         cfg_if! {
             if #[cfg(not(feature = "lean"))] {
                 let block_dag_monitor_service = Arc::new(BlockDagMonitorService::new(
@@ -114,6 +121,8 @@ impl Runtime {
             update_monitor_service.clone(),
             // #[cfg(not(feature = "lean"))]
             metrics_service.clone(),
+            #[cfg(not(target_arch = "wasm32"))]
+            metrics_exporter_service.clone(),
             #[cfg(not(feature = "lean"))]
             block_dag_monitor_service.clone(),
         ]);
@@ -135,6 +144,8 @@ impl Runtime {
                 adaptor,
                 // #[cfg(not(feature = "lean"))]
                 metrics_service,
+                #[cfg(not(target_arch = "wasm32"))]
+                metrics_exporter_service,
                 #[cfg(not(feature = "lean"))]
                 block_dag_monitor_service,
             }),
@@ -246,6 +257,11 @@ impl Runtime {
         &self.inner.metrics_service
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn metrics_exporter_service(&self) -> &Arc<MetricsExporterService> {
+        &self.inner.metrics_exporter_service
+    }
+
     cfg_if! {
         if #[cfg(not(feature = "lean"))] {
 
@@ -4,7 +4,6 @@ struct Inner<T>
 where
     T: Send,
 {
-    #[allow(dead_code)]
     id: String,
     payload: Mutex<Option<T>>,
     pending: AtomicBool,
@@ -90,6 +89,14 @@ where
         }
     }
 
+    /// Discards the payload data and removes the payload from the registry.
+    /// Results stored by tasks still holding this payload are dropped.
+    pub fn remove(&self) {
+        self.inner.payload.lock().unwrap().take();
+        self.clear_pending();
+        registry().lock().unwrap().remove(&self.inner.id);
+    }
+
     // pub fn inner_clone(&self) -> Option<T>
     // where
     //     T: Clone,
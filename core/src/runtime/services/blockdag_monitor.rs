@@ -4,10 +4,63 @@ use kaspa_rpc_core::api::notifications::Notification;
 use kaspa_rpc_core::notify::connection::{ChannelConnection, ChannelType};
 use kaspa_rpc_core::{RpcBlock, VirtualChainChangedNotification};
 
+/// Default duration of the block DAG history available for replay.
+pub const DEFAULT_HISTORY_RETENTION_MINUTES: u64 = 5;
+/// Upper bound on the number of events retained in the history.
+const HISTORY_CAPACITY: usize = 64 * 1024;
+const REPLAY_INTERVAL_MILLIS: u64 = 50;
+
+/// Replay state of the block DAG graph.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlockDagPlayback {
+    /// Graph follows live notifications
+    Live,
+    /// Graph is frozen at the given history timestamp
+    Paused { cursor: f64 },
+    /// Graph replays the history from the given timestamp
+    Replay { cursor: f64, speed: f64 },
+}
+
+impl BlockDagPlayback {
+    pub fn is_live(&self) -> bool {
+        matches!(self, BlockDagPlayback::Live)
+    }
+
+    pub fn cursor(&self) -> Option<f64> {
+        match self {
+            BlockDagPlayback::Live => None,
+            BlockDagPlayback::Paused { cursor } | BlockDagPlayback::Replay { cursor, .. } => {
+                Some(*cursor)
+            }
+        }
+    }
+
+    /// Moves a replay from `cursor` to `next`, switching to live playback
+    /// once `next` reaches `last`. Returns `false` and leaves the playback
+    /// untouched if it is no longer the replay at `cursor`, as the user may
+    /// change the playback while a replay step is being applied.
+    pub fn advance(&mut self, cursor: f64, speed: f64, next: f64, last: f64) -> bool {
+        if *self != (BlockDagPlayback::Replay { cursor, speed }) {
+            return false;
+        }
+
+        *self = if next >= last {
+            BlockDagPlayback::Live
+        } else {
+            BlockDagPlayback::Replay {
+                cursor: next,
+                speed,
+            }
+        };
+        true
+    }
+}
+
 pub enum BlockDagMonitorEvents {
     Enable,
     Disable,
     Settings(Arc<BlockDagGraphSettings>),
+    Playback,
     Reset,
     Exit,
 }
@@ -26,6 +79,10 @@ pub struct BlockDagMonitorService {
     pub separators: Mutex<Vec<u64>>,
     pub new_blocks: Arc<Mutex<AHashSet<kaspa_consensus_core::Hash>>>,
     pub settings: Mutex<Arc<BlockDagGraphSettings>>,
+    history: Mutex<DagHistory>,
+    playback: Mutex<BlockDagPlayback>,
+    /// DAA score of the most recent block applied to the graph
+    daa_score: Arc<AtomicU64>,
 }
 
 impl BlockDagMonitorService {
@@ -44,6 +101,12 @@ impl BlockDagMonitorService {
             is_active: Arc::new(AtomicBool::new(false)),
             is_connected: Arc::new(AtomicBool::new(false)),
             settings: Mutex::new(Arc::new(BlockDagGraphSettings::default())),
+            history: Mutex::new(DagHistory::new(
+                Duration::from_secs(DEFAULT_HISTORY_RETENTION_MINUTES * 60),
+                HISTORY_CAPACITY,
+            )),
+            playback: Mutex::new(BlockDagPlayback::Live),
+            daa_score: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -146,6 +209,124 @@ impl BlockDagMonitorService {
         self.new_blocks.lock().unwrap()
     }
 
+    pub fn playback(&self) -> BlockDagPlayback {
+        *self.playback.lock().unwrap()
+    }
+
+    pub fn set_playback(&self, playback: BlockDagPlayback) {
+        *self.playback.lock().unwrap() = playback;
+        self.service_events
+            .sender
+            .try_send(BlockDagMonitorEvents::Playback)
+            .unwrap();
+    }
+
+    /// DAA score of the most recent block displayed by the graph
+    /// (trails the node DAA score while paused or replaying).
+    pub fn daa_score(&self) -> u64 {
+        self.daa_score.load(Ordering::Relaxed)
+    }
+
+    /// Time range covered by the recorded history.
+    pub fn history_range(&self) -> Option<(f64, f64)> {
+        self.history.lock().unwrap().range()
+    }
+
+    pub fn history_retention(&self) -> Duration {
+        self.history.lock().unwrap().retention()
+    }
+
+    pub fn set_history_retention(&self, retention: Duration) {
+        self.history.lock().unwrap().set_retention(retention);
+    }
+
+    fn apply(
+        &self,
+        event: &DagEvent,
+        blocks_by_hash: &mut AHashMap<kaspa_consensus_core::Hash, Arc<RpcBlock>>,
+        settings: &BlockDagGraphSettings,
+    ) {
+        match event {
+            DagEvent::Block(block) => {
+                self.update_new_blocks(block);
+
+                blocks_by_hash.insert(block.header.hash, block.clone());
+
+                let daa_score = block.header.daa_score;
+                self.daa_score.fetch_max(daa_score, Ordering::Relaxed);
+                let mut chain = self.chain.lock().unwrap();
+                if let Some(bucket) = chain.get_mut(&daa_score) {
+                    bucket.push(DagBlock::new(block.clone(), settings), settings);
+                } else {
+                    let mut bucket =
+                        DaaBucket::new(daa_score as f64, DagBlock::new(block.clone(), settings));
+                    bucket.update(settings);
+                    chain.insert(daa_score, bucket);
+                }
+
+                let last_daa = daa_score.saturating_sub(settings.graph_length_daa as u64);
+                chain.retain(|daa_score, bucket| {
+                    if *daa_score > last_daa {
+                        true
+                    } else {
+                        bucket.blocks.iter().for_each(|block| {
+                            blocks_by_hash.remove(&block.data.header.hash);
+                        });
+                        false
+                    }
+                });
+            }
+            DagEvent::ChainChanged { removed, added } => {
+                let update = ChainUpdate::new(removed, added, |hash| {
+                    blocks_by_hash
+                        .get(hash)
+                        .and_then(|block| block.verbose_data.as_ref())
+                        .map(|verbose_data| {
+                            (
+                                verbose_data.merge_set_blues_hashes.as_slice(),
+                                verbose_data.merge_set_reds_hashes.as_slice(),
+                            )
+                        })
+                });
+
+                let mut chain = self.chain.lock().unwrap();
+                for (hash, merge) in update.merge {
+                    if let Some(block) = blocks_by_hash.get(&hash)
+                        && let Some(bucket) = chain.get_mut(&block.header.daa_score)
+                    {
+                        bucket.update_merge(hash, merge);
+                    }
+                }
+                for (hash, vspc) in update.vspc {
+                    if let Some(block) = blocks_by_hash.get(&hash)
+                        && let Some(bucket) = chain.get_mut(&block.header.daa_score)
+                    {
+                        bucket.update_vspc(hash, vspc, settings);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Rebuilds the graph from history events received up to `cursor`
+    /// (or from the entire history if `None`).
+    fn rebuild(
+        &self,
+        cursor: Option<f64>,
+        blocks_by_hash: &mut AHashMap<kaspa_consensus_core::Hash, Arc<RpcBlock>>,
+        settings: &BlockDagGraphSettings,
+    ) {
+        self.chain.lock().unwrap().clear();
+        self.new_blocks.lock().unwrap().clear();
+        self.daa_score.store(0, Ordering::Relaxed);
+        blocks_by_hash.clear();
+
+        let history = self.history.lock().unwrap();
+        history
+            .until(cursor.unwrap_or(f64::INFINITY))
+            .for_each(|event| self.apply(event, blocks_by_hash, settings));
+    }
+
     fn update_new_blocks(&self, block: &Arc<RpcBlock>) {
         let mut new_blocks = self.new_blocks.lock().unwrap();
         new_blocks.insert(block.header.hash);
@@ -208,40 +389,17 @@ impl Service for BlockDagMonitorService {
             AHashMap::default();
 
         let mut settings = (*self.settings.lock().unwrap()).clone();
+        let replay = task::interval(Duration::from_millis(REPLAY_INTERVAL_MILLIS));
+        pin_mut!(replay);
+
         loop {
             select! {
 
                 msg = self.notification_channel.receiver.recv().fuse() => {
                     if let Ok(notification) = msg {
-                        match notification {
+                        let event = match notification {
                             Notification::BlockAdded(block_added_notification) => {
-                                let block = block_added_notification.block.clone();
-
-                                self.update_new_blocks(&block);
-
-                                blocks_by_hash.insert(block.header.hash, block.clone());
-
-                                let daa_score = block.header.daa_score;
-                                let mut chain = self.chain.lock().unwrap();
-                                if let Some(bucket) = chain.get_mut(&daa_score) {
-                                    bucket.push(DagBlock::new(block, &settings), &settings);
-                                } else {
-                                    let mut bucket = DaaBucket::new(daa_score as f64, DagBlock::new(block, &settings));
-                                    bucket.update(&settings);
-                                    chain.insert(daa_score, bucket);
-                                }
-
-                                let last_daa = daa_score - settings.graph_length_daa as u64;
-                                chain.retain(|daa_score, bucket| {
-                                    if *daa_score > last_daa {
-                                        true
-                                    } else {
-                                        bucket.blocks.iter().for_each(|block| {
-                                            blocks_by_hash.remove(&block.data.header.hash);
-                                        });
-                                        false
-                                    }
-                                });
+                                Some(DagEvent::Block(block_added_notification.block.clone()))
                             },
                             Notification::VirtualChainChanged(virtual_chain_changed_notification) => {
                                 let VirtualChainChangedNotification {
@@ -250,37 +408,67 @@ impl Service for BlockDagMonitorService {
                                     ..
                                 } = virtual_chain_changed_notification;
 
-                                removed_chain_block_hashes.iter().for_each(|hash| {
-                                    if let Some(block) = blocks_by_hash.get(hash) {
-                                        let daa_score = block.header.daa_score;
-                                        let mut chain = self.chain.lock().unwrap();
-                                        if let Some(bucket) = chain.get_mut(&daa_score) {
-                                            bucket.update_vspc(*hash, false, &settings);
-                                        }
-                                    }
-                                });
-                                added_chain_block_hashes.iter().for_each(|hash| {
-                                    if let Some(block) = blocks_by_hash.get(hash) {
-                                        let daa_score = block.header.daa_score;
-                                        let mut chain = self.chain.lock().unwrap();
-                                        if let Some(bucket) = chain.get_mut(&daa_score) {
-                                            bucket.update_vspc(*hash, true, &settings);
-                                        }
-                                    }
-                                });
-                                // println!("VirtualChainChanged: {:?}", virtual_chain_changed_notification);
+                                Some(DagEvent::ChainChanged {
+                                    removed: removed_chain_block_hashes,
+                                    added: added_chain_block_hashes,
+                                })
                             },
                             _ => {
                                 // println!("notification: {:?}", notification);
+                                None
                             }
-                        }
+                        };
 
-                        runtime().request_repaint();
+                        if let Some(event) = event {
+                            let is_live = self.playback().is_live();
+                            if is_live {
+                                self.apply(&event, &mut blocks_by_hash, &settings);
+                            }
+
+                            // transactions are not retained in the history,
+                            // the block inspector fetches them on demand
+                            let event = match event {
+                                DagEvent::Block(block) => DagEvent::Block(Arc::new(RpcBlock {
+                                    transactions: vec![],
+                                    ..(*block).clone()
+                                })),
+                                event => event,
+                            };
+                            self.history.lock().unwrap().push(unixtime_as_millis_f64(), event);
+
+                            if is_live {
+                                runtime().request_repaint();
+                            }
+                        }
                     } else {
                         break;
                     }
                 },
 
+                _ = replay.next().fuse() => {
+                    if let BlockDagPlayback::Replay { cursor, speed } = self.playback() {
+                        let next = cursor + REPLAY_INTERVAL_MILLIS as f64 * speed;
+                        let last = self.history_range().map(|(_, last)| last).unwrap_or_default();
+                        {
+                            let history = self.history.lock().unwrap();
+                            history
+                                .between(cursor, next)
+                                .for_each(|event| self.apply(event, &mut blocks_by_hash, &settings));
+                        }
+
+                        let caught_up = {
+                            let mut playback = self.playback.lock().unwrap();
+                            playback.advance(cursor, speed, next, last) && playback.is_live()
+                        };
+                        if caught_up {
+                            // continue with the live graph
+                            self.rebuild(None, &mut blocks_by_hash, &settings);
+                        }
+
+                        runtime().request_repaint();
+                    }
+                },
+
                 msg = self.as_ref().service_events.receiver.recv().fuse() => {
                     if let Ok(event) = msg {
                         match event {
@@ -308,8 +496,16 @@ impl Service for BlockDagMonitorService {
                             }
                             BlockDagMonitorEvents::Reset => {
                                 self.chain.lock().unwrap().clear();
+                                self.history.lock().unwrap().clear();
+                                self.daa_score.store(0, Ordering::Relaxed);
+                                *self.playback.lock().unwrap() = BlockDagPlayback::Live;
                                 blocks_by_hash.clear();
                             }
+                            BlockDagMonitorEvents::Playback => {
+                                let cursor = self.playback().cursor();
+                                self.rebuild(cursor, &mut blocks_by_hash, &settings);
+                                runtime().request_repaint();
+                            }
                             BlockDagMonitorEvents::Settings(new_settings) => {
                                 *self.settings.lock().unwrap() = new_settings.clone();
                                 settings = new_settings;
@@ -60,6 +60,11 @@ impl FeerateMonitorService {
             // println!("{}",resp.priority_bucket.feerate);
             // let feerate = Arc::new(resp);
             // self.feerate.lock().unwrap().replace(feerate.clone());
+            #[cfg(not(target_arch = "wasm32"))]
+            runtime()
+                .metrics_exporter_service()
+                .update_feerate(Some(&resp));
+
             self.application_events
                 .send(Events::Feerate {
                     feerate: Some(Arc::new(resp)),
@@ -72,6 +77,9 @@ impl FeerateMonitorService {
     }
 
     async fn clear(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        runtime().metrics_exporter_service().update_feerate(None);
+
         self.application_events
             .send(Events::Feerate { feerate: None })
             .await
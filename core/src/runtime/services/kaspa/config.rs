@@ -30,6 +30,7 @@ pub struct Config {
     enable_wrpc_json: bool,
     enable_grpc: bool,
     grpc_network_interface: NetworkInterfaceConfig,
+    enable_unsafe_rpc: bool,
     kaspad_daemon_args_enable: bool,
     kaspad_daemon_args: String,
     kaspad_daemon_storage_folder_enable: bool,
@@ -39,6 +40,7 @@ pub struct Config {
 
 impl From<NodeSettings> for Config {
     fn from(node_settings: NodeSettings) -> Self {
+        let enable_unsafe_rpc = node_settings.unsafe_rpc_enabled();
         Self {
             network: node_settings.network,
             enable_upnp: node_settings.enable_upnp,
@@ -46,6 +48,7 @@ impl From<NodeSettings> for Config {
             enable_wrpc_json: node_settings.enable_wrpc_json,
             enable_grpc: node_settings.enable_grpc,
             grpc_network_interface: node_settings.grpc_network_interface,
+            enable_unsafe_rpc,
             kaspad_daemon_args_enable: node_settings.kaspad_daemon_args_enable,
             kaspad_daemon_args: node_settings.kaspad_daemon_args,
             kaspad_daemon_storage_folder_enable: node_settings.kaspad_daemon_storage_folder_enable,
@@ -58,6 +61,23 @@ impl From<NodeSettings> for Config {
 cfg_if! {
 
     if #[cfg(not(target_arch = "wasm32"))] {
+        impl Config {
+            /// Folder containing the node data folders of all networks.
+            pub fn storage_root(&self) -> PathBuf {
+                let appdir = self.kaspad_daemon_args_enable
+                    .then(|| self.kaspad_daemon_args.split_whitespace().find_map(|arg| arg.strip_prefix("--appdir=")))
+                    .flatten();
+
+                if let Some(appdir) = appdir {
+                    PathBuf::from(appdir)
+                } else if self.kaspad_daemon_storage_folder_enable && !self.kaspad_daemon_storage_folder.is_empty() {
+                    PathBuf::from(&self.kaspad_daemon_storage_folder)
+                } else {
+                    kaspad_lib::daemon::get_app_dir()
+                }
+            }
+        }
+
         impl TryFrom<Config> for Args {
             type Error = Error;
             fn try_from(config: Config) -> Result<Self> {
@@ -74,6 +94,8 @@ cfg_if! {
                 args.perf_metrics_interval_sec = 1;
                 args.yes = true;
                 args.utxoindex = true;
+                // required by the peer management actions (add peer, ban, unban)
+                args.unsafe_rpc = config.enable_unsafe_rpc;
                 args.disable_upnp = !config.enable_upnp;
 
                 if config.enable_grpc {
@@ -104,6 +126,10 @@ cfg_if! {
                 args.push("--perf-metrics-interval-sec=1");
                 args.push("--yes");
                 args.push("--utxoindex");
+                // required by the peer management actions (add peer, ban, unban)
+                if config.enable_unsafe_rpc {
+                    args.push("--unsaferpc");
+                }
 
                 match config.memory_scale {
                     NodeMemoryScale::Default => {},
@@ -50,6 +50,7 @@ cfg_if! {
             Stdout { line : String },
             Stderr { line : String },
             Disable { network : Network },
+            ResetDatabase { datadir : PathBuf, restart : Box<KaspadServiceEvents> },
             Exit,
         }
 
@@ -452,6 +453,62 @@ impl KaspaService {
         Ok(())
     }
 
+    /// Stops the node, removes its database and restarts the node
+    /// using the given settings.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn reset_database(&self, datadir: PathBuf, node_settings: &NodeSettings) {
+        match KaspadServiceEvents::from_node_settings(node_settings, None) {
+            Ok(restart) => {
+                self.service_events
+                    .sender
+                    .try_send(KaspadServiceEvents::ResetDatabase {
+                        datadir,
+                        restart: Box::new(restart),
+                    })
+                    .unwrap_or_else(|err| {
+                        log_error!("KaspadService error: {}", err);
+                    });
+            }
+            Err(err) => {
+                log_error!("KaspadServiceEvents::try_from() error: {}", err);
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn check_free_space(&self, config: &Config) {
+        use crate::storage::{FreeSpace, available_space};
+        use kaspa_metrics_core::data::as_data_size;
+
+        let storage_root = config.storage_root();
+        let Some(available) = available_space(&storage_root) else {
+            return;
+        };
+
+        let args = [
+            ("available", as_data_size(available as f64, true)),
+            ("path", storage_root.display().to_string()),
+        ];
+        match FreeSpace::from_available(available) {
+            FreeSpace::Sufficient => {}
+            FreeSpace::Low => {
+                runtime().notify(
+                    UserNotification::warning(i18n_args(
+                        "Low disk space: {available} available for the node database at {path}",
+                        &args,
+                    ))
+                    .duration(Duration::from_secs(10)),
+                );
+            }
+            FreeSpace::Critical => {
+                runtime().notify(UserNotification::error(i18n_args(
+                    "Critically low disk space: {available} available for the node database at {path}. The node may stop once the disk is full.",
+                    &args,
+                )));
+            }
+        }
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     fn update_storage(&self) {
         const STORAGE_UPDATE_DELAY: Duration = Duration::from_millis(3000);
@@ -487,6 +544,7 @@ impl KaspaService {
             #[cfg(not(target_arch = "wasm32"))]
             KaspadServiceEvents::StartInternalInProc { config, network } => {
                 self.stop_all_services().await?;
+                self.check_free_space(&config);
 
                 self.handle_network_change(network).await?;
 
@@ -509,6 +567,7 @@ impl KaspaService {
             #[cfg(not(target_arch = "wasm32"))]
             KaspadServiceEvents::StartInternalAsDaemon { config, network } => {
                 self.stop_all_services().await?;
+                self.check_free_space(&config);
 
                 self.handle_network_change(network).await?;
 
@@ -532,6 +591,7 @@ impl KaspaService {
             #[cfg(not(target_arch = "wasm32"))]
             KaspadServiceEvents::StartInternalAsPassiveSync { config, network } => {
                 self.stop_all_services().await?;
+                self.check_free_space(&config);
 
                 self.handle_network_change(network).await?;
 
@@ -559,6 +619,7 @@ impl KaspaService {
                 network,
             } => {
                 self.stop_all_services().await?;
+                self.check_free_space(&config);
 
                 self.handle_network_change(network).await?;
 
@@ -626,6 +687,24 @@ impl KaspaService {
                 }
             }
 
+            #[cfg(not(target_arch = "wasm32"))]
+            KaspadServiceEvents::ResetDatabase { datadir, restart } => {
+                self.stop_all_services().await?;
+
+                if datadir.exists() {
+                    log_info!("Removing node database: {}", datadir.display());
+                    if let Err(err) = crate::storage::remove_folder(datadir).await {
+                        runtime().error(format!("Error removing node database: {err}"));
+                    } else {
+                        runtime().notify(
+                            UserNotification::success(i18n("Node database has been reset")).short(),
+                        );
+                    }
+                }
+
+                self.service_events.sender.try_send(*restart)?;
+            }
+
             KaspadServiceEvents::Exit => {
                 return Ok(true);
             }
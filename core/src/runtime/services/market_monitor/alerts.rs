@@ -0,0 +1,225 @@
+use super::*;
+
+/// User-defined condition evaluated by the market monitor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum AlertCondition {
+    /// Kaspa price crosses the given price in either direction
+    PriceCrosses { currency: String, price: f64 },
+    /// Absolute 24h price change exceeds the given percentage
+    ChangeExceeds { currency: String, percent: f64 },
+    /// Mature balance of the account (of any account if `None`)
+    /// drops below the given amount
+    BalanceBelow {
+        account_id: Option<AccountId>,
+        amount_sompi: u64,
+    },
+}
+
+impl AlertCondition {
+    pub fn is_balance(&self) -> bool {
+        matches!(self, AlertCondition::BalanceBelow { .. })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Alert {
+    pub enabled: bool,
+    pub condition: AlertCondition,
+}
+
+impl Alert {
+    pub fn new(condition: AlertCondition) -> Self {
+        Self {
+            enabled: true,
+            condition,
+        }
+    }
+
+    fn key(&self, scope: &str) -> String {
+        format!(
+            "{}:{scope}",
+            serde_json::to_string(&self.condition).unwrap_or_default()
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertEvent {
+    PriceCrossed {
+        currency: String,
+        price: f64,
+        threshold: f64,
+        rising: bool,
+    },
+    ChangeExceeded {
+        currency: String,
+        change: f64,
+        percent: f64,
+    },
+    BalanceBelow {
+        account: String,
+        balance_sompi: u64,
+        amount_sompi: u64,
+    },
+}
+
+impl AlertEvent {
+    pub fn message(&self) -> String {
+        match self {
+            AlertEvent::PriceCrossed {
+                currency,
+                price,
+                threshold,
+                rising,
+            } => {
+                let precision = precision_from_symbol(currency);
+                let symbol = currency.to_uppercase();
+                let price = format_currency_with_symbol(*price, precision, &symbol);
+                let threshold = format_currency_with_symbol(*threshold, precision, &symbol);
+                if *rising {
+                    i18n_args(
+                        "Kaspa price has risen above {threshold} ({price})",
+                        &[("threshold", threshold), ("price", price)],
+                    )
+                } else {
+                    i18n_args(
+                        "Kaspa price has fallen below {threshold} ({price})",
+                        &[("threshold", threshold), ("price", price)],
+                    )
+                }
+            }
+            AlertEvent::ChangeExceeded {
+                currency,
+                change,
+                percent,
+            } => i18n_args(
+                "Kaspa 24h price change in {currency} is {change}% (alert at {percent}%)",
+                &[
+                    ("currency", currency.to_uppercase()),
+                    ("change", format!("{change:+.2}")),
+                    ("percent", format!("{percent:.2}")),
+                ],
+            ),
+            AlertEvent::BalanceBelow {
+                account,
+                balance_sompi,
+                amount_sompi,
+            } => i18n_args(
+                "Balance of account {account} has dropped below {amount} KAS ({balance} KAS)",
+                &[
+                    ("account", account.clone()),
+                    ("amount", sompi_to_kaspa_string(*amount_sompi)),
+                    ("balance", sompi_to_kaspa_string(*balance_sompi)),
+                ],
+            ),
+        }
+    }
+
+    pub fn user_notification(&self) -> UserNotification {
+        UserNotification::warning(self.message()).duration(Duration::from_secs(10))
+    }
+}
+
+/// Tracks the state of each alert so that an alert is delivered once
+/// when its condition becomes true and is re-armed when it clears.
+#[derive(Default)]
+pub struct AlertMonitor {
+    state: AHashMap<String, bool>,
+}
+
+impl AlertMonitor {
+    pub fn reset(&mut self) {
+        self.state.clear();
+    }
+
+    // returns `true` if the condition has become true
+    fn latch(&mut self, key: String, condition: bool) -> bool {
+        let previous = self.state.insert(key, condition).unwrap_or(false);
+        condition && !previous
+    }
+
+    pub fn evaluate_prices(
+        &mut self,
+        alerts: &[Alert],
+        market_price_list: &MarketDataMap,
+    ) -> Vec<AlertEvent> {
+        alerts
+            .iter()
+            .filter(|alert| alert.enabled)
+            .filter_map(|alert| match &alert.condition {
+                AlertCondition::PriceCrosses { currency, price } => {
+                    let data = market_price_list.get(&currency.to_lowercase())?;
+                    let above = data.price >= *price;
+                    // the first observation only records the side of the threshold
+                    let previous = self.state.insert(alert.key(""), above)?;
+                    (previous != above).then(|| AlertEvent::PriceCrossed {
+                        currency: currency.clone(),
+                        price: data.price,
+                        threshold: *price,
+                        rising: above,
+                    })
+                }
+                AlertCondition::ChangeExceeds { currency, percent } => {
+                    let data = market_price_list.get(&currency.to_lowercase())?;
+                    self.latch(alert.key(""), data.change.abs() >= *percent)
+                        .then(|| AlertEvent::ChangeExceeded {
+                            currency: currency.clone(),
+                            change: data.change,
+                            percent: *percent,
+                        })
+                }
+                AlertCondition::BalanceBelow { .. } => None,
+            })
+            .collect()
+    }
+
+    pub fn evaluate_balance(
+        &mut self,
+        alerts: &[Alert],
+        account_id: &AccountId,
+        account: &str,
+        balance_sompi: u64,
+    ) -> Vec<AlertEvent> {
+        alerts
+            .iter()
+            .filter(|alert| alert.enabled)
+            .filter_map(|alert| match &alert.condition {
+                AlertCondition::BalanceBelow {
+                    account_id: filter,
+                    amount_sompi,
+                } if filter.as_ref().is_none_or(|id| id == account_id) => {
+                    let below = balance_sompi < *amount_sompi;
+                    // the first observation only records the current state so that
+                    // an existing low balance does not raise an alert on startup
+                    let previous = self
+                        .state
+                        .insert(alert.key(&account_id.to_string()), below)?;
+                    (below && !previous).then(|| AlertEvent::BalanceBelow {
+                        account: account.to_string(),
+                        balance_sompi,
+                        amount_sompi: *amount_sompi,
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Shows the alert as an operating system notification.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn desktop_notification(message: String) {
+    // the notification daemon may block, keep it off the service task
+    std::thread::spawn(move || {
+        if let Err(err) = notify_rust::Notification::new()
+            .appname("Kaspa NG")
+            .summary("Kaspa NG")
+            .body(&message)
+            .show()
+        {
+            log_warn!("Unable to show desktop notification: {err}");
+        }
+    });
+}
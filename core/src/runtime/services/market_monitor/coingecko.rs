@@ -15,13 +15,19 @@ use std::collections::hash_map::Entry;
 //     }
 //   }
 
-// https://api.coingecko.com/api/v3/coins/list
+// https://api.coingecko.com/api/v3/coins/kaspa/ohlc?vs_currency=usd&days=30
 // [
-//   {
-//     "id": "01coin",
-//     "symbol": "zoc",
-//     "name": "01coin"
-//   },
+//   [1709251200000, 0.1373, 0.1401, 0.1362, 0.1390],
+//   ...
+// ]
+
+// https://api.coingecko.com/api/v3/simple/supported_vs_currencies
+// [
+//   "btc",
+//   "eth",
+//   ...
+//   "usd",
+// ]
 
 #[derive(Default, Debug, Serialize, Deserialize)]
 struct CoinGeckoSimplePrice {
@@ -53,10 +59,43 @@ impl From<CoinGeckoSimplePrice> for MarketDataMap {
     }
 }
 
+pub struct CoinGecko;
+
+#[async_trait]
+impl MarketDataProvider for CoinGecko {
+    fn name(&self) -> String {
+        MarketDataProviderKind::CoinGecko.to_string()
+    }
+
+    async fn fetch_available_currencies(&self) -> Result<CurrencyDescriptorList> {
+        fetch_available_currencies().await
+    }
+
+    async fn fetch_market_price_list(&self, currencies: &[&str]) -> Result<MarketDataMap> {
+        fetch_market_price_list(currencies).await
+    }
+
+    async fn fetch_ohlc(&self, currency: &str, days: u32) -> Result<Ohlc> {
+        fetch_ohlc(currency, days).await
+    }
+}
+
 pub async fn fetch_available_currencies() -> Result<CurrencyDescriptorList> {
-    let url = "https://api.coingecko.com/api/v3/coins/list";
-    let available_currencies = http::get_json::<CurrencyDescriptorList>(url).await?;
-    Ok(available_currencies)
+    let url = "https://api.coingecko.com/api/v3/simple/supported_vs_currencies";
+    let currencies = http::get_json::<Vec<String>>(url).await?;
+    Ok(currencies
+        .into_iter()
+        .map(CurrencyDescriptor::from_symbol)
+        .collect())
+}
+
+pub async fn fetch_ohlc(currency: &str, days: u32) -> Result<Ohlc> {
+    let url = format!(
+        "https://api.coingecko.com/api/v3/coins/kaspa/ohlc?vs_currency={}&days={days}",
+        currency.to_lowercase()
+    );
+    let candles = http::get_json::<Vec<[f64; 5]>>(url).await?;
+    Ok(Ohlc::new(candles.into_iter().map(Candle::from).collect()))
 }
 
 pub async fn fetch_market_price_list(currencies: &[&str]) -> Result<MarketDataMap> {
@@ -1,68 +1,264 @@
 use super::*;
-use std::collections::hash_map::Entry;
-use workflow_http::get_json;
+use chrono::DateTime;
 
-#[derive(Default, Serialize, Deserialize)]
-struct CoinGeckoSimplePrice {
-    kaspa: Option<AHashMap<String, f64>>,
+// CoinMarketCap Pro API, authenticated with an API key passed in the
+// `X-CMC_PRO_API_KEY` request header. Kaspa is identified by its
+// CoinMarketCap id; currencies are converted by their symbol.
+//
+// https://pro-api.coinmarketcap.com/v2/cryptocurrency/quotes/latest?id=20396&convert=USD,BTC
+// {
+//   "data": {
+//     "20396": {
+//       "quote": {
+//         "USD": { "price": 0.137395, "volume_24h": 138844602.78, "percent_change_24h": 16.77, "market_cap": 2954668910.04 },
+//         "BTC": { ... }
+//       }
+//     }
+//   }
+// }
+//
+// https://pro-api.coinmarketcap.com/v2/cryptocurrency/ohlcv/historical?id=20396&convert=USD&time_period=daily&count=30
+// {
+//   "data": {
+//     "quotes": [
+//       { "time_open": "2024-03-01T00:00:00.000Z", "quote": { "USD": { "open": 0.1373, "high": 0.1401, "low": 0.1362, "close": 0.1390 } } },
+//       ...
+//     ]
+//   }
+// }
+//
+// https://pro-api.coinmarketcap.com/v1/fiat/map
+// {
+//   "data": [
+//     { "id": 2781, "name": "United States Dollar", "sign": "$", "symbol": "USD" },
+//     ...
+//   ]
+// }
+
+pub const COINMARKETCAP_API_URL: &str = "https://pro-api.coinmarketcap.com";
+/// CoinMarketCap id of Kaspa
+const KASPA_ID: &str = "20396";
+/// Request header carrying the API key
+const API_KEY_HEADER: &str = "X-CMC_PRO_API_KEY";
+
+#[derive(Default, Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct CoinMarketCapSettings {
+    pub api_key: String,
 }
 
-impl CoinGeckoSimplePrice {
-    pub async fn get(currencies: &[&str]) -> Result<Self> {
-        let ids = "kaspa";
-        let currencies = currencies
-            .iter()
-            .map(|currency| currency.to_lowercase())
-            .collect::<Vec<_>>()
-            .join("%2C");
-        let url = format!(
-            "https://api.coingecko.com/api/v3/simple/price?ids={ids}&vs_currencies={currencies}&include_market_cap=true&include_24hr_vol=true&include_24hr_change=true"
-        );
-        Ok(get_json::<Self>(url).await?)
+impl CoinMarketCapSettings {
+    pub fn is_configured(&self) -> bool {
+        !self.api_key.trim().is_empty()
     }
 }
 
-impl From<CoinGeckoSimplePrice> for MarketDataMap {
-    fn from(data: CoinGeckoSimplePrice) -> Self {
-        let mut prices = AHashMap::new();
-        if let Some(kaspa) = data.kaspa {
-            prices = group_by_currency_prefix(&kaspa);
-        }
-        prices
+#[derive(Debug, Deserialize)]
+struct Response<T> {
+    data: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct Quotes {
+    quote: AHashMap<String, Quote>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Quote {
+    price: Option<f64>,
+    market_cap: Option<f64>,
+    volume_24h: Option<f64>,
+    percent_change_24h: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct History {
+    quotes: Vec<HistoricalQuotes>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoricalQuotes {
+    time_open: String,
+    quote: AHashMap<String, HistoricalQuote>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoricalQuote {
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Fiat {
+    name: String,
+    symbol: String,
+}
+
+/// Issues a GET request authenticated with the API key. Requests are made
+/// with `reqwest` directly as workflow-http does not support custom headers.
+async fn request(url: String, api_key: String) -> Result<String> {
+    let response = reqwest::Client::new()
+        .get(url)
+        .header(API_KEY_HEADER, api_key)
+        .send()
+        .await
+        .map_err(|err| Error::custom(err.to_string()))?;
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|err| Error::custom(err.to_string()))?;
+    if status.is_success() {
+        Ok(text)
+    } else {
+        Err(Error::custom(format!("{status}: {text}")))
     }
 }
 
-pub async fn fetch_available_currencies() -> Result<CurrencyDescriptorList> {
-    let url = "https://api.coingecko.com/api/v3/coins/list";
-    let available_currencies = http::get_json::<CurrencyDescriptorList>(url).await?;
-    Ok(available_currencies)
+// reqwest futures are not `Send` in the browser
+#[cfg(target_arch = "wasm32")]
+async fn dispatch_request(
+    url: String,
+    api_key: String,
+) -> std::result::Result<Result<String>, String> {
+    Ok(task::call_async_no_send!(request(url, api_key).await))
+}
+
+pub struct CoinMarketCap {
+    api_url: String,
+    settings: CoinMarketCapSettings,
 }
 
-pub async fn fetch_market_price_list(currencies: &[&str]) -> Result<MarketDataMap> {
-    let market_data = CoinGeckoSimplePrice::get(currencies).await?;
-    Ok(market_data.into())
+impl CoinMarketCap {
+    pub fn new(settings: CoinMarketCapSettings) -> Self {
+        Self {
+            api_url: COINMARKETCAP_API_URL.to_string(),
+            settings,
+        }
+    }
+
+    /// Directs requests to a different API host.
+    pub fn with_api_url(mut self, api_url: &str) -> Self {
+        self.api_url = api_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, path: &str, query: &str) -> Result<T> {
+        if !self.settings.is_configured() {
+            return Err(Error::custom("CoinMarketCap API key is not configured"));
+        }
+
+        let url = if query.is_empty() {
+            format!("{}{path}", self.api_url)
+        } else {
+            format!("{}{path}?{query}", self.api_url)
+        };
+        let api_key = self.settings.api_key.trim().to_string();
+
+        #[cfg(target_arch = "wasm32")]
+        let text = dispatch_request(url, api_key)
+            .await
+            .map_err(Error::custom)??;
+        #[cfg(not(target_arch = "wasm32"))]
+        let text = request(url, api_key).await?;
+
+        let response = serde_json::from_str::<Response<T>>(&text)?;
+        Ok(response.data)
+    }
 }
 
-fn group_by_currency_prefix(data: &AHashMap<String, f64>) -> MarketDataMap {
-    let mut grouped_data: MarketDataMap = AHashMap::new();
+#[async_trait]
+impl MarketDataProvider for CoinMarketCap {
+    fn name(&self) -> String {
+        MarketDataProviderKind::CoinMarketCap.to_string()
+    }
 
-    for (symbol, info) in data.iter() {
-        let parts: Vec<&str> = symbol.split('_').collect();
-        let currency_prefix = parts[0].to_lowercase();
-        let suffix = parts.last().map(|suffix| suffix.to_lowercase());
-        let data = match grouped_data.entry(currency_prefix.clone()) {
-            Entry::Occupied(entry) => entry.into_mut(),
-            Entry::Vacant(entry) => entry.insert(MarketData::new(symbol.as_str())),
+    async fn fetch_available_currencies(&self) -> Result<CurrencyDescriptorList> {
+        let fiat = self.get::<Vec<Fiat>>("/v1/fiat/map", "").await?;
+        // conversions to Bitcoin are supported in addition to fiat currencies
+        let bitcoin = CurrencyDescriptor {
+            id: "btc".to_string(),
+            symbol: "btc".to_string(),
+            name: "Bitcoin".to_string(),
         };
+        Ok(std::iter::once(bitcoin)
+            .chain(fiat.into_iter().map(|fiat| {
+                let symbol = fiat.symbol.to_lowercase();
+                CurrencyDescriptor {
+                    id: symbol.clone(),
+                    symbol,
+                    name: fiat.name,
+                }
+            }))
+            .collect())
+    }
+
+    async fn fetch_market_price_list(&self, currencies: &[&str]) -> Result<MarketDataMap> {
+        let convert = currencies
+            .iter()
+            .map(|currency| currency.to_uppercase())
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut data = self
+            .get::<AHashMap<String, Quotes>>(
+                "/v2/cryptocurrency/quotes/latest",
+                format!("id={KASPA_ID}&convert={convert}").as_str(),
+            )
+            .await?;
+        let quotes = data
+            .remove(KASPA_ID)
+            .ok_or_else(|| Error::custom("CoinMarketCap returned no Kaspa quotes"))?;
 
-        match suffix.as_deref() {
-            None => data.price = *info,
-            Some("market_cap") => data.market_cap = *info,
-            Some("24h_vol") => data.volume = *info,
-            Some("24h_change") => data.change = *info,
-            _ => (),
+        let market_price_list = quotes
+            .quote
+            .into_iter()
+            .filter_map(|(symbol, quote)| {
+                let symbol = symbol.to_lowercase();
+                let mut market_data = MarketData::new(symbol.as_str());
+                market_data.price = quote.price?;
+                market_data.market_cap = quote.market_cap.unwrap_or_default();
+                market_data.volume = quote.volume_24h.unwrap_or_default();
+                market_data.change = quote.percent_change_24h.unwrap_or_default();
+                Some((symbol, market_data))
+            })
+            .collect::<MarketDataMap>();
+
+        if market_price_list.is_empty() {
+            return Err(Error::custom("CoinMarketCap returned no prices"));
         }
+
+        Ok(market_price_list)
     }
 
-    grouped_data
+    async fn fetch_ohlc(&self, currency: &str, days: u32) -> Result<Ohlc> {
+        let convert = currency.to_uppercase();
+        let history = self
+            .get::<History>(
+                "/v2/cryptocurrency/ohlcv/historical",
+                format!("id={KASPA_ID}&convert={convert}&time_period=daily&count={days}").as_str(),
+            )
+            .await?;
+
+        let candles = history
+            .quotes
+            .into_iter()
+            .filter_map(|mut entry| {
+                let timestamp = DateTime::parse_from_rfc3339(entry.time_open.as_str())
+                    .ok()?
+                    .timestamp_millis();
+                let quote = entry.quote.remove(&convert)?;
+                Some(Candle::from([
+                    timestamp as f64,
+                    quote.open,
+                    quote.high,
+                    quote.low,
+                    quote.close,
+                ]))
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Ohlc::new(candles))
+    }
 }
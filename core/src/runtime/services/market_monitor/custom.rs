@@ -0,0 +1,169 @@
+use super::*;
+use serde_json::Value;
+
+// Self-hosted price endpoint. URLs are templates where `{currencies}`
+// is replaced with a comma-separated list of currency symbols and
+// `{currency}` / `{days}` with the requested currency and history range.
+// Response fields are located using JSON pointers (RFC 6901) which may
+// also contain the `{currency}` placeholder, for example:
+//
+// https://prices.example.com/kaspa?vs={currencies}
+// {
+//   "data": {
+//     "usd": { "price": 0.137395, "cap": 2954668910.04, "vol": 138844602.78, "change": 16.77 },
+//     "btc": { "price": 0.000002, ... }
+//   }
+// }
+//
+// price: "/data/{currency}/price", market cap: "/data/{currency}/cap", ...
+//
+// Price history must resolve to an array of `[timestamp, open, high, low, close]`
+// entries with timestamps in milliseconds; available currencies must resolve
+// to an array of currency symbols.
+
+#[derive(Default, Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct CustomEndpointSettings {
+    pub price_url: String,
+    pub price: String,
+    pub market_cap: String,
+    pub volume: String,
+    pub change: String,
+    pub ohlc_url: String,
+    pub ohlc: String,
+    pub currencies_url: String,
+    pub currencies: String,
+}
+
+impl CustomEndpointSettings {
+    pub fn is_configured(&self) -> bool {
+        !self.price_url.is_empty() && !self.price.is_empty()
+    }
+}
+
+pub struct CustomEndpoint {
+    settings: CustomEndpointSettings,
+}
+
+impl CustomEndpoint {
+    pub fn new(settings: CustomEndpointSettings) -> Self {
+        Self { settings }
+    }
+
+    async fn get(url: String) -> Result<Value> {
+        Ok(http::get_json::<Value>(url).await?)
+    }
+}
+
+fn resolve<'data>(data: &'data Value, pointer: &str, currency: &str) -> Option<&'data Value> {
+    data.pointer(pointer.replace("{currency}", currency).as_str())
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(string) => string.parse().ok(),
+        _ => None,
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for CustomEndpoint {
+    fn name(&self) -> String {
+        MarketDataProviderKind::Custom.to_string()
+    }
+
+    async fn fetch_available_currencies(&self) -> Result<CurrencyDescriptorList> {
+        if self.settings.currencies_url.is_empty() {
+            return Err(Error::custom(
+                "Custom endpoint does not provide available currencies",
+            ));
+        }
+
+        let data = Self::get(self.settings.currencies_url.clone()).await?;
+        let currencies = data
+            .pointer(self.settings.currencies.as_str())
+            .and_then(Value::as_array)
+            .ok_or_else(|| Error::custom("Unable to locate the list of currencies"))?;
+
+        Ok(currencies
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|symbol| CurrencyDescriptor::from_symbol(symbol.to_lowercase()))
+            .collect())
+    }
+
+    async fn fetch_market_price_list(&self, currencies: &[&str]) -> Result<MarketDataMap> {
+        let symbols = currencies
+            .iter()
+            .map(|currency| currency.to_lowercase())
+            .collect::<Vec<_>>();
+        let url = self
+            .settings
+            .price_url
+            .replace("{currencies}", symbols.join(",").as_str());
+        let data = Self::get(url).await?;
+
+        let mut market_price_list = MarketDataMap::new();
+        for symbol in symbols {
+            let field = |pointer: &str| {
+                (!pointer.is_empty())
+                    .then(|| resolve(&data, pointer, symbol.as_str()).and_then(as_f64))
+                    .flatten()
+                    .unwrap_or_default()
+            };
+
+            let Some(price) =
+                resolve(&data, self.settings.price.as_str(), symbol.as_str()).and_then(as_f64)
+            else {
+                continue;
+            };
+
+            let mut market_data = MarketData::new(symbol.as_str());
+            market_data.price = price;
+            market_data.market_cap = field(self.settings.market_cap.as_str());
+            market_data.volume = field(self.settings.volume.as_str());
+            market_data.change = field(self.settings.change.as_str());
+            market_price_list.insert(symbol, market_data);
+        }
+
+        if market_price_list.is_empty() {
+            return Err(Error::custom("Custom endpoint returned no prices"));
+        }
+
+        Ok(market_price_list)
+    }
+
+    async fn fetch_ohlc(&self, currency: &str, days: u32) -> Result<Ohlc> {
+        if self.settings.ohlc_url.is_empty() {
+            return Err(Error::custom(
+                "Custom endpoint does not provide price history",
+            ));
+        }
+
+        let currency = currency.to_lowercase();
+        let url = self
+            .settings
+            .ohlc_url
+            .replace("{currency}", currency.as_str())
+            .replace("{days}", days.to_string().as_str());
+        let data = Self::get(url).await?;
+        let entries = resolve(&data, self.settings.ohlc.as_str(), currency.as_str())
+            .and_then(Value::as_array)
+            .ok_or_else(|| Error::custom("Unable to locate the price history"))?;
+
+        let candles = entries
+            .iter()
+            .filter_map(|entry| {
+                let values = entry
+                    .as_array()?
+                    .iter()
+                    .map(as_f64)
+                    .collect::<Option<Vec<_>>>()?;
+                <[f64; 5]>::try_from(values).ok().map(Candle::from)
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Ohlc::new(candles))
+    }
+}
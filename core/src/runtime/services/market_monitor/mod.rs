@@ -1,56 +1,248 @@
 use crate::imports::*;
 use crate::market::*;
 
+mod alerts;
 mod coingecko;
 mod coinmarketcap;
+mod custom;
+
+pub use alerts::{Alert, AlertCondition, AlertEvent, AlertMonitor};
+pub use coingecko::CoinGecko;
+pub use coinmarketcap::{COINMARKETCAP_API_URL, CoinMarketCap, CoinMarketCapSettings};
+pub use custom::{CustomEndpoint, CustomEndpointSettings};
 
 pub const POLLING_INTERVAL_SECONDS: u64 = 60;
+/// Interval after which the cached price history is refreshed
+pub const OHLC_REFRESH_INTERVAL_SECONDS: u64 = 60 * 60;
+/// Number of days of price history to fetch
+pub const OHLC_DAYS: u32 = 30;
+
+/// Source of Kaspa market prices and price history.
+#[async_trait]
+pub trait MarketDataProvider: Send + Sync {
+    fn name(&self) -> String;
+
+    async fn fetch_available_currencies(&self) -> Result<CurrencyDescriptorList>;
+
+    async fn fetch_market_price_list(&self, currencies: &[&str]) -> Result<MarketDataMap>;
+
+    async fn fetch_ohlc(&self, currency: &str, days: u32) -> Result<Ohlc>;
+}
 
 #[derive(Default, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
-pub enum MarketDataProvider {
+pub enum MarketDataProviderKind {
     #[default]
     CoinGecko,
     CoinMarketCap,
+    Custom,
 }
 
-impl MarketDataProvider {
-    #[allow(dead_code)]
-    async fn fetch_available_currencies(&self) -> Result<CurrencyDescriptorList> {
+impl std::fmt::Display for MarketDataProviderKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::CoinGecko => coingecko::fetch_available_currencies().await,
-            Self::CoinMarketCap => coinmarketcap::fetch_available_currencies().await,
+            Self::CoinGecko => write!(f, "CoinGecko"),
+            Self::CoinMarketCap => write!(f, "CoinMarketCap"),
+            Self::Custom => write!(f, "Custom"),
+        }
+    }
+}
+
+impl MarketDataProviderKind {
+    pub fn list() -> [MarketDataProviderKind; 3] {
+        [Self::CoinGecko, Self::CoinMarketCap, Self::Custom]
+    }
+}
+
+/// Queries the given providers in order, returning the
+/// first successful response.
+pub struct Failover {
+    providers: Vec<Arc<dyn MarketDataProvider>>,
+}
+
+impl Failover {
+    pub fn new(providers: Vec<Arc<dyn MarketDataProvider>>) -> Self {
+        Self { providers }
+    }
+
+    fn failed(provider: &Arc<dyn MarketDataProvider>, err: &Error) {
+        log_warn!("Market data provider {} failed: {err}", provider.name());
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for Failover {
+    fn name(&self) -> String {
+        self.providers
+            .iter()
+            .map(|provider| provider.name())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    async fn fetch_available_currencies(&self) -> Result<CurrencyDescriptorList> {
+        let mut last_error = None;
+        for provider in self.providers.iter() {
+            match provider.fetch_available_currencies().await {
+                Ok(currencies) => return Ok(currencies),
+                Err(err) => {
+                    Self::failed(provider, &err);
+                    last_error = Some(err);
+                }
+            }
         }
+        Err(last_error.unwrap_or_else(|| Error::custom("No market data providers")))
     }
 
     async fn fetch_market_price_list(&self, currencies: &[&str]) -> Result<MarketDataMap> {
-        match self {
-            Self::CoinGecko => coingecko::fetch_market_price_list(currencies).await,
-            Self::CoinMarketCap => coinmarketcap::fetch_market_price_list(currencies).await,
+        let mut last_error = None;
+        for provider in self.providers.iter() {
+            match provider.fetch_market_price_list(currencies).await {
+                Ok(market_price_list) => return Ok(market_price_list),
+                Err(err) => {
+                    Self::failed(provider, &err);
+                    last_error = Some(err);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| Error::custom("No market data providers")))
+    }
+
+    async fn fetch_ohlc(&self, currency: &str, days: u32) -> Result<Ohlc> {
+        let mut last_error = None;
+        for provider in self.providers.iter() {
+            match provider.fetch_ohlc(currency, days).await {
+                Ok(ohlc) => return Ok(ohlc),
+                Err(err) => {
+                    Self::failed(provider, &err);
+                    last_error = Some(err);
+                }
+            }
         }
+        Err(last_error.unwrap_or_else(|| Error::custom("No market data providers")))
     }
 }
 
-#[derive(Default, Serialize, Deserialize)]
-#[allow(dead_code)]
-struct MarketMonitorSettings {
-    enabled: bool,
-    provider: MarketDataProvider,
-    currencies: Vec<String>,
+/// Market data source and the currencies Kaspa prices are fetched in.
+/// The monitor itself is enabled by [`Settings::market_monitor`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct MarketMonitorSettings {
+    pub provider: MarketDataProviderKind,
+    /// Fall back to the remaining providers if the selected one fails
+    pub failover: bool,
+    pub coinmarketcap: CoinMarketCapSettings,
+    pub custom: CustomEndpointSettings,
+    /// Currency used for balance and fee estimates
+    pub primary_currency: String,
+    pub currencies: Vec<String>,
+    pub alerts: Vec<Alert>,
+    /// Also deliver alerts as operating system notifications
+    pub desktop_notifications: bool,
+}
+
+impl Default for MarketMonitorSettings {
+    fn default() -> Self {
+        Self {
+            provider: MarketDataProviderKind::default(),
+            failover: true,
+            coinmarketcap: CoinMarketCapSettings::default(),
+            custom: CustomEndpointSettings::default(),
+            primary_currency: "usd".to_string(),
+            currencies: vec!["usd".to_string(), "btc".to_string()],
+            alerts: vec![],
+            desktop_notifications: false,
+        }
+    }
+}
+
+impl MarketMonitorSettings {
+    fn create_provider(&self, kind: &MarketDataProviderKind) -> Arc<dyn MarketDataProvider> {
+        match kind {
+            MarketDataProviderKind::CoinGecko => Arc::new(CoinGecko),
+            MarketDataProviderKind::CoinMarketCap => {
+                Arc::new(CoinMarketCap::new(self.coinmarketcap.clone()))
+            }
+            MarketDataProviderKind::Custom => Arc::new(CustomEndpoint::new(self.custom.clone())),
+        }
+    }
+
+    /// Creates the selected provider followed by the failover providers.
+    /// CoinMarketCap and the custom endpoint are used for failover
+    /// only if they are configured.
+    pub fn provider(&self) -> Arc<dyn MarketDataProvider> {
+        let mut providers = vec![self.create_provider(&self.provider)];
+        if self.failover {
+            MarketDataProviderKind::list()
+                .iter()
+                .filter(|kind| **kind != self.provider)
+                .filter(|kind| self.is_configured(kind))
+                .for_each(|kind| providers.push(self.create_provider(kind)));
+        }
+        Arc::new(Failover::new(providers))
+    }
+
+    fn is_configured(&self, kind: &MarketDataProviderKind) -> bool {
+        match kind {
+            MarketDataProviderKind::CoinGecko => true,
+            MarketDataProviderKind::CoinMarketCap => self.coinmarketcap.is_configured(),
+            MarketDataProviderKind::Custom => self.custom.is_configured(),
+        }
+    }
+
+    fn provider_settings_eq(&self, other: &Self) -> bool {
+        self.provider == other.provider
+            && self.failover == other.failover
+            && self.coinmarketcap == other.coinmarketcap
+            && self.custom == other.custom
+    }
+
+    /// Currencies to fetch, always including the primary currency.
+    pub fn requested_currencies(&self) -> Vec<String> {
+        let mut currencies = self.currencies.clone();
+        if !currencies.contains(&self.primary_currency) {
+            currencies.insert(0, self.primary_currency.clone());
+        }
+        currencies
+    }
+
+    /// Sorts currency symbols alphabetically, placing the primary currency first.
+    pub fn sorted_symbols<'a>(&self, symbols: impl Iterator<Item = &'a String>) -> Vec<&'a String> {
+        let mut symbols = symbols.collect::<Vec<_>>();
+        symbols.sort_by_key(|symbol| (**symbol != self.primary_currency, *symbol));
+        symbols
+    }
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CurrencyDescriptor {
     pub id: String,
     pub symbol: String,
     pub name: String,
 }
 
+impl CurrencyDescriptor {
+    pub fn from_symbol(symbol: String) -> Self {
+        Self {
+            id: symbol.clone(),
+            name: symbol.to_uppercase(),
+            symbol,
+        }
+    }
+}
+
 pub type CurrencyDescriptorList = Vec<CurrencyDescriptor>;
 
 pub enum MarketMonitorEvents {
     Enable,
     Disable,
+    Update,
+    FetchAvailableCurrencies,
+    Balance {
+        account_id: AccountId,
+        account: String,
+        balance_sompi: u64,
+    },
     Exit,
 }
 
@@ -60,27 +252,30 @@ pub struct MarketMonitorService {
     pub task_ctl: Channel<()>,
     pub is_enabled: AtomicBool,
     pub currencies: Mutex<Option<Vec<String>>>,
-    pub provider: Mutex<MarketDataProvider>,
+    pub settings: Mutex<MarketMonitorSettings>,
+    pub provider: Mutex<Arc<dyn MarketDataProvider>>,
     pub available_currencies: Mutex<Option<Vec<CurrencyDescriptor>>>,
     pub market_price_list: Mutex<Option<Arc<MarketDataMap>>>,
+    pub ohlc: Mutex<OhlcMap>,
+    pub alert_monitor: Mutex<AlertMonitor>,
 }
 
 impl MarketMonitorService {
     pub fn new(application_events: ApplicationEventsChannel, settings: &Settings) -> Self {
-        let currencies = ["usd", "btc"]
-            .into_iter()
-            .map(String::from)
-            .collect::<Vec<_>>();
+        let currencies = settings.market.requested_currencies();
 
         Self {
             application_events,
             service_events: Channel::unbounded(),
             task_ctl: Channel::oneshot(),
             is_enabled: AtomicBool::new(settings.market_monitor),
-            provider: Mutex::new(MarketDataProvider::default()),
+            provider: Mutex::new(settings.market.provider()),
             currencies: Mutex::new(Some(currencies)),
+            settings: Mutex::new(settings.market.clone()),
             available_currencies: Mutex::new(None),
             market_price_list: Mutex::new(None),
+            ohlc: Mutex::new(OhlcMap::default()),
+            alert_monitor: Mutex::new(AlertMonitor::default()),
         }
     }
 
@@ -88,10 +283,60 @@ impl MarketMonitorService {
         self.currencies.lock().unwrap().clone()
     }
 
-    pub fn provider(&self) -> MarketDataProvider {
+    pub fn provider(&self) -> Arc<dyn MarketDataProvider> {
         self.provider.lock().unwrap().clone()
     }
 
+    pub fn available_currencies(&self) -> Option<Vec<CurrencyDescriptor>> {
+        self.available_currencies.lock().unwrap().clone()
+    }
+
+    /// Applies updated provider and currency settings and refreshes the prices.
+    pub fn update_settings(&self, settings: &MarketMonitorSettings) {
+        let (provider_changed, alerts_changed) = {
+            let mut current = self.settings.lock().unwrap();
+            let changed = (
+                !current.provider_settings_eq(settings),
+                current.alerts != settings.alerts,
+            );
+            *current = settings.clone();
+            changed
+        };
+        if alerts_changed {
+            self.alert_monitor.lock().unwrap().reset();
+        }
+        if provider_changed {
+            *self.provider.lock().unwrap() = settings.provider();
+            self.available_currencies.lock().unwrap().take();
+            self.ohlc.lock().unwrap().clear();
+        }
+        self.currencies
+            .lock()
+            .unwrap()
+            .replace(settings.requested_currencies());
+        if let Err(err) = self.service_events.try_send(MarketMonitorEvents::Update) {
+            log_error!("Market monitor: unable to request a price update: {err}");
+        }
+    }
+
+    /// Requests the list of currencies supported by the current provider.
+    pub fn fetch_available_currencies(&self) {
+        self.service_events
+            .try_send(MarketMonitorEvents::FetchAvailableCurrencies)
+            .unwrap();
+    }
+
+    /// Evaluates balance alerts against the updated balance of an account.
+    pub fn update_balance(&self, account_id: AccountId, account: String, balance_sompi: u64) {
+        self.service_events
+            .try_send(MarketMonitorEvents::Balance {
+                account_id,
+                account,
+                balance_sompi,
+            })
+            .unwrap();
+    }
+
     pub fn enable(&self, enable: bool) {
         if enable {
             self.service_events
@@ -104,7 +349,6 @@ impl MarketMonitorService {
         }
     }
 
-    #[allow(dead_code)]
     async fn update_available_currencies(&self) -> Result<()> {
         let available_currencies = self.provider().fetch_available_currencies().await?;
         self.available_currencies
@@ -114,18 +358,116 @@ impl MarketMonitorService {
         Ok(())
     }
 
+    fn deliver_alerts(&self, events: Vec<AlertEvent>) {
+        #[cfg(not(target_arch = "wasm32"))]
+        let desktop_notifications = self.settings.lock().unwrap().desktop_notifications;
+
+        for event in events {
+            #[cfg(not(target_arch = "wasm32"))]
+            if desktop_notifications {
+                alerts::desktop_notification(event.message());
+            }
+
+            self.application_events
+                .sender
+                .try_send(Events::Notify {
+                    user_notification: event.user_notification(),
+                })
+                .ok();
+        }
+    }
+
+    fn evaluate_price_alerts(&self, market_price_list: &MarketDataMap) {
+        let alerts = self.settings.lock().unwrap().alerts.clone();
+        let events = self
+            .alert_monitor
+            .lock()
+            .unwrap()
+            .evaluate_prices(&alerts, market_price_list);
+        self.deliver_alerts(events);
+    }
+
+    fn evaluate_balance_alerts(&self, account_id: &AccountId, account: &str, balance_sompi: u64) {
+        let alerts = self.settings.lock().unwrap().alerts.clone();
+        let events = self.alert_monitor.lock().unwrap().evaluate_balance(
+            &alerts,
+            account_id,
+            account,
+            balance_sompi,
+        );
+        self.deliver_alerts(events);
+    }
+
+    fn post_ohlc(&self) {
+        let ohlc = self.ohlc.lock().unwrap().clone();
+        self.application_events
+            .sender
+            .try_send(Events::Market(MarketUpdate::Ohlc(Arc::new(ohlc))))
+            .unwrap();
+    }
+
+    async fn load_ohlc_cache(&self) {
+        match load_ohlc_cache().await {
+            Ok(ohlc) if !ohlc.is_empty() => {
+                *self.ohlc.lock().unwrap() = ohlc;
+                self.post_ohlc();
+            }
+            Ok(_) => {}
+            Err(err) => {
+                log_warn!("Unable to load the price history cache: {err}");
+            }
+        }
+    }
+
+    // fetch the price history of the primary currency if the cached one is stale
+    async fn update_ohlc(&self) {
+        if !self.is_enabled.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let currency = self.settings.lock().unwrap().primary_currency.clone();
+        let max_age = Duration::from_secs(OHLC_REFRESH_INTERVAL_SECONDS);
+        let is_stale = self
+            .ohlc
+            .lock()
+            .unwrap()
+            .get(&currency)
+            .is_none_or(|ohlc| ohlc.is_stale(unixtime_as_millis_f64(), max_age));
+        if !is_stale {
+            return;
+        }
+
+        match self.provider().fetch_ohlc(&currency, OHLC_DAYS).await {
+            Ok(ohlc) => {
+                self.ohlc.lock().unwrap().insert(currency, ohlc);
+                self.post_ohlc();
+                let cache = self.ohlc.lock().unwrap().clone();
+                if let Err(err) = store_ohlc_cache(&cache).await {
+                    log_warn!("Unable to store the price history cache: {err}");
+                }
+            }
+            Err(err) => {
+                log_warn!("Unable to fetch the price history: {err}");
+            }
+        }
+    }
+
     async fn update_market_price_list(&self) -> Result<()> {
         if let Some(currencies) = self.currencies() {
             let currencies = currencies.iter().map(String::as_str).collect::<Vec<_>>();
             if let Ok(market_price_list) =
                 self.provider().fetch_market_price_list(&currencies).await
             {
-                self.application_events
-                    .sender
-                    .try_send(Events::Market(MarketUpdate::Price(Arc::new(
-                        market_price_list,
-                    ))))
-                    .unwrap();
+                self.evaluate_price_alerts(&market_price_list);
+                if let Err(err) =
+                    self.application_events
+                        .sender
+                        .try_send(Events::Market(MarketUpdate::Price(Arc::new(
+                            market_price_list,
+                        ))))
+                {
+                    log_error!("Market monitor: unable to post the price update: {err}");
+                }
             }
         }
         Ok(())
@@ -144,10 +486,14 @@ impl Service for MarketMonitorService {
         let interval = task::interval(Duration::from_secs(POLLING_INTERVAL_SECONDS));
         pin_mut!(interval);
 
+        this.load_ohlc_cache().await;
+        this.update_ohlc().await;
+
         loop {
             select! {
                 _ = interval.next().fuse() => {
                     this.update_market_price_list().await?;
+                    this.update_ohlc().await;
                 },
 
                 msg = this.as_ref().service_events.receiver.recv().fuse() => {
@@ -157,11 +503,24 @@ impl Service for MarketMonitorService {
                                 if !this.is_enabled.load(Ordering::SeqCst) {
                                     this.is_enabled.store(true, Ordering::SeqCst);
                                     this.update_market_price_list().await?;
+                                    this.update_ohlc().await;
                                 }
                             }
                             MarketMonitorEvents::Disable => {
                                 this.is_enabled.store(false, Ordering::SeqCst);
                             }
+                            MarketMonitorEvents::Update => {
+                                this.update_market_price_list().await?;
+                                this.update_ohlc().await;
+                            }
+                            MarketMonitorEvents::FetchAvailableCurrencies => {
+                                if let Err(err) = this.update_available_currencies().await {
+                                    log_warn!("Unable to fetch available currencies: {err}");
+                                }
+                            }
+                            MarketMonitorEvents::Balance { account_id, account, balance_sompi } => {
+                                this.evaluate_balance_alerts(&account_id, &account, balance_sompi);
+                            }
                             MarketMonitorEvents::Exit => {
                                 break;
                             }
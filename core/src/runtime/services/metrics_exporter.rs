@@ -0,0 +1,441 @@
+//! Local OpenMetrics exporter
+//!
+//! Serves the latest node metrics snapshot along with application-level
+//! gauges (peers, mempool, feerate estimates, sync state and optionally
+//! wallet balances) at `http://127.0.0.1:<port>/metrics` in the
+//! [OpenMetrics](https://openmetrics.io) text format.
+
+use crate::imports::*;
+use kaspa_metrics_core::{Metric, MetricsSnapshot};
+use std::fmt::Write;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+pub const OPENMETRICS_CONTENT_TYPE: &str =
+    "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Maximum size of the request head accepted by the exporter.
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct AccountBalanceGauge {
+    pub account_id: String,
+    pub name: String,
+    pub mature_sompi: u64,
+    pub pending_sompi: u64,
+}
+
+/// Application state exported alongside the node metrics.
+#[derive(Default, Debug, Clone)]
+pub struct ExporterGauges {
+    pub network: Option<String>,
+    pub is_synced: Option<bool>,
+    pub peers: Option<usize>,
+    pub mempool_size: Option<usize>,
+    /// Feerate estimate buckets (`low`, `economic`, `priority`)
+    pub feerate: Vec<(&'static str, FeerateBucket)>,
+    pub balances: Vec<AccountBalanceGauge>,
+}
+
+pub enum MetricsExporterEvents {
+    Exit,
+}
+
+pub struct MetricsExporterService {
+    pub service_events: Channel<MetricsExporterEvents>,
+    pub task_ctl: Channel<()>,
+    pub settings: MetricsExporterSettings,
+    snapshot: Mutex<Option<Box<MetricsSnapshot>>>,
+    gauges: Mutex<ExporterGauges>,
+    feerate: Mutex<Option<FeerateEstimate>>,
+}
+
+impl MetricsExporterService {
+    pub fn new(_application_events: ApplicationEventsChannel, settings: &Settings) -> Self {
+        Self {
+            service_events: Channel::unbounded(),
+            task_ctl: Channel::oneshot(),
+            settings: settings.metrics_exporter.clone(),
+            snapshot: Mutex::new(None),
+            gauges: Mutex::new(ExporterGauges::default()),
+            feerate: Mutex::new(None),
+        }
+    }
+
+    /// Exporter is started only if enabled at application startup.
+    pub fn is_enabled(&self) -> bool {
+        self.settings.enabled
+    }
+
+    /// Publish the metrics snapshot along with the current application gauges.
+    pub fn update(&self, snapshot: &MetricsSnapshot) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let gauges = self.gauges(snapshot);
+        *self.snapshot.lock().unwrap() = Some(Box::new(snapshot.clone()));
+        *self.gauges.lock().unwrap() = gauges;
+    }
+
+    /// Average the feerate estimates received from the node; `None` resets the average.
+    pub fn update_feerate(&self, estimate: Option<&RpcFeeEstimate>) {
+        let mut feerate = self.feerate.lock().unwrap();
+        match (estimate, feerate.as_mut()) {
+            (Some(estimate), Some(average)) => average.insert(estimate),
+            (Some(estimate), None) => *feerate = Some(FeerateEstimate::new(estimate)),
+            (None, _) => *feerate = None,
+        }
+    }
+
+    fn gauges(&self, snapshot: &MetricsSnapshot) -> ExporterGauges {
+        let feerate = self
+            .feerate
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|feerate| {
+                vec![
+                    ("low", feerate.low.value()),
+                    ("economic", feerate.economic.value()),
+                    ("priority", feerate.priority.value()),
+                ]
+            })
+            .unwrap_or_default();
+
+        let peers = runtime()
+            .peer_monitor_service()
+            .peer_info()
+            .map(|peer_info| peer_info.len());
+
+        let mut gauges = ExporterGauges {
+            peers,
+            mempool_size: Some(snapshot.get(&Metric::NetworkMempoolSize) as usize),
+            feerate,
+            ..Default::default()
+        };
+
+        if let Ok(wallet) = runtime().wallet().downcast_arc::<CoreWallet>() {
+            gauges.network = wallet
+                .network_id()
+                .ok()
+                .map(|network_id| Network::from(network_id).to_string());
+            gauges.is_synced = wallet.is_connected().then(|| wallet.is_synced());
+
+            if self.settings.wallet_balances {
+                gauges.balances = wallet
+                    .active_accounts()
+                    .inner()
+                    .values()
+                    .filter_map(|account| {
+                        account.balance().map(|balance| AccountBalanceGauge {
+                            account_id: account.id().to_string(),
+                            name: account.name_or_id(),
+                            mature_sompi: balance.mature,
+                            pending_sompi: balance.pending,
+                        })
+                    })
+                    .collect();
+            }
+        }
+
+        gauges
+    }
+
+    pub fn render(&self) -> String {
+        let snapshot = self.snapshot.lock().unwrap().clone();
+        let gauges = self.gauges.lock().unwrap().clone();
+        render(snapshot.as_deref(), &gauges)
+    }
+
+    async fn bind(&self) -> Result<TcpListener> {
+        let port = self.settings.port;
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .await
+            .map_err(|err| Error::custom(format!("unable to bind port {port}: {err}")))?;
+        log_info!("Metrics exporter listening on http://127.0.0.1:{port}/metrics");
+        Ok(listener)
+    }
+
+    async fn listen(self: &Arc<Self>, listener: TcpListener) {
+        loop {
+            select! {
+                accept = listener.accept().fuse() => {
+                    match accept {
+                        Ok((stream, _)) => {
+                            let this = self.clone();
+                            tokio::spawn(async move {
+                                if let Err(err) = this.connection(stream).await {
+                                    log_warn!("Metrics exporter connection error: {err}");
+                                }
+                            });
+                        }
+                        Err(err) => {
+                            log_warn!("Metrics exporter accept error: {err}");
+                        }
+                    }
+                },
+                msg = self.service_events.receiver.recv().fuse() => {
+                    match msg {
+                        Ok(MetricsExporterEvents::Exit) | Err(_) => break,
+                    }
+                }
+            }
+        }
+    }
+
+    async fn connection(self: Arc<Self>, mut stream: TcpStream) -> Result<()> {
+        let mut request = Vec::new();
+        let mut chunk = [0u8; 1024];
+        loop {
+            let len = stream.read(&mut chunk).await?;
+            if len == 0 {
+                break;
+            }
+            request.extend_from_slice(&chunk[..len]);
+            if request.windows(4).any(|window| window == b"\r\n\r\n")
+                || request.len() > MAX_REQUEST_SIZE
+            {
+                break;
+            }
+        }
+
+        let request = String::from_utf8_lossy(&request);
+        let mut request_line = request
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .split_whitespace();
+        let response = match (request_line.next(), request_line.next()) {
+            (Some("GET"), Some(path)) if path == "/metrics" || path.starts_with("/metrics?") => {
+                response("200 OK", OPENMETRICS_CONTENT_TYPE, self.render())
+            }
+            (Some("GET"), _) => response("404 Not Found", "text/plain", "Not Found\n".to_string()),
+            _ => response(
+                "405 Method Not Allowed",
+                "text/plain",
+                "Method Not Allowed\n".to_string(),
+            ),
+        };
+
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await?;
+        Ok(())
+    }
+}
+
+fn response(status: &str, content_type: &str, body: String) -> String {
+    format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Converts `NodeCpuUsage` to `node_cpu_usage`.
+fn snake_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len() + 8);
+    for (index, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if index > 0 {
+                result.push('_');
+            }
+            result.push(c.to_ascii_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn family(output: &mut String, name: &str, help: &str) {
+    writeln!(output, "# TYPE {name} gauge").unwrap();
+    writeln!(output, "# HELP {name} {}", escape_label(help)).unwrap();
+}
+
+fn sample(output: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    if labels.is_empty() {
+        writeln!(output, "{name} {}", format_value(value)).unwrap();
+    } else {
+        let labels = labels
+            .iter()
+            .map(|(key, value)| format!("{key}=\"{}\"", escape_label(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(output, "{name}{{{labels}}} {}", format_value(value)).unwrap();
+    }
+}
+
+/// Renders node metrics (`kaspa_*`) and application gauges (`kaspa_ng_*`)
+/// in the OpenMetrics text format.
+pub fn render(snapshot: Option<&MetricsSnapshot>, gauges: &ExporterGauges) -> String {
+    let mut output = String::new();
+
+    if let Some(snapshot) = snapshot {
+        for metric in Metric::into_iter() {
+            let name = format!("kaspa_{}", snake_case(metric.as_str()));
+            family(&mut output, &name, metric.title().0);
+            sample(&mut output, &name, &[], snapshot.get(&metric));
+        }
+    }
+
+    if let Some(network) = gauges.network.as_ref() {
+        family(
+            &mut output,
+            "kaspa_ng_info",
+            "Kaspa NG application information",
+        );
+        sample(
+            &mut output,
+            "kaspa_ng_info",
+            &[("network", network), ("version", crate::app::VERSION)],
+            1.0,
+        );
+    }
+
+    if let Some(is_synced) = gauges.is_synced {
+        family(
+            &mut output,
+            "kaspa_ng_synced",
+            "Node is synced (1) or syncing (0)",
+        );
+        sample(
+            &mut output,
+            "kaspa_ng_synced",
+            &[],
+            if is_synced { 1.0 } else { 0.0 },
+        );
+    }
+
+    if let Some(peers) = gauges.peers {
+        family(&mut output, "kaspa_ng_peers", "Number of connected peers");
+        sample(&mut output, "kaspa_ng_peers", &[], peers as f64);
+    }
+
+    if let Some(mempool_size) = gauges.mempool_size {
+        family(
+            &mut output,
+            "kaspa_ng_mempool_size",
+            "Number of transactions in the mempool",
+        );
+        sample(
+            &mut output,
+            "kaspa_ng_mempool_size",
+            &[],
+            mempool_size as f64,
+        );
+    }
+
+    if !gauges.feerate.is_empty() {
+        family(
+            &mut output,
+            "kaspa_ng_feerate",
+            "Estimated feerate in sompi per gram",
+        );
+        for (bucket, value) in gauges.feerate.iter() {
+            sample(
+                &mut output,
+                "kaspa_ng_feerate",
+                &[("bucket", bucket)],
+                value.feerate,
+            );
+        }
+        family(
+            &mut output,
+            "kaspa_ng_feerate_estimated_seconds",
+            "Estimated confirmation time of the feerate bucket in seconds",
+        );
+        for (bucket, value) in gauges.feerate.iter() {
+            sample(
+                &mut output,
+                "kaspa_ng_feerate_estimated_seconds",
+                &[("bucket", bucket)],
+                value.seconds,
+            );
+        }
+    }
+
+    if !gauges.balances.is_empty() {
+        family(
+            &mut output,
+            "kaspa_ng_account_balance_kas",
+            "Wallet account balance in KAS",
+        );
+        for balance in gauges.balances.iter() {
+            for (state, sompi) in [
+                ("mature", balance.mature_sompi),
+                ("pending", balance.pending_sompi),
+            ] {
+                sample(
+                    &mut output,
+                    "kaspa_ng_account_balance_kas",
+                    &[
+                        ("account_id", balance.account_id.as_str()),
+                        ("name", balance.name.as_str()),
+                        ("state", state),
+                    ],
+                    sompi_to_kaspa(sompi),
+                );
+            }
+        }
+    }
+
+    output.push_str("# EOF\n");
+    output
+}
+
+#[async_trait]
+impl Service for MetricsExporterService {
+    fn name(&self) -> &'static str {
+        "metrics-exporter"
+    }
+
+    async fn spawn(self: Arc<Self>) -> Result<()> {
+        let listener = if self.is_enabled() {
+            self.bind()
+                .await
+                .map_err(|err| log_error!("Metrics exporter error: {err}"))
+                .ok()
+        } else {
+            None
+        };
+
+        if let Some(listener) = listener {
+            self.listen(listener).await;
+        } else {
+            // idle until the exit signal
+            let _ = self.service_events.receiver.recv().await;
+        }
+
+        self.task_ctl.send(()).await.unwrap();
+        Ok(())
+    }
+
+    fn terminate(self: Arc<Self>) {
+        self.service_events
+            .sender
+            .try_send(MetricsExporterEvents::Exit)
+            .unwrap();
+    }
+
+    async fn join(self: Arc<Self>) -> Result<()> {
+        self.task_ctl.recv().await.unwrap();
+        Ok(())
+    }
+}
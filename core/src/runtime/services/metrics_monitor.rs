@@ -1,189 +0,0 @@
-use crate::imports::*;
-use crate::runtime::Service;
-pub use futures::{Future, future::FutureExt, select};
-use kaspa_metrics_core::{Metric, Metrics, MetricsSnapshot};
-use kaspa_rpc_core::GetSystemInfoResponse;
-#[allow(unused_imports)]
-use kaspa_wallet_core::rpc::{NotificationMode, Rpc, RpcCtl, WrpcEncoding};
-
-#[allow(clippy::identity_op)]
-pub const MAX_METRICS_SAMPLES: usize = 60 * 60 * 24 * 1; // 1 day
-
-pub struct MetricsService {
-    pub application_events: ApplicationEventsChannel,
-    pub task_ctl: Channel<()>,
-    pub metrics: Arc<Metrics>,
-    pub metrics_data: Mutex<HashMap<Metric, Vec<PlotPoint>>>,
-    pub samples_since_connection: Arc<AtomicUsize>,
-    pub rpc_api: Mutex<Option<Arc<dyn RpcApi>>>,
-}
-
-impl MetricsService {
-    pub fn new(application_events: ApplicationEventsChannel, _settings: &Settings) -> Self {
-        let metrics = Arc::new(Metrics::default());
-        let metrics_data = Metric::into_iter()
-            .map(|metric| (metric, Vec::new()))
-            .collect::<HashMap<Metric, Vec<_>>>();
-
-        Self {
-            application_events,
-            task_ctl: Channel::oneshot(),
-            metrics,
-            metrics_data: Mutex::new(metrics_data),
-            samples_since_connection: Arc::new(AtomicUsize::new(0)),
-            rpc_api: Mutex::new(None),
-        }
-    }
-
-    pub fn rpc_api(&self) -> Option<Arc<dyn RpcApi>> {
-        self.rpc_api.lock().unwrap().clone()
-    }
-
-    pub fn metrics_data(&self) -> MutexGuard<'_, HashMap<Metric, Vec<PlotPoint>>> {
-        self.metrics_data.lock().unwrap()
-    }
-
-    pub fn metrics(&self) -> &Arc<Metrics> {
-        &self.metrics
-    }
-
-    pub fn reset_metrics_data(&self) -> Result<()> {
-        let mut metrics_data = self.metrics_data.lock().unwrap();
-        for metric in Metric::into_iter() {
-            metrics_data.insert(metric, Vec::with_capacity(MAX_METRICS_SAMPLES));
-        }
-        Ok(())
-    }
-
-    pub fn ingest_metrics_snapshot(&self, snapshot: Box<MetricsSnapshot>) -> Result<()> {
-        let timestamp = snapshot.unixtime_millis;
-        let mut metrics_data = self.metrics_data.lock().unwrap();
-        for metric in Metric::into_iter() {
-            let dest = metrics_data.get_mut(&metric).unwrap();
-            if dest.is_empty() {
-                if snapshot.duration_millis < 0.0 {
-                    continue;
-                }
-                let y = snapshot.get(&metric);
-                let mut timestamp = timestamp - MAX_METRICS_SAMPLES as f64 * 1000.0;
-                for _ in 0..(MAX_METRICS_SAMPLES - 1) {
-                    dest.push(PlotPoint { x: timestamp, y });
-
-                    timestamp += 1000.0;
-                }
-            }
-            if dest.len() > MAX_METRICS_SAMPLES {
-                dest.drain(0..dest.len() - MAX_METRICS_SAMPLES);
-            }
-
-            let y = snapshot.get(&metric);
-            if y.is_finite() {
-                dest.push(PlotPoint { x: timestamp, y });
-            } else {
-                dest.push(PlotPoint {
-                    x: timestamp,
-                    y: 0.0,
-                });
-            }
-        }
-
-        if snapshot.node_cpu_cores > 0.0 {
-            self.application_events
-                .sender
-                .try_send(crate::events::Events::MempoolSize {
-                    mempool_size: snapshot.get(&Metric::NetworkMempoolSize) as usize,
-                })
-                .unwrap();
-
-            self.application_events
-                .sender
-                .try_send(crate::events::Events::Metrics { snapshot })
-                .unwrap();
-        }
-
-        self.samples_since_connection.fetch_add(1, Ordering::SeqCst);
-
-        Ok(())
-    }
-
-    pub fn samples_since_connection(&self) -> usize {
-        self.samples_since_connection.load(Ordering::SeqCst)
-    }
-}
-
-#[async_trait]
-impl Service for MetricsService {
-    fn name(&self) -> &'static str {
-        "metrics-service"
-    }
-
-    async fn attach_rpc(self: Arc<Self>, rpc_api: &Arc<dyn RpcApi>) -> Result<()> {
-        self.rpc_api.lock().unwrap().replace(rpc_api.clone());
-
-        let this = self.clone();
-        self.metrics
-            .register_sink(Arc::new(Box::new(move |snapshot: MetricsSnapshot| {
-                if let Err(err) = this.ingest_metrics_snapshot(Box::new(snapshot)) {
-                    println!("Error ingesting metrics snapshot: {}", err);
-                }
-                None
-            })));
-
-        self.reset_metrics_data()?;
-        self.metrics.start_task().await?;
-        self.metrics.bind_rpc(Some(rpc_api.clone()));
-        Ok(())
-    }
-    async fn detach_rpc(self: Arc<Self>) -> Result<()> {
-        self.rpc_api.lock().unwrap().take();
-
-        self.metrics.unregister_sink();
-        self.metrics.stop_task().await?;
-        self.metrics.bind_rpc(None);
-
-        Ok(())
-    }
-
-    async fn connect_rpc(self: Arc<Self>) -> Result<()> {
-        self.samples_since_connection.store(0, Ordering::SeqCst);
-
-        if let Some(rpc_api) = self.rpc_api()
-            && let Ok(system_info) = rpc_api.get_system_info().await
-        {
-            let GetSystemInfoResponse {
-                version, system_id, ..
-            } = system_info;
-
-            let system_id = system_id
-                .map(|id| format!(" - {}", id[0..8].to_vec().to_hex()))
-                .unwrap_or_else(|| "".to_string());
-
-            self.application_events
-                .sender
-                .try_send(crate::events::Events::NodeInfo {
-                    node_info: Some(Box::new(format!("{}{}", version, system_id))),
-                })
-                .unwrap();
-        }
-
-        Ok(())
-    }
-
-    async fn disconnect_rpc(self: Arc<Self>) -> Result<()> {
-        self.application_events
-            .sender
-            .try_send(crate::events::Events::NodeInfo { node_info: None })
-            .unwrap();
-        Ok(())
-    }
-
-    async fn spawn(self: Arc<Self>) -> Result<()> {
-        Ok(())
-    }
-
-    fn terminate(self: Arc<Self>) {}
-
-    async fn join(self: Arc<Self>) -> Result<()> {
-        Ok(())
-    }
-}
@@ -0,0 +1,256 @@
+use crate::imports::*;
+use kaspa_metrics_core::{Metric, MetricsSnapshot};
+
+/// Node health condition evaluated on each metrics snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum MetricCondition {
+    /// Metric value is above the given limit
+    Above { metric: Metric, value: f64 },
+    /// Metric value is below the given limit
+    Below { metric: Metric, value: f64 },
+    /// Metric value has not changed
+    Stalled { metric: Metric },
+}
+
+impl MetricCondition {
+    pub fn metric(&self) -> Metric {
+        match self {
+            MetricCondition::Above { metric, .. }
+            | MetricCondition::Below { metric, .. }
+            | MetricCondition::Stalled { metric } => *metric,
+        }
+    }
+}
+
+/// Rule raising an alert once its condition holds for `duration` seconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MetricRule {
+    pub enabled: bool,
+    pub condition: MetricCondition,
+    pub duration: u64,
+}
+
+impl MetricRule {
+    pub fn new(condition: MetricCondition, duration: u64) -> Self {
+        Self {
+            enabled: true,
+            condition,
+            duration,
+        }
+    }
+
+    fn key(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn describe(&self) -> String {
+        let duration = self.duration.to_string();
+        match &self.condition {
+            MetricCondition::Above { metric, value } => i18n_args(
+                "{metric} above {value} for {duration} sec",
+                &[
+                    ("metric", i18n(metric.title().0).to_string()),
+                    ("value", metric.format(*value, true, false)),
+                    ("duration", duration),
+                ],
+            ),
+            MetricCondition::Below { metric, value } => i18n_args(
+                "{metric} below {value} for {duration} sec",
+                &[
+                    ("metric", i18n(metric.title().0).to_string()),
+                    ("value", metric.format(*value, true, false)),
+                    ("duration", duration),
+                ],
+            ),
+            MetricCondition::Stalled { metric } => i18n_args(
+                "{metric} unchanged for {duration} sec",
+                &[
+                    ("metric", i18n(metric.title().0).to_string()),
+                    ("duration", duration),
+                ],
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct MetricsAlertSettings {
+    pub rules: Vec<MetricRule>,
+}
+
+impl Default for MetricsAlertSettings {
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                MetricRule::new(
+                    MetricCondition::Below {
+                        metric: Metric::NodeActivePeers,
+                        value: 3.0,
+                    },
+                    120,
+                ),
+                MetricRule::new(
+                    MetricCondition::Stalled {
+                        metric: Metric::NetworkVirtualDaaScore,
+                    },
+                    60,
+                ),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricAlertEvent {
+    pub rule: MetricRule,
+    pub value: f64,
+    /// `true` when the alert is raised, `false` when the condition clears
+    pub active: bool,
+}
+
+impl MetricAlertEvent {
+    pub fn message(&self) -> String {
+        let metric = self.rule.condition.metric();
+        if self.active {
+            i18n_args(
+                "Node alert: {rule} (current: {value})",
+                &[
+                    ("rule", self.rule.describe()),
+                    ("value", metric.format(self.value, true, false)),
+                ],
+            )
+        } else {
+            i18n_args(
+                "Node alert cleared: {metric} is {value}",
+                &[
+                    ("metric", i18n(metric.title().0).to_string()),
+                    ("value", metric.format(self.value, true, false)),
+                ],
+            )
+        }
+    }
+
+    pub fn user_notification(&self) -> UserNotification {
+        if self.active {
+            UserNotification::warning(self.message()).duration(Duration::from_secs(10))
+        } else {
+            UserNotification::info(self.message()).short()
+        }
+    }
+}
+
+#[derive(Default)]
+struct RuleState {
+    /// Time since which the condition holds
+    since: Option<f64>,
+    active: bool,
+    /// Last value and the time it changed (stall detection)
+    last: Option<(f64, f64)>,
+}
+
+/// Tracks how long each rule condition has held and raises an alert
+/// once it exceeds the rule duration. Rules are evaluated only once
+/// the node has synced, as peer counts and the DAA score are expected
+/// to be low or stalled during the initial block download. A node
+/// falling out of sync later on is what alerts are meant to catch,
+/// so rules remain in effect from then on.
+#[derive(Default)]
+pub struct MetricAlertMonitor {
+    state: AHashMap<String, RuleState>,
+    has_synced: bool,
+}
+
+impl MetricAlertMonitor {
+    pub fn reset(&mut self) {
+        self.state.clear();
+    }
+
+    /// Updates the node sync state; rules take effect once
+    /// the node has completed its initial sync.
+    pub fn update_sync_state(&mut self, is_synced: bool) {
+        self.has_synced |= is_synced;
+    }
+
+    pub fn evaluate(
+        &mut self,
+        rules: &[MetricRule],
+        snapshot: &MetricsSnapshot,
+    ) -> Vec<MetricAlertEvent> {
+        if !self.has_synced {
+            return vec![];
+        }
+
+        let timestamp = snapshot.unixtime_millis;
+
+        rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| {
+                let value = snapshot.get(&rule.condition.metric());
+                if !value.is_finite() {
+                    return None;
+                }
+
+                let state = self.state.entry(rule.key()).or_default();
+                let duration = rule.duration as f64 * 1000.0;
+
+                let holds = match &rule.condition {
+                    MetricCondition::Above { value: limit, .. } => value > *limit,
+                    MetricCondition::Below { value: limit, .. } => value < *limit,
+                    MetricCondition::Stalled { .. } => {
+                        let (last, changed) = state.last.get_or_insert((value, timestamp));
+                        if *last != value {
+                            *last = value;
+                            *changed = timestamp;
+                        }
+                        // the stall window itself is the rule duration
+                        timestamp - *changed >= duration
+                    }
+                };
+
+                if holds {
+                    let since = *state.since.get_or_insert(timestamp);
+                    let elapsed = matches!(rule.condition, MetricCondition::Stalled { .. })
+                        || timestamp - since >= duration;
+                    if elapsed && !state.active {
+                        state.active = true;
+                        return Some(MetricAlertEvent {
+                            rule: rule.clone(),
+                            value,
+                            active: true,
+                        });
+                    }
+                } else {
+                    state.since = None;
+                    if state.active {
+                        state.active = false;
+                        return Some(MetricAlertEvent {
+                            rule: rule.clone(),
+                            value,
+                            active: false,
+                        });
+                    }
+                }
+
+                None
+            })
+            .collect()
+    }
+
+    /// Rules whose alert is currently raised.
+    pub fn active(&self, rules: &[MetricRule]) -> Vec<MetricRule> {
+        rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .filter(|rule| {
+                self.state
+                    .get(&rule.key())
+                    .is_some_and(|state| state.active)
+            })
+            .cloned()
+            .collect()
+    }
+}
@@ -0,0 +1,107 @@
+//! Metrics export and comparison
+//!
+//! CSV export of metric series and [`MetricsMark`] snapshots used to
+//! compare all metric values between two moments (for example before
+//! and after changing the node configuration).
+
+use crate::imports::*;
+use kaspa_metrics_core::{Metric, MetricsSnapshot};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Renders metric series as CSV with one row per timestamp.
+///
+/// The first two columns contain the unix timestamp in milliseconds and
+/// the UTC date; samples missing from a series are left empty.
+pub fn metrics_to_csv(series: &[(Metric, Vec<PlotPoint>)]) -> String {
+    let mut rows = BTreeMap::<i64, Vec<Option<f64>>>::new();
+    for (column, (_, points)) in series.iter().enumerate() {
+        for point in points.iter() {
+            let row = rows
+                .entry(point.x as i64)
+                .or_insert_with(|| vec![None; series.len()]);
+            row[column] = Some(point.y);
+        }
+    }
+
+    let mut csv = String::from("timestamp,time");
+    for (metric, _) in series.iter() {
+        csv.push(',');
+        csv.push_str(metric.as_str());
+    }
+    csv.push('\n');
+
+    for (timestamp, values) in rows {
+        let time = chrono::DateTime::<chrono::Utc>::from_timestamp_millis(timestamp)
+            .map(|time| time.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string())
+            .unwrap_or_default();
+        write!(csv, "{timestamp},{time}").unwrap();
+        for value in values {
+            csv.push(',');
+            if let Some(value) = value.filter(|value| value.is_finite()) {
+                write!(csv, "{value}").unwrap();
+            }
+        }
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Values of all metrics captured at a given moment.
+#[derive(Debug, Clone)]
+pub struct MetricsMark {
+    /// Unix time of the snapshot in milliseconds
+    pub timestamp: f64,
+    pub values: Vec<(Metric, f64)>,
+}
+
+impl MetricsMark {
+    pub fn new(snapshot: &MetricsSnapshot) -> Self {
+        Self {
+            timestamp: snapshot.unixtime_millis,
+            values: Metric::into_iter()
+                .map(|metric| (metric, snapshot.get(&metric)))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, metric: &Metric) -> Option<f64> {
+        self.values
+            .iter()
+            .find(|(item, _)| item == metric)
+            .map(|(_, value)| *value)
+    }
+}
+
+/// Difference of a metric between two marks.
+#[derive(Debug, Clone)]
+pub struct MetricDelta {
+    pub metric: Metric,
+    pub first: f64,
+    pub second: f64,
+    pub delta: f64,
+    /// Relative change in percent, `None` if the first value is zero
+    pub percent: Option<f64>,
+}
+
+/// Compares two marks, listing metrics in the `Metric::into_iter()` order.
+pub fn compare_marks(first: &MetricsMark, second: &MetricsMark) -> Vec<MetricDelta> {
+    first
+        .values
+        .iter()
+        .filter_map(|(metric, first)| {
+            let second = second.get(metric)?;
+            let delta = second - first;
+            let percent = (*first != 0.0 && first.is_finite() && second.is_finite())
+                .then(|| delta / first.abs() * 100.0);
+            Some(MetricDelta {
+                metric: *metric,
+                first: *first,
+                second,
+                delta,
+                percent,
+            })
+        })
+        .collect()
+}
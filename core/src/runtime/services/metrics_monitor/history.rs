@@ -0,0 +1,481 @@
+//! On-disk metrics history.
+//!
+//! Metrics snapshots are stored in three tiers of decreasing resolution
+//! (1 second, 1 minute and 1 hour), each retained for a fixed number of
+//! samples. Every tier is kept in an append-only file consisting of a
+//! header listing the stored metrics followed by fixed-size rows:
+//!
+//! `[timestamp: f64][value: f32; metric count]` (little-endian)
+//!
+//! Files are compacted once they grow past twice the tier capacity.
+//!
+//! The bucket that is still being accumulated is written on flush to a
+//! `.partial` file next to the tier, holding the same header followed by
+//! `[bucket: u64][sample count: u64][sum: f64; metric count]`, and is
+//! restored on load so that samples taken before a restart are merged
+//! with those taken after it.
+
+use crate::imports::*;
+use kaspa_metrics_core::{Metric, MetricsSnapshot};
+use kaspa_wallet_core::storage::local::storage::Storage;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 4] = b"KNGM";
+const VERSION: u8 = 1;
+/// Maximum number of points returned for a single graph
+pub const MAX_SERIES_POINTS: usize = 60 * 60 * 24;
+
+pub struct TierDescriptor {
+    pub name: &'static str,
+    /// Sample interval in milliseconds
+    pub resolution: u64,
+    /// Number of samples retained
+    pub capacity: usize,
+}
+
+impl TierDescriptor {
+    /// Time span covered by a full tier in seconds
+    pub fn span(&self) -> u64 {
+        self.resolution * self.capacity as u64 / 1000
+    }
+}
+
+pub const TIERS: [TierDescriptor; 3] = [
+    TierDescriptor {
+        name: "1s",
+        resolution: 1000,
+        capacity: 60 * 60 * 24, // 1 day
+    },
+    TierDescriptor {
+        name: "1m",
+        resolution: 60 * 1000,
+        capacity: 60 * 24 * 14, // 14 days
+    },
+    TierDescriptor {
+        name: "1h",
+        resolution: 60 * 60 * 1000,
+        capacity: 24 * 365, // 1 year
+    },
+];
+
+fn metrics() -> Vec<Metric> {
+    Metric::into_iter().collect()
+}
+
+#[derive(Debug, Clone)]
+struct Row {
+    timestamp: f64,
+    values: Vec<f32>,
+}
+
+impl Row {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(&self.timestamp.to_le_bytes());
+        for value in self.values.iter() {
+            buffer.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+struct Accumulator {
+    bucket: u64,
+    sums: Vec<f64>,
+    count: usize,
+}
+
+impl Accumulator {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(&self.bucket.to_le_bytes());
+        buffer.extend_from_slice(&(self.count as u64).to_le_bytes());
+        for sum in self.sums.iter() {
+            buffer.extend_from_slice(&sum.to_le_bytes());
+        }
+    }
+
+    /// Decodes an accumulator stored with the given columns,
+    /// mapping them to the current metric list.
+    fn decode(data: &[u8], columns: &[String], metrics: &[Metric]) -> Option<Self> {
+        if data.len() != 16 + columns.len() * 8 {
+            return None;
+        }
+        let bucket = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let count = u64::from_le_bytes(data[8..16].try_into().unwrap()) as usize;
+        let sums = metrics
+            .iter()
+            .map(|metric| {
+                columns
+                    .iter()
+                    .position(|name| name == metric.as_str())
+                    .map(|column| {
+                        let start = 16 + column * 8;
+                        f64::from_le_bytes(data[start..start + 8].try_into().unwrap())
+                    })
+                    .unwrap_or_default()
+            })
+            .collect();
+        (count > 0).then_some(Self {
+            bucket,
+            sums,
+            count,
+        })
+    }
+
+    fn row(&self, resolution: u64) -> Row {
+        Row {
+            timestamp: (self.bucket * resolution) as f64,
+            values: self
+                .sums
+                .iter()
+                .map(|sum| (sum / self.count as f64) as f32)
+                .collect(),
+        }
+    }
+}
+
+struct Tier {
+    descriptor: &'static TierDescriptor,
+    path: PathBuf,
+    rows: VecDeque<Row>,
+    accumulator: Option<Accumulator>,
+    writer: Option<BufWriter<File>>,
+    rows_in_file: usize,
+}
+
+fn header(metrics: &[Metric]) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    buffer.extend_from_slice(MAGIC);
+    buffer.push(VERSION);
+    buffer.extend_from_slice(&(metrics.len() as u16).to_le_bytes());
+    for metric in metrics {
+        let name = metric.as_str().as_bytes();
+        buffer.push(name.len() as u8);
+        buffer.extend_from_slice(name);
+    }
+    buffer
+}
+
+/// Parses the file header, returning the column names and the header length.
+fn parse_header(data: &[u8]) -> Option<(Vec<String>, usize)> {
+    if data.len() < 7 || &data[0..4] != MAGIC || data[4] != VERSION {
+        return None;
+    }
+    let count = u16::from_le_bytes([data[5], data[6]]) as usize;
+    let mut offset = 7;
+    let mut columns = Vec::with_capacity(count);
+    for _ in 0..count {
+        let len = *data.get(offset)? as usize;
+        let name = data.get(offset + 1..offset + 1 + len)?;
+        columns.push(String::from_utf8_lossy(name).to_string());
+        offset += 1 + len;
+    }
+    Some((columns, offset))
+}
+
+impl Tier {
+    fn load(descriptor: &'static TierDescriptor, path: PathBuf, now: f64) -> Result<Self> {
+        let metrics = metrics();
+        let mut rows = VecDeque::with_capacity(descriptor.capacity);
+        let mut rewrite = true;
+        let mut rows_in_file = 0;
+
+        if path.exists() {
+            let data = std::fs::read(&path)?;
+            if let Some((columns, offset)) = parse_header(&data) {
+                // map stored columns to the current metric list
+                let mapping = metrics
+                    .iter()
+                    .map(|metric| columns.iter().position(|name| name == metric.as_str()))
+                    .collect::<Vec<_>>();
+                let row_len = 8 + columns.len() * 4;
+                let body = &data[offset..];
+                let oldest = now - (descriptor.resolution as f64 * descriptor.capacity as f64);
+
+                for chunk in body.chunks_exact(row_len) {
+                    let timestamp = f64::from_le_bytes(chunk[0..8].try_into().unwrap());
+                    rows_in_file += 1;
+                    if timestamp < oldest {
+                        continue;
+                    }
+                    let values = mapping
+                        .iter()
+                        .map(|column| {
+                            column
+                                .map(|column| {
+                                    let start = 8 + column * 4;
+                                    f32::from_le_bytes(chunk[start..start + 4].try_into().unwrap())
+                                })
+                                .unwrap_or_default()
+                        })
+                        .collect();
+                    rows.push_back(Row { timestamp, values });
+                }
+
+                while rows.len() > descriptor.capacity {
+                    rows.pop_front();
+                }
+
+                let columns_match = mapping
+                    .iter()
+                    .enumerate()
+                    .all(|(index, column)| *column == Some(index))
+                    && columns.len() == metrics.len();
+                rewrite = !columns_match || body.len() % row_len != 0 || rows.len() != rows_in_file;
+            }
+        }
+
+        let mut tier = Self {
+            descriptor,
+            path,
+            rows,
+            accumulator: None,
+            writer: None,
+            rows_in_file,
+        };
+        tier.accumulator = tier.load_partial(now);
+
+        if rewrite {
+            tier.compact()?;
+        } else {
+            let file = OpenOptions::new().append(true).open(&tier.path)?;
+            tier.writer = Some(BufWriter::new(file));
+        }
+
+        Ok(tier)
+    }
+
+    fn partial_path(&self) -> PathBuf {
+        self.path.with_extension("partial")
+    }
+
+    /// Restores the bucket that was open when the tier was last flushed,
+    /// unless it has expired or has already been written as a row.
+    fn load_partial(&self, now: f64) -> Option<Accumulator> {
+        let data = std::fs::read(self.partial_path()).ok()?;
+        let (columns, offset) = parse_header(&data)?;
+        let accumulator = Accumulator::decode(&data[offset..], &columns, &metrics())?;
+
+        let resolution = self.descriptor.resolution;
+        let timestamp = (accumulator.bucket * resolution) as f64;
+        let oldest = now - (resolution as f64 * self.descriptor.capacity as f64);
+        let written = self
+            .rows
+            .back()
+            .is_some_and(|row| row.timestamp >= timestamp);
+        (timestamp >= oldest && !written).then_some(accumulator)
+    }
+
+    fn store_partial(&self) -> Result<()> {
+        let path = self.partial_path();
+        match self.accumulator.as_ref() {
+            Some(accumulator) => {
+                let mut buffer = header(&metrics());
+                accumulator.encode(&mut buffer);
+                let temp = path.with_extension("partial.tmp");
+                std::fs::write(&temp, &buffer)?;
+                std::fs::rename(&temp, &path)?;
+            }
+            None if path.exists() => std::fs::remove_file(&path)?,
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Rewrites the tier file with the retained rows.
+    fn compact(&mut self) -> Result<()> {
+        if let Some(mut writer) = self.writer.take() {
+            writer.flush()?;
+        }
+
+        let mut buffer = header(&metrics());
+        for row in self.rows.iter() {
+            row.encode(&mut buffer);
+        }
+
+        let temp = self.path.with_extension("tmp");
+        std::fs::write(&temp, &buffer)?;
+        std::fs::rename(&temp, &self.path)?;
+
+        let file = OpenOptions::new().append(true).open(&self.path)?;
+        self.writer = Some(BufWriter::new(file));
+        self.rows_in_file = self.rows.len();
+        Ok(())
+    }
+
+    fn push(&mut self, row: Row) -> Result<()> {
+        if let Some(writer) = self.writer.as_mut() {
+            let mut buffer = Vec::with_capacity(8 + row.values.len() * 4);
+            row.encode(&mut buffer);
+            writer.write_all(&buffer)?;
+        }
+
+        self.rows.push_back(row);
+        if self.rows.len() > self.descriptor.capacity {
+            self.rows.pop_front();
+        }
+
+        self.rows_in_file += 1;
+        if self.rows_in_file > self.descriptor.capacity * 2 {
+            self.compact()?;
+        }
+
+        Ok(())
+    }
+
+    fn ingest(&mut self, timestamp: f64, values: &[f32]) -> Result<()> {
+        let resolution = self.descriptor.resolution;
+        let bucket = timestamp as u64 / resolution;
+
+        if let Some(accumulator) = self.accumulator.as_ref()
+            && accumulator.bucket != bucket
+        {
+            let row = accumulator.row(resolution);
+            self.accumulator = None;
+            self.push(row)?;
+        }
+
+        let accumulator = self.accumulator.get_or_insert_with(|| Accumulator {
+            bucket,
+            sums: vec![0.0; values.len()],
+            count: 0,
+        });
+        accumulator
+            .sums
+            .iter_mut()
+            .zip(values.iter())
+            .for_each(|(sum, value)| *sum += *value as f64);
+        accumulator.count += 1;
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.as_mut() {
+            writer.flush()?;
+        }
+        self.store_partial()
+    }
+
+    fn oldest(&self) -> Option<f64> {
+        self.rows.front().map(|row| row.timestamp)
+    }
+
+    fn series(&self, column: usize, from: f64, to: f64) -> Vec<PlotPoint> {
+        let start = self.rows.partition_point(|row| row.timestamp < from);
+        self.rows
+            .range(start..)
+            .take_while(|row| row.timestamp <= to)
+            .map(|row| PlotPoint {
+                x: row.timestamp,
+                y: row.values[column] as f64,
+            })
+            .collect()
+    }
+}
+
+/// Metrics history of a single network, stored in the given folder.
+pub struct MetricsHistory {
+    network: String,
+    tiers: Vec<Tier>,
+}
+
+impl MetricsHistory {
+    /// Default location of the metrics history files.
+    pub fn folder() -> Result<PathBuf> {
+        let storage = Storage::try_new("kaspa-ng.metrics")?;
+        Ok(storage.filename().clone())
+    }
+
+    pub fn load(folder: &Path, network: &str) -> Result<Self> {
+        std::fs::create_dir_all(folder)?;
+        let now = unixtime_as_millis_f64();
+        let tiers = TIERS
+            .iter()
+            .map(|descriptor| {
+                let path = folder.join(format!("{network}-{}.bin", descriptor.name));
+                Tier::load(descriptor, path, now)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            network: network.to_string(),
+            tiers,
+        })
+    }
+
+    pub fn network(&self) -> &str {
+        &self.network
+    }
+
+    pub fn ingest(&mut self, snapshot: &MetricsSnapshot) -> Result<()> {
+        let values = Metric::into_iter()
+            .map(|metric| {
+                let value = snapshot.get(&metric);
+                if value.is_finite() { value as f32 } else { 0.0 }
+            })
+            .collect::<Vec<_>>();
+        self.append(snapshot.unixtime_millis, &values)
+    }
+
+    /// Appends metric values, ordered as `Metric::into_iter()`, to all tiers.
+    pub fn append(&mut self, timestamp: f64, values: &[f32]) -> Result<()> {
+        for tier in self.tiers.iter_mut() {
+            tier.ingest(timestamp, values)?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        for tier in self.tiers.iter_mut() {
+            tier.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Timestamp of the oldest stored sample.
+    pub fn oldest(&self) -> Option<f64> {
+        self.tiers
+            .iter()
+            .filter_map(|tier| tier.oldest())
+            .min_by(|a, b| a.total_cmp(b))
+    }
+
+    /// Samples of the metric between the given timestamps taken from the
+    /// finest tier that covers the range within [`MAX_SERIES_POINTS`].
+    pub fn series(&self, metric: Metric, from: f64, to: f64) -> Vec<PlotPoint> {
+        let Some(column) = Metric::into_iter().position(|item| item == metric) else {
+            return vec![];
+        };
+
+        let span = (to - from).max(0.0);
+        let candidates = self
+            .tiers
+            .iter()
+            .filter(|tier| tier.oldest().is_some())
+            .filter(|tier| span / tier.descriptor.resolution as f64 <= MAX_SERIES_POINTS as f64)
+            .collect::<Vec<_>>();
+
+        let tier = candidates
+            .iter()
+            .find(|tier| tier.oldest().is_some_and(|oldest| oldest <= from))
+            .or_else(|| {
+                candidates.iter().min_by(|a, b| {
+                    a.oldest()
+                        .unwrap_or(f64::MAX)
+                        .total_cmp(&b.oldest().unwrap_or(f64::MAX))
+                })
+            });
+
+        tier.map(|tier| tier.series(column, from, to))
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for MetricsHistory {
+    fn drop(&mut self) {
+        if let Err(err) = self.flush() {
+            log_warn!("Unable to flush metrics history: {err}");
+        }
+    }
+}
@@ -0,0 +1,401 @@
+use crate::imports::*;
+use crate::runtime::Service;
+pub use futures::{Future, future::FutureExt, select};
+use kaspa_metrics_core::{Metric, Metrics, MetricsSnapshot};
+use kaspa_rpc_core::GetSystemInfoResponse;
+#[allow(unused_imports)]
+use kaspa_wallet_core::rpc::{NotificationMode, Rpc, RpcCtl, WrpcEncoding};
+
+pub mod alerts;
+pub use alerts::{MetricAlertMonitor, MetricCondition, MetricRule, MetricsAlertSettings};
+
+pub mod export;
+pub use export::{MetricDelta, MetricsMark, compare_marks, metrics_to_csv};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod history;
+#[cfg(not(target_arch = "wasm32"))]
+pub use history::MetricsHistory;
+
+#[allow(clippy::identity_op)]
+pub const MAX_METRICS_SAMPLES: usize = 60 * 60 * 24 * 1; // 1 day
+/// Interval at which the metrics history is written to disk
+pub const HISTORY_FLUSH_INTERVAL_SECONDS: u64 = 60;
+
+pub enum MetricsServiceEvents {
+    Exit,
+}
+
+pub struct MetricsService {
+    pub application_events: ApplicationEventsChannel,
+    pub service_events: Channel<MetricsServiceEvents>,
+    pub task_ctl: Channel<()>,
+    pub metrics: Arc<Metrics>,
+    pub metrics_data: Mutex<HashMap<Metric, Vec<PlotPoint>>>,
+    pub samples_since_connection: Arc<AtomicUsize>,
+    pub rpc_api: Mutex<Option<Arc<dyn RpcApi>>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub history: Mutex<Option<MetricsHistory>>,
+    pub alert_settings: Mutex<MetricsAlertSettings>,
+    pub alert_monitor: Mutex<MetricAlertMonitor>,
+}
+
+impl MetricsService {
+    pub fn new(application_events: ApplicationEventsChannel, settings: &Settings) -> Self {
+        let metrics = Arc::new(Metrics::default());
+        let metrics_data = Metric::into_iter()
+            .map(|metric| (metric, Vec::new()))
+            .collect::<HashMap<Metric, Vec<_>>>();
+
+        Self {
+            application_events,
+            service_events: Channel::unbounded(),
+            task_ctl: Channel::oneshot(),
+            metrics,
+            metrics_data: Mutex::new(metrics_data),
+            samples_since_connection: Arc::new(AtomicUsize::new(0)),
+            rpc_api: Mutex::new(None),
+            #[cfg(not(target_arch = "wasm32"))]
+            history: Mutex::new(None),
+            alert_settings: Mutex::new(settings.metrics_alerts.clone()),
+            alert_monitor: Mutex::new(MetricAlertMonitor::default()),
+        }
+    }
+
+    pub fn rpc_api(&self) -> Option<Arc<dyn RpcApi>> {
+        self.rpc_api.lock().unwrap().clone()
+    }
+
+    pub fn metrics_data(&self) -> MutexGuard<'_, HashMap<Metric, Vec<PlotPoint>>> {
+        self.metrics_data.lock().unwrap()
+    }
+
+    pub fn metrics(&self) -> &Arc<Metrics> {
+        &self.metrics
+    }
+
+    /// Time span of the available metrics history in seconds.
+    pub fn history_span(&self) -> u64 {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(oldest) = self
+            .history
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|history| history.oldest())
+        {
+            let span = ((unixtime_as_millis_f64() - oldest) / 1000.0) as u64;
+            return span.max(MAX_METRICS_SAMPLES as u64);
+        }
+
+        MAX_METRICS_SAMPLES as u64
+    }
+
+    /// Samples of the metric within the given range of seconds relative to
+    /// the current time, taken from the stored history if available.
+    pub fn graph_data(&self, metric: Metric, range: std::ops::Range<isize>) -> Vec<PlotPoint> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(history) = self.history.lock().unwrap().as_ref() {
+            let now = unixtime_as_millis_f64();
+            return history.series(
+                metric,
+                now + range.start as f64 * 1000.0,
+                now + range.end as f64 * 1000.0,
+            );
+        }
+
+        let metrics_data = self.metrics_data();
+        let data = metrics_data.get(&metric).unwrap();
+        let start = range
+            .start
+            .clamp(-(MAX_METRICS_SAMPLES as isize), 0)
+            .unsigned_abs()
+            .min(data.len());
+        let end = range
+            .end
+            .clamp(-(MAX_METRICS_SAMPLES as isize), 0)
+            .unsigned_abs()
+            .min(data.len());
+        data[data.len() - start..data.len() - end].to_vec()
+    }
+
+    /// CSV of the given metrics within the given range of seconds
+    /// relative to the current time (see [`graph_data`](Self::graph_data)).
+    pub fn export_csv(&self, metrics: &[Metric], range: std::ops::Range<isize>) -> String {
+        let series = metrics
+            .iter()
+            .map(|metric| (*metric, self.graph_data(*metric, range.clone())))
+            .collect::<Vec<_>>();
+        metrics_to_csv(&series)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn load_history(&self, rpc_api: &Arc<dyn RpcApi>) -> Result<()> {
+        let network = rpc_api.get_server_info().await?.network_id.to_string();
+        let loaded = self
+            .history
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|history| history.network() == network);
+        if !loaded {
+            let folder = MetricsHistory::folder()?;
+            let history =
+                tokio::task::spawn_blocking(move || MetricsHistory::load(&folder, &network))
+                    .await
+                    .map_err(|err| {
+                        Error::custom(format!("Unable to load metrics history: {err}"))
+                    })??;
+            self.history.lock().unwrap().replace(history);
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn flush_history(&self) {
+        if let Some(history) = self.history.lock().unwrap().as_mut()
+            && let Err(err) = history.flush()
+        {
+            log_warn!("Unable to flush metrics history: {err}");
+        }
+    }
+
+    pub fn reset_metrics_data(&self) -> Result<()> {
+        let mut metrics_data = self.metrics_data.lock().unwrap();
+        for metric in Metric::into_iter() {
+            metrics_data.insert(metric, Vec::with_capacity(MAX_METRICS_SAMPLES));
+        }
+        Ok(())
+    }
+
+    /// Applies updated alert rules, re-evaluating them from scratch.
+    pub fn update_alert_settings(&self, settings: &MetricsAlertSettings) {
+        *self.alert_settings.lock().unwrap() = settings.clone();
+        self.alert_monitor.lock().unwrap().reset();
+    }
+
+    /// Suspends alert rules until the node has completed its initial sync.
+    pub fn update_sync_state(&self, is_synced: bool) {
+        self.alert_monitor
+            .lock()
+            .unwrap()
+            .update_sync_state(is_synced);
+    }
+
+    /// Rules whose alert is currently raised.
+    pub fn active_alerts(&self) -> Vec<MetricRule> {
+        let settings = self.alert_settings.lock().unwrap();
+        self.alert_monitor.lock().unwrap().active(&settings.rules)
+    }
+
+    fn evaluate_alerts(&self, snapshot: &MetricsSnapshot) {
+        let events = {
+            let settings = self.alert_settings.lock().unwrap();
+            self.alert_monitor
+                .lock()
+                .unwrap()
+                .evaluate(&settings.rules, snapshot)
+        };
+
+        for event in events {
+            self.application_events
+                .sender
+                .try_send(crate::events::Events::Notify {
+                    user_notification: event.user_notification(),
+                })
+                .ok();
+        }
+    }
+
+    pub fn ingest_metrics_snapshot(&self, snapshot: Box<MetricsSnapshot>) -> Result<()> {
+        let timestamp = snapshot.unixtime_millis;
+        let mut metrics_data = self.metrics_data.lock().unwrap();
+        for metric in Metric::into_iter() {
+            let dest = metrics_data.get_mut(&metric).unwrap();
+            if dest.is_empty() {
+                if snapshot.duration_millis < 0.0 {
+                    continue;
+                }
+                let y = snapshot.get(&metric);
+                let mut timestamp = timestamp - MAX_METRICS_SAMPLES as f64 * 1000.0;
+                for _ in 0..(MAX_METRICS_SAMPLES - 1) {
+                    dest.push(PlotPoint { x: timestamp, y });
+
+                    timestamp += 1000.0;
+                }
+            }
+            if dest.len() > MAX_METRICS_SAMPLES {
+                dest.drain(0..dest.len() - MAX_METRICS_SAMPLES);
+            }
+
+            let y = snapshot.get(&metric);
+            if y.is_finite() {
+                dest.push(PlotPoint { x: timestamp, y });
+            } else {
+                dest.push(PlotPoint {
+                    x: timestamp,
+                    y: 0.0,
+                });
+            }
+        }
+
+        drop(metrics_data);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if snapshot.duration_millis >= 0.0
+            && let Some(history) = self.history.lock().unwrap().as_mut()
+            && let Err(err) = history.ingest(&snapshot)
+        {
+            log_warn!("Unable to store metrics history: {err}");
+        }
+
+        if snapshot.duration_millis >= 0.0 {
+            self.evaluate_alerts(&snapshot);
+        }
+
+        if snapshot.node_cpu_cores > 0.0 {
+            #[cfg(not(target_arch = "wasm32"))]
+            runtime().metrics_exporter_service().update(&snapshot);
+
+            self.application_events
+                .sender
+                .try_send(crate::events::Events::MempoolSize {
+                    mempool_size: snapshot.get(&Metric::NetworkMempoolSize) as usize,
+                })
+                .unwrap();
+
+            self.application_events
+                .sender
+                .try_send(crate::events::Events::Metrics { snapshot })
+                .unwrap();
+        }
+
+        self.samples_since_connection.fetch_add(1, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    pub fn samples_since_connection(&self) -> usize {
+        self.samples_since_connection.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl Service for MetricsService {
+    fn name(&self) -> &'static str {
+        "metrics-service"
+    }
+
+    async fn attach_rpc(self: Arc<Self>, rpc_api: &Arc<dyn RpcApi>) -> Result<()> {
+        self.rpc_api.lock().unwrap().replace(rpc_api.clone());
+
+        let this = self.clone();
+        self.metrics
+            .register_sink(Arc::new(Box::new(move |snapshot: MetricsSnapshot| {
+                if let Err(err) = this.ingest_metrics_snapshot(Box::new(snapshot)) {
+                    println!("Error ingesting metrics snapshot: {}", err);
+                }
+                None
+            })));
+
+        self.reset_metrics_data()?;
+        self.metrics.start_task().await?;
+        self.metrics.bind_rpc(Some(rpc_api.clone()));
+        Ok(())
+    }
+    async fn detach_rpc(self: Arc<Self>) -> Result<()> {
+        self.rpc_api.lock().unwrap().take();
+
+        self.metrics.unregister_sink();
+        self.metrics.stop_task().await?;
+        self.metrics.bind_rpc(None);
+
+        Ok(())
+    }
+
+    async fn connect_rpc(self: Arc<Self>) -> Result<()> {
+        self.samples_since_connection.store(0, Ordering::SeqCst);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(rpc_api) = self.rpc_api()
+            && let Err(err) = self.load_history(&rpc_api).await
+        {
+            log_warn!("Unable to load metrics history: {err}");
+        }
+
+        if let Some(rpc_api) = self.rpc_api()
+            && let Ok(system_info) = rpc_api.get_system_info().await
+        {
+            let GetSystemInfoResponse {
+                version, system_id, ..
+            } = system_info;
+
+            let system_id = system_id
+                .map(|id| format!(" - {}", id[0..8].to_vec().to_hex()))
+                .unwrap_or_else(|| "".to_string());
+
+            self.application_events
+                .sender
+                .try_send(crate::events::Events::NodeInfo {
+                    node_info: Some(Box::new(format!("{}{}", version, system_id))),
+                })
+                .unwrap();
+        }
+
+        Ok(())
+    }
+
+    async fn disconnect_rpc(self: Arc<Self>) -> Result<()> {
+        // the next node connected to may be running its initial sync
+        *self.alert_monitor.lock().unwrap() = MetricAlertMonitor::default();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.flush_history();
+
+        self.application_events
+            .sender
+            .try_send(crate::events::Events::NodeInfo { node_info: None })
+            .unwrap();
+        Ok(())
+    }
+
+    async fn spawn(self: Arc<Self>) -> Result<()> {
+        let interval = task::interval(Duration::from_secs(HISTORY_FLUSH_INTERVAL_SECONDS));
+        pin_mut!(interval);
+
+        loop {
+            select! {
+                _ = interval.next().fuse() => {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    {
+                        let this = self.clone();
+                        tokio::task::spawn_blocking(move || this.flush_history()).await.ok();
+                    }
+                },
+                msg = self.service_events.receiver.recv().fuse() => {
+                    match msg {
+                        Ok(MetricsServiceEvents::Exit) | Err(_) => break,
+                    }
+                }
+            }
+        }
+
+        self.task_ctl.send(()).await.unwrap();
+        Ok(())
+    }
+
+    fn terminate(self: Arc<Self>) {
+        #[cfg(not(target_arch = "wasm32"))]
+        self.flush_history();
+
+        self.service_events
+            .sender
+            .try_send(MetricsServiceEvents::Exit)
+            .unwrap();
+    }
+
+    async fn join(self: Arc<Self>) -> Result<()> {
+        self.task_ctl.recv().await.unwrap();
+        Ok(())
+    }
+}
@@ -13,18 +13,27 @@ pub mod feerate_monitor;
 pub use feerate_monitor::FeerateMonitorService;
 
 pub mod market_monitor;
-pub use market_monitor::MarketMonitorService;
+pub use market_monitor::{
+    CoinMarketCapSettings, CustomEndpointSettings, MarketDataProvider, MarketDataProviderKind,
+    MarketMonitorService, MarketMonitorSettings,
+};
 
 pub mod update_monitor;
 pub use update_monitor::UpdateMonitorService;
 
 pub mod metrics_monitor;
 pub use metrics_monitor::MetricsService;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod metrics_exporter;
+#[cfg(not(target_arch = "wasm32"))]
+pub use metrics_exporter::MetricsExporterService;
+
 cfg_if! {
     if #[cfg(not(feature = "lean"))] {
 
         pub mod blockdag_monitor;
-        pub use blockdag_monitor::BlockDagMonitorService;
+        pub use blockdag_monitor::{BlockDagMonitorService, BlockDagPlayback};
     }
 }
 
@@ -1,7 +1,131 @@
 use crate::imports::*;
-use kaspa_rpc_core::RpcPeerInfo;
+use kaspa_rpc_core::{RpcContextualPeerAddress, RpcIpAddress, RpcPeerInfo};
+use std::net::IpAddr;
 
 pub const PEER_POLLING_INTERVAL_SECONDS: u64 = 1; // 1 sec
+/// Service resolving the country of a peer IP address (`GET /{ip}` yields
+/// `{ "ip": "...", "country": "US" }`). Peer addresses are disclosed to the
+/// service, so lookups are performed only once enabled by the user.
+pub const PEER_GEOLOCATION_URL: &str = "https://api.country.is";
+/// Maximum number of peer addresses resolved per polling interval
+const PEER_GEOLOCATION_LOOKUPS: usize = 4;
+
+/// Column used to sort the peer table.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerSortColumn {
+    #[default]
+    Address,
+    UserAgent,
+    ProtocolVersion,
+    Direction,
+    Ping,
+    TimeConnected,
+}
+
+pub fn sort_peers(peers: &mut [&RpcPeerInfo], column: PeerSortColumn, ascending: bool) {
+    peers.sort_by(|a, b| {
+        let ordering = match column {
+            PeerSortColumn::Address => a.address.to_string().cmp(&b.address.to_string()),
+            PeerSortColumn::UserAgent => a.user_agent.cmp(&b.user_agent),
+            PeerSortColumn::ProtocolVersion => a
+                .advertised_protocol_version
+                .cmp(&b.advertised_protocol_version),
+            PeerSortColumn::Direction => a.is_outbound.cmp(&b.is_outbound),
+            PeerSortColumn::Ping => a.last_ping_duration.cmp(&b.last_ping_duration),
+            PeerSortColumn::TimeConnected => a.time_connected.cmp(&b.time_connected),
+        };
+        if ascending {
+            ordering
+        } else {
+            ordering.reverse()
+        }
+    });
+}
+
+/// Extracts the node software and version from a peer user agent
+/// (`/kaspad:1.0.0/kaspad:1.0.0(kdx)/` yields `kaspad:1.0.0`).
+pub fn user_agent_version(user_agent: &str) -> &str {
+    user_agent
+        .split('/')
+        .map(str::trim)
+        .find(|part| !part.is_empty())
+        .unwrap_or("unknown")
+}
+
+// number of occurrences per label, most common labels first
+fn histogram<'a>(labels: impl IntoIterator<Item = &'a str>) -> Vec<(String, usize)> {
+    let mut counts = AHashMap::<&str, usize>::new();
+    for label in labels {
+        *counts.entry(label).or_default() += 1;
+    }
+
+    let mut histogram = counts
+        .into_iter()
+        .map(|(label, count)| (label.to_string(), count))
+        .collect::<Vec<_>>();
+    histogram.sort_by(|(a_label, a_count), (b_label, b_count)| {
+        b_count.cmp(a_count).then_with(|| a_label.cmp(b_label))
+    });
+    histogram
+}
+
+/// Number of peers per node version, most common versions first.
+pub fn version_histogram<'a>(
+    user_agents: impl IntoIterator<Item = &'a str>,
+) -> Vec<(String, usize)> {
+    histogram(user_agents.into_iter().map(user_agent_version))
+}
+
+/// Checks if the address can be located (i.e. it is not a loopback,
+/// private or link-local address).
+pub fn is_public_address(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast())
+        }
+        IpAddr::V6(ip) => {
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_unique_local()
+                || ip.is_unicast_link_local())
+        }
+    }
+}
+
+/// Number of peers per country, most common countries first. Peers
+/// on private networks are counted as `local`, peers that have not
+/// been located as `unknown`.
+pub fn country_histogram(
+    addresses: impl IntoIterator<Item = IpAddr>,
+    countries: &AHashMap<IpAddr, String>,
+) -> Vec<(String, usize)> {
+    let labels = addresses
+        .into_iter()
+        .map(|ip| {
+            if !is_public_address(&ip) {
+                "local"
+            } else {
+                countries.get(&ip).map(String::as_str).unwrap_or("unknown")
+            }
+        })
+        .collect::<Vec<_>>();
+    histogram(labels)
+}
+
+#[derive(Debug, Deserialize)]
+struct PeerGeolocation {
+    country: String,
+}
+
+async fn lookup_country(ip: IpAddr) -> Result<String> {
+    let location =
+        http::get_json::<PeerGeolocation>(format!("{PEER_GEOLOCATION_URL}/{ip}")).await?;
+    Ok(location.country)
+}
 
 pub enum PeerMonitorEvents {
     Enable,
@@ -16,6 +140,10 @@ pub struct PeerMonitorService {
     pub rpc_api: Mutex<Option<Arc<dyn RpcApi>>>,
     pub peer_info: Mutex<Option<Arc<Vec<RpcPeerInfo>>>>,
     pub is_enabled: Arc<AtomicBool>,
+    /// Countries of located peer addresses
+    pub countries: Arc<Mutex<AHashMap<IpAddr, String>>>,
+    pub is_geolocation_enabled: Arc<AtomicBool>,
+    is_geolocation_pending: Arc<AtomicBool>,
 }
 
 impl PeerMonitorService {
@@ -27,6 +155,9 @@ impl PeerMonitorService {
             rpc_api: Mutex::new(None),
             peer_info: Mutex::new(None),
             is_enabled: Arc::new(AtomicBool::new(false)),
+            countries: Arc::new(Mutex::new(AHashMap::new())),
+            is_geolocation_enabled: Arc::new(AtomicBool::new(false)),
+            is_geolocation_pending: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -38,6 +169,84 @@ impl PeerMonitorService {
         self.peer_info.lock().unwrap().clone()
     }
 
+    pub fn countries(&self) -> AHashMap<IpAddr, String> {
+        self.countries.lock().unwrap().clone()
+    }
+
+    /// Enables peer address lookups with [`PEER_GEOLOCATION_URL`].
+    pub fn enable_geolocation(&self) {
+        self.is_geolocation_enabled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_geolocation_enabled(&self) -> bool {
+        self.is_geolocation_enabled.load(Ordering::Relaxed)
+    }
+
+    // locate a few of the connected peers that have not been located yet
+    fn update_countries(&self) {
+        if !self.is_geolocation_enabled()
+            || self.is_geolocation_pending.swap(true, Ordering::SeqCst)
+        {
+            return;
+        }
+
+        let addresses = {
+            let countries = self.countries.lock().unwrap();
+            self.peer_info()
+                .iter()
+                .flat_map(|peers| peers.iter())
+                .map(|peer| *peer.address.ip)
+                .filter(|ip| is_public_address(ip) && !countries.contains_key(ip))
+                .collect::<AHashSet<_>>()
+                .into_iter()
+                .take(PEER_GEOLOCATION_LOOKUPS)
+                .collect::<Vec<_>>()
+        };
+
+        let countries = self.countries.clone();
+        let is_geolocation_pending = self.is_geolocation_pending.clone();
+        spawn(async move {
+            for ip in addresses {
+                // failed lookups are not retried
+                let country = lookup_country(ip).await.unwrap_or_else(|err| {
+                    log_warn!("Unable to locate peer {ip}: {err}");
+                    "unknown".to_string()
+                });
+                countries.lock().unwrap().insert(ip, country);
+            }
+            is_geolocation_pending.store(false, Ordering::SeqCst);
+            Ok(())
+        });
+    }
+
+    fn rpc_api_or_err(&self) -> Result<Arc<dyn RpcApi>> {
+        self.rpc_api()
+            .ok_or_else(|| Error::custom("Not connected to a node"))
+    }
+
+    /// Connects the node to the given peer.
+    pub async fn add_peer(
+        &self,
+        address: RpcContextualPeerAddress,
+        is_permanent: bool,
+    ) -> Result<()> {
+        self.rpc_api_or_err()?
+            .add_peer(address, is_permanent)
+            .await?;
+        Ok(())
+    }
+
+    /// Bans the given IP address and disconnects all peers using it.
+    pub async fn ban(&self, ip: RpcIpAddress) -> Result<()> {
+        self.rpc_api_or_err()?.ban(ip).await?;
+        Ok(())
+    }
+
+    pub async fn unban(&self, ip: RpcIpAddress) -> Result<()> {
+        self.rpc_api_or_err()?.unban(ip).await?;
+        Ok(())
+    }
+
     pub fn enable(&self) {
         self.service_events
             .sender
@@ -88,6 +297,7 @@ impl Service for PeerMonitorService {
                     if let Some(rpc_api) = this.rpc_api()
                         && let Ok(resp) = rpc_api.get_connected_peer_info().await {
                             this.peer_info.lock().unwrap().replace(Arc::new(resp.peer_info));
+                            this.update_countries();
                         }
                 },
                 msg = this.as_ref().service_events.receiver.recv().fuse() => {
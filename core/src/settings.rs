@@ -1,4 +1,6 @@
 use crate::imports::*;
+use crate::runtime::services::MarketMonitorSettings;
+use crate::runtime::services::metrics_monitor::MetricsAlertSettings;
 use kaspa_metrics_core::Metric;
 use kaspa_utils::networking::ContextualNetAddress;
 use kaspa_wallet_core::storage::local::storage::Storage;
@@ -380,6 +382,9 @@ pub struct NodeSettings {
     pub enable_grpc: bool,
     pub grpc_network_interface: NetworkInterfaceConfig,
     pub enable_upnp: bool,
+    /// Enables unsafe RPC methods used for peer management
+    #[serde(default)]
+    pub enable_unsafe_rpc: bool,
     pub memory_scale: NodeMemoryScale,
 
     pub network: Network,
@@ -407,6 +412,7 @@ impl Default for NodeSettings {
             enable_grpc: false,
             grpc_network_interface: NetworkInterfaceConfig::default(),
             enable_upnp: true,
+            enable_unsafe_rpc: false,
             memory_scale: NodeMemoryScale::default(),
             network: Network::default(),
             node_kind: KaspadNodeKind::default(),
@@ -444,6 +450,7 @@ impl NodeSettings {
                     || self.enable_wrpc_json != other.enable_wrpc_json
                     || self.wrpc_json_network_interface != other.wrpc_json_network_interface
                     || self.enable_upnp != other.enable_upnp
+                    || self.enable_unsafe_rpc != other.enable_unsafe_rpc
                 {
                     Some(self.node_kind != KaspadNodeKind::IntegratedInProc)
                 } else if self.kaspad_daemon_args != other.kaspad_daemon_args
@@ -477,6 +484,21 @@ impl NodeSettings {
 
         }
     }
+
+    /// Checks if the RPC of a node started by the application is reachable
+    /// from other hosts. Custom `--rpclisten*` daemon arguments are treated
+    /// as public as their interfaces are not verified.
+    pub fn is_rpc_public(&self) -> bool {
+        self.enable_wrpc_borsh
+            || (self.enable_grpc && self.grpc_network_interface.kind != NetworkInterfaceKind::Local)
+            || (self.kaspad_daemon_args_enable && self.kaspad_daemon_args.contains("--rpclisten"))
+    }
+
+    /// Unsafe RPC methods required for peer management are enabled if the user
+    /// opted in, on nodes started by the application that do not expose their RPC.
+    pub fn unsafe_rpc_enabled(&self) -> bool {
+        self.enable_unsafe_rpc && self.node_kind.is_local() && !self.is_rpc_public()
+    }
 }
 
 impl RpcConfig {
@@ -524,6 +546,55 @@ impl Default for MetricsSettings {
     }
 }
 
+/// Graph of a metric, optionally overlaid with a second metric
+/// plotted against its own (right) axis.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MetricsGraph {
+    pub metric: Metric,
+    #[serde(default)]
+    pub overlay: Option<Metric>,
+}
+
+impl MetricsGraph {
+    pub fn new(metric: Metric) -> Self {
+        Self {
+            metric,
+            overlay: None,
+        }
+    }
+}
+
+/// Named, ordered set of metric graphs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MetricsDashboard {
+    pub name: String,
+    pub graphs: Vec<MetricsGraph>,
+}
+
+impl MetricsDashboard {
+    pub fn new(name: impl Into<String>, metrics: impl IntoIterator<Item = Metric>) -> Self {
+        Self {
+            name: name.into(),
+            graphs: metrics.into_iter().map(MetricsGraph::new).collect(),
+        }
+    }
+
+    pub fn contains(&self, metric: &Metric) -> bool {
+        self.graphs.iter().any(|graph| &graph.metric == metric)
+    }
+
+    /// Moves the graph at the given index by `offset` positions.
+    pub fn move_graph(&mut self, index: usize, offset: isize) {
+        let target = index as isize + offset;
+        if index < self.graphs.len() && target >= 0 && (target as usize) < self.graphs.len() {
+            let graph = self.graphs.remove(index);
+            self.graphs.insert(target as usize, graph);
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct UserInterfaceSettings {
@@ -531,11 +602,33 @@ pub struct UserInterfaceSettings {
     pub theme_style: String,
     pub scale: f32,
     pub metrics: MetricsSettings,
+    /// User-defined metric dashboards
+    #[serde(default)]
+    pub dashboards: Vec<MetricsDashboard>,
+    /// Name of the displayed dashboard (all enabled metrics if `None`)
+    #[serde(default)]
+    pub dashboard: Option<String>,
     pub balance_padding: bool,
     #[serde(default)]
     pub disable_frame: bool,
 }
 
+impl UserInterfaceSettings {
+    pub fn active_dashboard(&self) -> Option<&MetricsDashboard> {
+        let name = self.dashboard.as_ref()?;
+        self.dashboards
+            .iter()
+            .find(|dashboard| &dashboard.name == name)
+    }
+
+    pub fn active_dashboard_mut(&mut self) -> Option<&mut MetricsDashboard> {
+        let name = self.dashboard.as_ref()?;
+        self.dashboards
+            .iter_mut()
+            .find(|dashboard| &dashboard.name == name)
+    }
+}
+
 impl Default for UserInterfaceSettings {
     fn default() -> Self {
         // cfg_if! {
@@ -551,6 +644,8 @@ impl Default for UserInterfaceSettings {
             theme_style: "Rounded".to_string(),
             scale: 1.0,
             metrics: MetricsSettings::default(),
+            dashboards: Vec::new(),
+            dashboard: None,
             balance_padding: true,
             disable_frame: true,
         }
@@ -629,6 +724,60 @@ impl EstimatorSettings {
     }
 }
 
+pub const DEFAULT_BRIDGE_PORT: u16 = 16490;
+
+/// Local WebSocket bridge allowing desktop web apps and
+/// scripts to issue wallet requests (native only).
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BridgeSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+}
+
+impl Default for BridgeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_BRIDGE_PORT,
+            token: Self::generate_token(),
+        }
+    }
+}
+
+impl BridgeSettings {
+    /// Creates a random pairing token clients must present when connecting.
+    pub fn generate_token() -> String {
+        use rand::RngExt;
+        let bytes: [u8; 16] = rand::rng().random();
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+}
+
+pub const DEFAULT_METRICS_EXPORTER_PORT: u16 = 16491;
+
+/// Local HTTP endpoint exposing node and application metrics
+/// in the OpenMetrics text format (native only).
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct MetricsExporterSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Include balances of the open wallet's accounts
+    pub wallet_balances: bool,
+}
+
+impl Default for MetricsExporterSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_METRICS_EXPORTER_PORT,
+            wallet_balances: false,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Settings {
@@ -640,11 +789,19 @@ pub struct Settings {
     pub developer: DeveloperSettings,
     #[serde(default)]
     pub estimator: EstimatorSettings,
+    #[serde(default)]
+    pub bridge: BridgeSettings,
+    #[serde(default)]
+    pub metrics_exporter: MetricsExporterSettings,
+    #[serde(default)]
+    pub metrics_alerts: MetricsAlertSettings,
     pub node: NodeSettings,
     pub user_interface: UserInterfaceSettings,
     pub language_code: String,
     pub update_monitor: bool,
     pub market_monitor: bool,
+    #[serde(default)]
+    pub market: MarketMonitorSettings,
     // #[serde(default)]
     // pub disable_frame: bool,
 }
@@ -660,11 +817,15 @@ impl Default for Settings {
             update: crate::app::VERSION.to_string(),
             developer: DeveloperSettings::default(),
             estimator: EstimatorSettings::default(),
+            bridge: BridgeSettings::default(),
+            metrics_exporter: MetricsExporterSettings::default(),
+            metrics_alerts: MetricsAlertSettings::default(),
             node: NodeSettings::default(),
             user_interface: UserInterfaceSettings::default(),
             language_code: "en".to_string(),
             update_monitor: true,
             market_monitor: true,
+            market: MarketMonitorSettings::default(),
             // disable_frame: false,
         }
     }
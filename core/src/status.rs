@@ -185,6 +185,44 @@ impl<'core> Status<'core> {
         }
     }
 
+    /// Indicator of raised node alerts (see [`MetricRule`](crate::runtime::services::metrics_monitor::MetricRule))
+    fn render_node_alerts(&mut self, ui: &mut egui::Ui) {
+        let alerts = runtime().metrics_service().active_alerts();
+        if alerts.is_empty() {
+            return;
+        }
+
+        self.render_separator(ui);
+        let text = alerts
+            .iter()
+            .map(|rule| rule.describe())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if ui
+            .add(
+                Label::new(
+                    RichText::new(format!(
+                        "{} {}",
+                        egui_phosphor::light::WARNING,
+                        alerts.len()
+                    ))
+                    .color(theme_color().warning_color),
+                )
+                .sense(Sense::click()),
+            )
+            .on_hover_text(text)
+            .clicked()
+        {
+            cfg_if! {
+                if #[cfg(not(feature = "lean"))] {
+                    self.core.select::<modules::Metrics>();
+                } else {
+                    self.core.select::<modules::Settings>();
+                }
+            }
+        }
+    }
+
     fn render_separator(&mut self, ui: &mut egui::Ui) {
         if self.device().desktop() {
             ui.separator();
@@ -374,6 +412,8 @@ impl<'core> Status<'core> {
                     }
                 }
 
+                self.render_node_alerts(ui);
+
                 if !self.device().single_pane() {
                     module.status_bar(self.core, ui);
                 }
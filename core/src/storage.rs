@@ -8,6 +8,9 @@ pub struct StorageFolder {
     pub folder_size: u64,
     pub folder_size_string: String,
     pub confirm_deletion: bool,
+    pub confirm_reset: bool,
+    pub confirm_relocation: bool,
+    pub destination: String,
 }
 
 impl Ord for StorageFolder {
@@ -46,16 +49,127 @@ impl StorageUpdateOptions {
     }
 }
 
+/// Free disk space below which a warning is shown before starting the node.
+pub const FREE_SPACE_WARNING: u64 = 32 * 1024 * 1024 * 1024;
+/// Free disk space below which the node database is likely to run out of space.
+pub const FREE_SPACE_CRITICAL: u64 = 8 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreeSpace {
+    Sufficient,
+    Low,
+    Critical,
+}
+
+impl FreeSpace {
+    pub fn from_available(available: u64) -> Self {
+        if available < FREE_SPACE_CRITICAL {
+            FreeSpace::Critical
+        } else if available < FREE_SPACE_WARNING {
+            FreeSpace::Low
+        } else {
+            FreeSpace::Sufficient
+        }
+    }
+}
+
+/// Returns the space available on the disk containing `path`
+/// (or its nearest existing parent folder).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn available_space(path: &Path) -> Option<u64> {
+    let path = path
+        .ancestors()
+        .find(|path| path.exists())
+        .and_then(|path| path.canonicalize().ok())?;
+
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+/// Copies `source` folder to `target`, adding the number of copied bytes
+/// to `progress`. If the copy fails, the partially copied target is removed.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn copy_folder(source: &Path, target: &Path, progress: &AtomicU64) -> std::io::Result<()> {
+    let copy = || -> std::io::Result<()> {
+        for entry in walkdir::WalkDir::new(source) {
+            let entry = entry?;
+            let relative = entry
+                .path()
+                .strip_prefix(source)
+                .map_err(std::io::Error::other)?;
+            let destination = target.join(relative);
+            if entry.file_type().is_dir() {
+                std::fs::create_dir_all(&destination)?;
+            } else {
+                let bytes = std::fs::copy(entry.path(), &destination)?;
+                progress.fetch_add(bytes, Ordering::Relaxed);
+            }
+        }
+        Ok(())
+    };
+
+    copy().inspect_err(|_| {
+        std::fs::remove_dir_all(target).ok();
+    })
+}
+
+/// Moves `source` folder to `target`. If the folder can not be renamed
+/// (i.e. the target is located on a different disk), the folder is copied
+/// and the source is removed once the copy completes. A failure to remove
+/// the source after a complete copy does not fail the move and is returned
+/// as `Ok(Some(err))` so that the remaining folder can be reported.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn move_folder(
+    source: &Path,
+    target: &Path,
+    progress: &AtomicU64,
+) -> std::io::Result<Option<std::io::Error>> {
+    if std::fs::rename(source, target).is_ok() {
+        return Ok(None);
+    }
+
+    copy_folder(source, target, progress)?;
+    Ok(std::fs::remove_dir_all(source).err())
+}
+
+/// Removes the `path` folder on a blocking thread, as removing
+/// a node database can take a considerable amount of time.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn remove_folder(path: PathBuf) -> std::io::Result<()> {
+    tokio::task::spawn_blocking(move || std::fs::remove_dir_all(path))
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+/// Data folder move in progress.
+#[derive(Clone)]
+pub struct Relocation {
+    pub network: Network,
+    /// Size of the data folder in bytes
+    pub total: u64,
+    /// Number of bytes copied so far
+    pub copied: Arc<AtomicU64>,
+}
+
 #[derive(Default, Clone)]
 pub struct Storage {
     pub folders: Arc<Mutex<Vec<StorageFolder>>>,
     pub storage_root: Arc<Mutex<Option<PathBuf>>>,
+    pub free_space: Arc<Mutex<Option<u64>>>,
+    pub relocation: Arc<Mutex<Option<Relocation>>>,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 impl Storage {
     pub fn track_storage_root(&self, storage_root: Option<&str>) {
         *self.storage_root.lock().unwrap() = storage_root.map(PathBuf::from);
+        // folders of the previous storage root are no longer in use
+        self.folders.lock().unwrap().clear();
         self.update(None);
     }
 
@@ -89,6 +203,8 @@ impl Storage {
                 task::sleep(delay).await;
             }
 
+            *this.free_space.lock().unwrap() = available_space(&rusty_kaspa_app_dir);
+
             let paths = std::fs::read_dir(rusty_kaspa_app_dir).unwrap();
             for path in paths {
                 let path = path?.path();
@@ -133,6 +249,9 @@ impl Storage {
                 folder_size,
                 folder_size_string,
                 confirm_deletion: false,
+                confirm_reset: false,
+                confirm_relocation: false,
+                destination: String::default(),
             });
 
             folders.sort();
@@ -162,7 +281,7 @@ impl Storage {
             if let Some(path) = this.folder(network) {
                 if path.exists() {
                     println!("Removing storage folder: {:?}", path.display());
-                    if let Err(e) = std::fs::remove_dir_all(&path) {
+                    if let Err(e) = remove_folder(path.clone()).await {
                         println!("Error removing storage folder: {:?}", e);
                         runtime().error(format!("Error removing storage folder: {:?}", e));
                     }
@@ -176,6 +295,181 @@ impl Storage {
         });
     }
 
+    /// Moves the `source` data folder of the given network to the `destination`
+    /// folder, which then becomes the node data storage folder.
+    pub fn relocate(
+        &self,
+        network: Network,
+        source: PathBuf,
+        folder_size: u64,
+        destination: PathBuf,
+    ) {
+        let relocation = Relocation {
+            network,
+            total: folder_size,
+            copied: Arc::new(AtomicU64::new(0)),
+        };
+        {
+            let mut current = self.relocation.lock().unwrap();
+            if current.is_some() {
+                return;
+            }
+            current.replace(relocation.clone());
+        }
+
+        let this = self.clone();
+        spawn(async move {
+            let result = this.move_data_folder(source, destination, relocation).await;
+            this.relocation.lock().unwrap().take();
+            runtime().request_repaint();
+            result
+        });
+    }
+
+    async fn move_data_folder(
+        &self,
+        source: PathBuf,
+        destination: PathBuf,
+        relocation: Relocation,
+    ) -> Result<()> {
+        let Some(name) = source.file_name() else {
+            return Ok(());
+        };
+
+        let target = destination.join(name);
+        if target.exists() {
+            return Err(Error::custom(format!(
+                "Destination folder already exists: {}",
+                target.display()
+            )));
+        }
+
+        if let Some(available) = available_space(&destination)
+            && available < relocation.total
+        {
+            return Err(Error::custom(format!(
+                "Not enough free space to move the data folder to {}",
+                destination.display()
+            )));
+        }
+
+        let created = !destination.exists();
+        std::fs::create_dir_all(&destination)?;
+
+        log_info!(
+            "Moving storage folder: {} -> {}",
+            source.display(),
+            target.display()
+        );
+        let moved = {
+            let source = source.clone();
+            let copied = relocation.copied.clone();
+            tokio::task::spawn_blocking(move || move_folder(&source, &target, &copied))
+                .await
+                .map_err(|err| Error::custom(format!("Error moving storage folder: {err}")))?
+        };
+
+        match moved {
+            Ok(remaining) => {
+                if let Some(err) = remaining {
+                    runtime().notify(UserNotification::warning(i18n_args(
+                        "Unable to remove the previous data folder {path}: {error}",
+                        &[
+                            ("path", source.display().to_string()),
+                            ("error", err.to_string()),
+                        ],
+                    )));
+                }
+            }
+            Err(err) => {
+                // the partial copy has been removed, also remove
+                // the destination folder if it has been created
+                if created {
+                    std::fs::remove_dir(&destination).ok();
+                }
+                return Err(Error::custom(format!("Error moving storage folder: {err}")));
+            }
+        }
+
+        runtime()
+            .send(Events::StorageRelocated {
+                storage_root: destination,
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns `true` while a data folder is being moved.
+    pub fn is_relocating(&self) -> bool {
+        self.relocation.lock().unwrap().is_some()
+    }
+
+    fn render_relocation(&self, ui: &mut Ui) {
+        use kaspa_metrics_core::data::as_data_size;
+
+        let Some(Relocation {
+            network,
+            total,
+            copied,
+        }) = self.relocation.lock().unwrap().clone()
+        else {
+            return;
+        };
+
+        let copied = copied.load(Ordering::Relaxed);
+        let fraction = if total > 0 {
+            copied as f32 / total as f32
+        } else {
+            0.0
+        };
+        ui.label(i18n_args(
+            "Moving {network} data folder...",
+            &[("network", network.to_string().to_uppercase())],
+        ));
+        ui.add(ProgressBar::new(fraction).desired_width(260.).text(format!(
+            "{} / {}",
+            as_data_size(copied as f64, true),
+            as_data_size(total as f64, true)
+        )));
+        ui.label(i18n(
+            "Node settings can not be changed until the move completes.",
+        ));
+        ui.add_space(4.);
+        ui.ctx().request_repaint_after(Duration::from_millis(250));
+    }
+
+    /// Removes the node database of a network that is not currently
+    /// in use, retaining the logs.
+    pub fn reset_database(&self, network: Network) {
+        let this = self.clone();
+        spawn(async move {
+            if let Some(path) = this.folder(network) {
+                let datadir = path.join("datadir");
+                if datadir.exists() {
+                    remove_folder(datadir).await.map_err(|err| {
+                        Error::custom(format!("Error removing node database: {err}"))
+                    })?;
+                }
+                runtime().notify(
+                    UserNotification::success(i18n("Node database has been reset")).short(),
+                );
+                this.update(None);
+            }
+            Ok(())
+        });
+    }
+
+    pub fn free_space(&self) -> Option<(String, FreeSpace)> {
+        use kaspa_metrics_core::data::as_data_size;
+        self.free_space.lock().unwrap().map(|available| {
+            (
+                as_data_size(available as f64, true),
+                FreeSpace::from_available(available),
+            )
+        })
+    }
+
     pub fn render(&self, ui: &mut Ui) {
         let folders = self.folders.lock().unwrap();
         if !folders.is_empty() {
@@ -195,6 +489,7 @@ impl Storage {
                                     network.to_string().to_uppercase()
                                 ));
                             }
+                            self.render_free_space(ui);
                         });
                     });
             });
@@ -205,6 +500,19 @@ impl Storage {
         let mut folders = self.folders.lock().unwrap();
         for folder in folders.iter_mut() {
             folder.confirm_deletion = false;
+            folder.confirm_reset = false;
+            folder.confirm_relocation = false;
+        }
+    }
+
+    fn render_free_space(&self, ui: &mut Ui) {
+        if let Some((available, free_space)) = self.free_space() {
+            let text = i18n_args("Free disk space: {available}", &[("available", available)]);
+            match free_space {
+                FreeSpace::Sufficient => { ui.label(text); },
+                FreeSpace::Low => { ui.colored_label(theme_color().warning_color, text); },
+                FreeSpace::Critical => { ui.colored_label(theme_color().error_color, text); },
+            }
         }
     }
 
@@ -216,27 +524,127 @@ impl Storage {
                 .default_open(false)
                 .show(ui, |ui| {
                     ui.vertical(|ui| {
+                        ui.label(format!("{} {}", i18n("Data storage folder:"), self.storage_root().display()));
+                        self.render_free_space(ui);
+                        ui.add_space(4.);
+                        self.render_relocation(ui);
+                        let is_relocating = self.is_relocating();
+                        // the node keeps the data storage folder open, which is shared by all networks
+                        let is_node_running = core.settings.node.node_kind.is_local();
+                        let network_folders = folders.iter().map(|folder| (folder.network, folder.path.clone())).collect::<Vec<_>>();
+
                         for folder in folders.iter_mut() {
-                            let StorageFolder { network, folder_size_string, path, confirm_deletion, .. } = folder;
+                            let StorageFolder { network, folder_size, folder_size_string, path, confirm_deletion, confirm_reset, confirm_relocation, destination, .. } = folder;
 
                             CollapsingHeader::new(format!("{}: {folder_size_string}", network.to_string().to_uppercase()))
                             .default_open(false)
                             .show(ui, |ui| {
-                                let is_running = core.settings.node.network == *network && core.settings.node.node_kind.is_local();
+                                let is_running = is_node_running && core.settings.node.network == *network;
+                                let is_pending = *confirm_deletion || *confirm_reset || *confirm_relocation || is_relocating;
 
-                                ui.horizontal(|ui|{
+                                ui.horizontal_wrapped(|ui|{
                                     if ui.medium_button(i18n("Open Data Folder")).clicked()
-                                        && let Err(err) = open::that(path) {
+                                        && let Err(err) = open::that(&*path) {
                                             runtime().error(format!("Error opening folder: {:?}", err));
                                         }
-                                    if ui.medium_button_enabled(!is_running && !*confirm_deletion, i18n("Delete Data Folder")).clicked() {
+                                    if ui.medium_button_enabled(!is_node_running && !is_pending, i18n("Move Data Folder")).clicked() {
+                                        *confirm_relocation = true;
+                                    }
+                                    if ui.medium_button_enabled(!is_pending, i18n("Reset Node Database")).clicked() {
+                                        *confirm_reset = true;
+                                    }
+                                    if ui.medium_button_enabled(!is_running && !is_pending, i18n("Delete Data Folder")).clicked() {
                                         *confirm_deletion = true;
                                     }
                                 });
 
                                 if is_running {
-                                    ui.label(i18n("Cannot delete data folder while the node is running"));
-                                    ui.label(i18n("Please set node to 'Disabled' to delete the data folder"));
+                                    ui.label(i18n("Cannot move or delete data folder while the node is running"));
+                                    ui.label(i18n("Please set node to 'Disabled' to move or delete the data folder"));
+                                } else if is_node_running {
+                                    ui.label(i18n("Cannot move data folder while the node is running"));
+                                    ui.label(i18n("Please set node to 'Disabled' to move the data folder"));
+                                }
+
+                                if *confirm_relocation {
+                                    ui.add_sized(vec2(260.,4.), Separator::default());
+                                    ui.label(i18n("Move the data folder to a new location:"));
+                                    ui.horizontal(|ui| {
+                                        ui.add(TextEdit::singleline(destination));
+                                        if ui.medium_button(i18n("Browse")).clicked()
+                                            && let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                                                *destination = folder.to_string_lossy().to_string();
+                                            }
+                                    });
+
+                                    let appdir_override = core.settings.node.kaspad_daemon_args_enable && core.settings.node.kaspad_daemon_args.contains("--appdir");
+                                    let target = Path::new(destination.trim());
+                                    if appdir_override {
+                                        ui.colored_label(theme_color().warning_color, i18n("Your daemon arguments contain '--appdir' directive, which overrides the data storage folder setting."));
+                                    } else if !destination.trim().is_empty() {
+                                        if let Some(available) = available_space(target) {
+                                            use kaspa_metrics_core::data::as_data_size;
+                                            ui.label(i18n_args("Free disk space: {available}", &[("available", as_data_size(available as f64, true))]));
+                                        }
+                                        ui.label(i18n("The destination becomes the data storage folder for all networks."));
+                                        let remaining = network_folders.iter().filter(|(other, _)| *other != *network).collect::<Vec<_>>();
+                                        if !remaining.is_empty() {
+                                            ui.colored_label(theme_color().warning_color, i18n("The following data folders are not moved and will no longer be used:"));
+                                            for (other, path) in remaining {
+                                                ui.colored_label(theme_color().warning_color, format!("{}: {}", other.to_string().to_uppercase(), path.display()));
+                                            }
+                                        }
+                                    }
+
+                                    ui.label("");
+                                    ui.colored_label(theme_color().alert_color, i18n("Please Confirm Data Folder Move"));
+                                    if let Some(response) = ui.confirm_medium_apply_cancel(Align::Min) {
+                                        match response {
+                                            Confirm::Ack => {
+                                                if destination.trim().is_empty() || appdir_override {
+                                                    runtime().error(i18n("Please specify a valid destination folder"));
+                                                } else {
+                                                    *confirm_relocation = false;
+                                                    self.relocate(*network, path.clone(), *folder_size, PathBuf::from(destination.trim()));
+                                                    destination.clear();
+                                                }
+                                            },
+                                            Confirm::Nack => {
+                                                *confirm_relocation = false;
+                                                destination.clear();
+                                            }
+                                        }
+                                    }
+                                    ui.add_sized(vec2(260.,4.), Separator::default());
+                                }
+
+                                if *confirm_reset {
+                                    ui.add_sized(vec2(260.,4.), Separator::default());
+                                    if is_running {
+                                        ui.label(i18n("The node will be stopped, its database erased and the node restarted."));
+                                        ui.label(i18n("The node will then resynchronize with the network from scratch."));
+                                    } else {
+                                        ui.label(i18n("This action will erase Kaspa database, retaining the logs."));
+                                        ui.label(i18n("The node will resynchronize with the network on the next start."));
+                                    }
+                                    ui.label("");
+                                    ui.colored_label(theme_color().alert_color, i18n("Please Confirm Database Reset"));
+                                    if let Some(response) = ui.confirm_medium_apply_cancel(Align::Min) {
+                                        match response {
+                                            Confirm::Ack => {
+                                                *confirm_reset = false;
+                                                if is_running {
+                                                    runtime().kaspa_service().reset_database(path.join("datadir"), &core.settings.node);
+                                                } else {
+                                                    self.reset_database(*network);
+                                                }
+                                            },
+                                            Confirm::Nack => {
+                                                *confirm_reset = false;
+                                            }
+                                        }
+                                    }
+                                    ui.add_sized(vec2(260.,4.), Separator::default());
                                 }
 
                                 if *confirm_deletion {
@@ -0,0 +1,204 @@
+use crate::imports::*;
+use crate::primitives::{ChainUpdate, CoinbasePayload, DagEvent, DagHistory, MergeState};
+
+#[test]
+fn test_coinbase_payload() {
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&1_234_567u64.to_le_bytes());
+    payload.extend_from_slice(&(50 * 100_000_000u64).to_le_bytes());
+    payload.extend_from_slice(&0u16.to_le_bytes());
+    payload.push(3);
+    payload.extend_from_slice(&[0x20, 0xac, 0x51]);
+    payload.extend_from_slice(b"0.16.1/\x01miner");
+
+    let coinbase = CoinbasePayload::try_parse(&payload).unwrap();
+    assert_eq!(coinbase.blue_score, 1_234_567);
+    assert_eq!(coinbase.subsidy, 50 * 100_000_000);
+    assert_eq!(coinbase.script_version, 0);
+    assert_eq!(coinbase.script, vec![0x20, 0xac, 0x51]);
+    assert_eq!(coinbase.extra_data_text(), "0.16.1/miner");
+
+    // truncated script
+    assert!(CoinbasePayload::try_parse(&payload[..20]).is_none());
+    assert!(CoinbasePayload::try_parse(&[]).is_none());
+}
+
+fn hashes(words: &[u64]) -> Vec<KaspaHash> {
+    words
+        .iter()
+        .copied()
+        .map(KaspaHash::from_u64_word)
+        .collect()
+}
+
+fn chain_changed(added: u64) -> DagEvent {
+    DagEvent::ChainChanged {
+        removed: Arc::new(vec![]),
+        added: Arc::new(vec![KaspaHash::from_u64_word(added)]),
+    }
+}
+
+fn added(event: &DagEvent) -> KaspaHash {
+    match event {
+        DagEvent::ChainChanged { added, .. } => added[0],
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_dag_history() {
+    let mut history = DagHistory::new(Duration::from_secs(10), 4);
+    assert!(history.range().is_none());
+
+    for (index, timestamp) in [1000.0, 2000.0, 3000.0].into_iter().enumerate() {
+        history.push(timestamp, chain_changed(index as u64));
+    }
+    assert_eq!(history.range(), Some((1000.0, 3000.0)));
+    assert_eq!(
+        history.until(2000.0).map(added).collect::<Vec<_>>(),
+        hashes(&[0, 1])
+    );
+    assert_eq!(
+        history
+            .between(1000.0, 3000.0)
+            .map(added)
+            .collect::<Vec<_>>(),
+        hashes(&[1, 2])
+    );
+    assert_eq!(history.between(3000.0, 5000.0).count(), 0);
+
+    // events older than the retention period are dropped
+    history.push(11500.0, chain_changed(3));
+    assert_eq!(history.range(), Some((2000.0, 11500.0)));
+
+    // capacity limits the number of retained events
+    history.push(11600.0, chain_changed(4));
+    history.push(11700.0, chain_changed(5));
+    assert_eq!(history.len(), 4);
+    assert_eq!(history.range(), Some((3000.0, 11700.0)));
+
+    history.set_retention(Duration::from_secs(1));
+    assert_eq!(
+        history.until(f64::INFINITY).map(added).collect::<Vec<_>>(),
+        hashes(&[3, 4, 5])
+    );
+
+    history.clear();
+    assert!(history.is_empty());
+}
+
+#[test]
+fn test_dag_playback_replay() {
+    use crate::runtime::services::BlockDagPlayback;
+
+    let mut playback = BlockDagPlayback::Replay {
+        cursor: 1000.0,
+        speed: 2.0,
+    };
+    assert!(playback.advance(1000.0, 2.0, 1500.0, 3000.0));
+    assert_eq!(
+        playback,
+        BlockDagPlayback::Replay {
+            cursor: 1500.0,
+            speed: 2.0
+        }
+    );
+
+    // playback changed by the user while the step was applied
+    playback = BlockDagPlayback::Paused { cursor: 1500.0 };
+    assert!(!playback.advance(1500.0, 2.0, 3500.0, 3000.0));
+    assert_eq!(playback, BlockDagPlayback::Paused { cursor: 1500.0 });
+
+    playback = BlockDagPlayback::Replay {
+        cursor: 500.0,
+        speed: 2.0,
+    };
+    assert!(!playback.advance(1500.0, 2.0, 3500.0, 3000.0));
+    assert!(!playback.is_live());
+
+    // a replay that catches up with the history continues live
+    playback = BlockDagPlayback::Replay {
+        cursor: 2500.0,
+        speed: 2.0,
+    };
+    assert!(playback.advance(2500.0, 2.0, 3500.0, 3000.0));
+    assert!(playback.is_live());
+}
+
+/// Merge sets of chain blocks keyed by the chain block hash
+fn merge_sets(
+    entries: &[(u64, &[u64], &[u64])],
+) -> AHashMap<KaspaHash, (Vec<KaspaHash>, Vec<KaspaHash>)> {
+    entries
+        .iter()
+        .map(|(hash, blues, reds)| {
+            (
+                KaspaHash::from_u64_word(*hash),
+                (hashes(blues), hashes(reds)),
+            )
+        })
+        .collect()
+}
+
+fn chain_update(
+    removed: &[u64],
+    added: &[u64],
+    merge_sets: &AHashMap<KaspaHash, (Vec<KaspaHash>, Vec<KaspaHash>)>,
+) -> ChainUpdate {
+    ChainUpdate::new(&hashes(removed), &hashes(added), |hash| {
+        merge_sets
+            .get(hash)
+            .map(|(blues, reds)| (blues.as_slice(), reds.as_slice()))
+    })
+}
+
+fn merge_state(update: &ChainUpdate, hash: u64) -> Option<MergeState> {
+    update.merge.get(&KaspaHash::from_u64_word(hash)).copied()
+}
+
+fn vspc(update: &ChainUpdate, hash: u64) -> Option<bool> {
+    update.vspc.get(&KaspaHash::from_u64_word(hash)).copied()
+}
+
+#[test]
+fn test_chain_update_merge_state() {
+    // chain blocks 10 and 11 merge blocks 1..=4; 20 replaces 11 and merges 4 as blue
+    let merge_sets = merge_sets(&[(10, &[1, 2], &[3]), (11, &[], &[4]), (20, &[4], &[])]);
+
+    let update = chain_update(&[], &[10, 11], &merge_sets);
+    assert_eq!(update.merge.len(), 4);
+    assert_eq!(merge_state(&update, 1), Some(MergeState::Blue));
+    assert_eq!(merge_state(&update, 2), Some(MergeState::Blue));
+    assert_eq!(merge_state(&update, 3), Some(MergeState::Red));
+    assert_eq!(merge_state(&update, 4), Some(MergeState::Red));
+
+    // blocks merged by a removed chain block are unmerged
+    // unless an added chain block merges them again
+    let update = chain_update(&[10, 11], &[20], &merge_sets);
+    assert_eq!(merge_state(&update, 1), Some(MergeState::Unmerged));
+    assert_eq!(merge_state(&update, 3), Some(MergeState::Unmerged));
+    assert_eq!(merge_state(&update, 4), Some(MergeState::Blue));
+
+    // chain blocks without known merge sets leave merge states untouched
+    let update = chain_update(&[30], &[31], &merge_sets);
+    assert!(update.merge.is_empty());
+}
+
+#[test]
+fn test_chain_update_chain_blocks() {
+    let merge_sets = merge_sets(&[]);
+
+    let update = chain_update(&[], &[10, 11], &merge_sets);
+    assert_eq!(update.vspc.len(), 2);
+    assert_eq!(vspc(&update, 10), Some(true));
+    assert_eq!(vspc(&update, 11), Some(true));
+
+    // a reorg deselects the removed chain blocks; a block that is
+    // removed and added by the same change remains a chain block
+    let update = chain_update(&[10, 11], &[11, 20], &merge_sets);
+    assert_eq!(update.vspc.len(), 3);
+    assert_eq!(vspc(&update, 10), Some(false));
+    assert_eq!(vspc(&update, 11), Some(true));
+    assert_eq!(vspc(&update, 20), Some(true));
+    assert_eq!(vspc(&update, 1), None);
+}
@@ -0,0 +1,542 @@
+use crate::imports::*;
+use crate::interop::*;
+
+const MAINNET_ADDRESS: &str = "kaspa:qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqkx9awp4e";
+const TESTNET_ADDRESS: &str =
+    "kaspatest:qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqhqrxplya";
+const ORIGIN: &str = "https://example.org";
+
+/// In-memory transport that serves queued pending requests
+/// and records the responses posted by the adaptor.
+#[derive(Default)]
+struct MemorySender {
+    pending: Mutex<VecDeque<PendingRequest>>,
+    responses: Mutex<Vec<(u64, Option<String>, Response)>>,
+    notifications: Mutex<Vec<Notification>>,
+}
+
+impl MemorySender {
+    fn with_requests(requests: Vec<PendingRequest>) -> Self {
+        Self {
+            pending: Mutex::new(requests.into()),
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait]
+impl transport::Sender for MemorySender {
+    async fn send_message(
+        &self,
+        target: Target,
+        data: Vec<u8>,
+    ) -> kaspa_wallet_core::result::Result<Vec<u8>> {
+        assert!(matches!(target, Target::Adaptor));
+        let custom = |err: std::io::Error| kaspa_wallet_core::error::Error::custom(err.to_string());
+
+        match ServerAction::try_from_slice(&data).map_err(custom)? {
+            ServerAction::PendingRequests => {
+                let mut pending = self.pending.lock().unwrap();
+                match pending.pop_front() {
+                    Some(request) => {
+                        borsh::to_vec(&request.with_remaining(pending.len() as u32)).map_err(custom)
+                    }
+                    None => Ok(vec![]),
+                }
+            }
+            ServerAction::Response(sender_id, id, data) => {
+                let response = Response::try_from_slice(&data).map_err(custom)?;
+                self.responses
+                    .lock()
+                    .unwrap()
+                    .push((sender_id, id, response));
+                Ok(vec![])
+            }
+            ServerAction::CloseWindow => Ok(vec![]),
+            ServerAction::Notify(data) => {
+                let notification = Notification::try_from_slice(&data).map_err(custom)?;
+                self.notifications.lock().unwrap().push(notification);
+                Ok(vec![])
+            }
+        }
+    }
+}
+
+fn transaction_request(address: &str, amount_sompi: u64) -> TransactionRequest {
+    TransactionRequest {
+        outputs: vec![TransactionRequestOutput {
+            address: address.to_string(),
+            amount_sompi,
+        }],
+        payload: None,
+        priority: FeePriority::default(),
+    }
+}
+
+fn wait_for(condition: impl Fn() -> bool) {
+    for _ in 0..500 {
+        if condition() {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    panic!("timeout waiting for the adaptor");
+}
+
+#[test]
+fn test_transaction_request_serialization() {
+    let json = format!(
+        r#"{{"outputs":[{{"address":"{TESTNET_ADDRESS}","amountSompi":150000000}}],"priority":"high"}}"#
+    );
+    let request: TransactionRequest = serde_json::from_str(&json).unwrap();
+    assert_eq!(request.outputs.len(), 1);
+    assert_eq!(request.total_amount_sompi(), Some(150_000_000));
+    assert_eq!(request.payload, None);
+    assert_eq!(request.priority, FeePriority::High);
+
+    let request = Request::SendTransaction(request);
+    let bytes = borsh::to_vec(&request).unwrap();
+    match Request::try_from_slice(&bytes).unwrap() {
+        Request::SendTransaction(decoded) => {
+            assert_eq!(decoded.outputs[0].address, TESTNET_ADDRESS);
+            assert_eq!(decoded.priority, FeePriority::High);
+        }
+        other => panic!("unexpected request: {other:?}"),
+    }
+}
+
+#[test]
+fn test_transaction_request_destination() {
+    let request = transaction_request(TESTNET_ADDRESS, 100_000);
+    assert!(request.destination(NetworkType::Testnet).is_ok());
+    assert!(request.destination(NetworkType::Mainnet).is_err());
+
+    let request = transaction_request(MAINNET_ADDRESS, 100_000);
+    assert!(request.destination(NetworkType::Mainnet).is_ok());
+
+    let request = transaction_request(MAINNET_ADDRESS, 0);
+    assert!(request.destination(NetworkType::Mainnet).is_err());
+
+    let request = transaction_request("kaspa:invalid", 100_000);
+    assert!(request.destination(NetworkType::Mainnet).is_err());
+
+    let request = TransactionRequest {
+        outputs: vec![],
+        payload: None,
+        priority: FeePriority::Low,
+    };
+    assert!(request.destination(NetworkType::Mainnet).is_err());
+
+    // output amounts overflowing u64 are rejected
+    let mut request = transaction_request(MAINNET_ADDRESS, u64::MAX);
+    request.outputs.push(TransactionRequestOutput {
+        address: MAINNET_ADDRESS.to_string(),
+        amount_sompi: 2,
+    });
+    assert_eq!(request.total_amount_sompi(), None);
+    assert!(request.destination(NetworkType::Mainnet).is_err());
+}
+
+#[test]
+fn test_fee_priority_rate_without_estimate() {
+    assert_eq!(FeePriority::Low.fee_rate(None), 1.0);
+    assert_eq!(FeePriority::Normal.fee_rate(None), 1.0);
+    assert_eq!(FeePriority::High.fee_rate(None), 1.0);
+}
+
+#[test]
+fn test_adaptor_rejects_pending_transaction() {
+    let request = Request::SignTransaction(transaction_request(TESTNET_ADDRESS, 100_000));
+    let sender = Arc::new(MemorySender::with_requests(vec![
+        PendingRequest::new(7, Some("rid-1".to_string()), request)
+            .with_origin(Some(ORIGIN.to_string())),
+    ]));
+    let adaptor = Arc::new(Adaptor::new(
+        sender.clone(),
+        ApplicationEventsChannel::unbounded(),
+    ));
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(adaptor.clone().init()).unwrap();
+
+    wait_for(|| adaptor.request().is_some());
+    assert!(matches!(
+        adaptor.request(),
+        Some(Request::SignTransaction(_))
+    ));
+    assert_eq!(adaptor.origin().as_deref(), Some(ORIGIN));
+
+    adaptor.reject("User rejected the request.");
+    wait_for(|| !sender.responses.lock().unwrap().is_empty());
+    assert!(adaptor.request().is_none());
+
+    let responses = sender.responses.lock().unwrap();
+    let (sender_id, id, response) = &responses[0];
+    assert_eq!(*sender_id, 7);
+    assert_eq!(id.as_deref(), Some("rid-1"));
+    assert!(
+        matches!(response, Response::Canceled { error } if error == "User rejected the request.")
+    );
+}
+
+#[test]
+fn test_adaptor_signs_estimated_transaction() {
+    use kaspa_wallet_core::tx::GeneratorSummary;
+
+    let request = Request::SignTransaction(transaction_request(TESTNET_ADDRESS, 100_000));
+    let sender = Arc::new(MemorySender::with_requests(vec![
+        PendingRequest::new(7, Some("SignTransaction-1".to_string()), request)
+            .with_origin(Some(ORIGIN.to_string())),
+    ]));
+    let adaptor = Arc::new(Adaptor::new(
+        sender.clone(),
+        ApplicationEventsChannel::unbounded(),
+    ));
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(adaptor.clone().init()).unwrap();
+
+    wait_for(|| adaptor.request().is_some());
+    assert!(!adaptor.update_transaction_results(100_000));
+    assert!(adaptor.estimate().is_none());
+
+    adaptor.transaction_estimate().store(Ok(GeneratorSummary {
+        network_id: NetworkId::with_suffix(NetworkType::Testnet, 10),
+        aggregated_utxos: 2,
+        aggregate_fees: 3_000,
+        aggregate_mass: 3_000,
+        number_of_generated_transactions: 1,
+        final_transaction_amount: Some(100_000),
+        final_transaction_id: None,
+    }));
+    assert!(!adaptor.update_transaction_results(100_000));
+    assert!(matches!(adaptor.estimate(), Some(Ok(estimate)) if estimate.aggregate_fees == 3_000));
+    assert!(adaptor.request().is_some());
+
+    adaptor
+        .transaction_result()
+        .store(Ok(Response::SignTransaction {
+            pskb: "signed-bundle".to_string(),
+        }));
+    assert!(adaptor.update_transaction_results(100_000));
+    assert!(adaptor.estimate().is_none());
+
+    wait_for(|| !sender.responses.lock().unwrap().is_empty());
+    assert!(adaptor.request().is_none());
+
+    let responses = sender.responses.lock().unwrap();
+    let (sender_id, id, response) = &responses[0];
+    assert_eq!(*sender_id, 7);
+    assert_eq!(id.as_deref(), Some("SignTransaction-1"));
+    assert!(matches!(response, Response::SignTransaction { pskb } if pskb == "signed-bundle"));
+}
+
+#[test]
+fn test_adaptor_steps_through_queued_requests() {
+    let sign_message = |message: &str| Request::SignMessage {
+        message: message.to_string(),
+    };
+    let sender = Arc::new(MemorySender::with_requests(vec![
+        PendingRequest::new(1, Some("Connect-1".to_string()), Request::Connect {}),
+        PendingRequest::new(2, Some("SignMessage-1".to_string()), sign_message("first")),
+        PendingRequest::new(3, Some("SignMessage-2".to_string()), sign_message("second"))
+            .with_origin(Some(ORIGIN.to_string())),
+    ]));
+    let adaptor = Arc::new(Adaptor::new(
+        sender.clone(),
+        ApplicationEventsChannel::unbounded(),
+    ));
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(adaptor.clone().init()).unwrap();
+
+    wait_for(|| matches!(adaptor.request(), Some(Request::Connect {})));
+    assert_eq!(adaptor.remaining(), 2);
+    // connection requests without an origin are presented to the user
+    assert_eq!(adaptor.origin(), None);
+    adaptor.reject("first");
+
+    // other requests without an origin are rejected without reaching the user
+    wait_for(
+        || matches!(adaptor.request(), Some(Request::SignMessage { message }) if message == "second"),
+    );
+    assert_eq!(adaptor.remaining(), 0);
+    assert_eq!(adaptor.origin().as_deref(), Some(ORIGIN));
+
+    // cancellation only applies to the sender of the current request
+    adaptor.cancel(1);
+    assert!(adaptor.request().is_some());
+    adaptor.cancel(3);
+
+    wait_for(|| sender.responses.lock().unwrap().len() == 3);
+    let responses = sender.responses.lock().unwrap();
+    assert_eq!(responses[0].0, 1);
+    assert_eq!(responses[0].1.as_deref(), Some("Connect-1"));
+    assert_eq!(responses[1].0, 2);
+    assert!(
+        matches!(&responses[1].2, Response::Canceled { error } if error == "Unknown request origin.")
+    );
+    assert_eq!(responses[2].0, 3);
+    assert!(matches!(responses[2].2, Response::Canceled { .. }));
+}
+
+#[test]
+fn test_adaptor_discards_canceled_signature() {
+    let sign_message = |message: &str| Request::SignMessage {
+        message: message.to_string(),
+    };
+    let sender = Arc::new(MemorySender::with_requests(vec![
+        PendingRequest::new(1, Some("SignMessage-1".to_string()), sign_message("first"))
+            .with_origin(Some(ORIGIN.to_string())),
+        PendingRequest::new(2, Some("SignMessage-2".to_string()), sign_message("second"))
+            .with_origin(Some(ORIGIN.to_string())),
+    ]));
+    let adaptor = Arc::new(Adaptor::new(
+        sender.clone(),
+        ApplicationEventsChannel::unbounded(),
+    ));
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(adaptor.clone().init()).unwrap();
+
+    wait_for(
+        || matches!(adaptor.request(), Some(Request::SignMessage { message }) if message == "first"),
+    );
+    let address = Address::try_from(TESTNET_ADDRESS).unwrap();
+    // signature produced for the first request before it has been canceled
+    let first_result = adaptor.sign_message_result();
+    first_result.store(Ok((address.clone(), "first-signature".to_string())));
+    adaptor.cancel(1);
+
+    wait_for(
+        || matches!(adaptor.request(), Some(Request::SignMessage { message }) if message == "second"),
+    );
+    assert!(adaptor.sign_message_result().take().is_none());
+
+    // signature of the canceled request completing late
+    first_result.store(Ok((address, "late-signature".to_string())));
+    assert!(adaptor.sign_message_result().take().is_none());
+
+    adaptor.reject("User rejected the request.");
+    wait_for(|| sender.responses.lock().unwrap().len() == 2);
+    let responses = sender.responses.lock().unwrap();
+    assert!(
+        responses
+            .iter()
+            .all(|(_, _, response)| matches!(response, Response::Canceled { .. }))
+    );
+}
+
+#[test]
+fn test_adaptor_discards_canceled_transaction_results() {
+    let send =
+        |amount_sompi| Request::SendTransaction(transaction_request(TESTNET_ADDRESS, amount_sompi));
+    let sender = Arc::new(MemorySender::with_requests(vec![
+        PendingRequest::new(1, Some("SendTransaction-1".to_string()), send(100_000))
+            .with_origin(Some(ORIGIN.to_string())),
+        PendingRequest::new(2, Some("SendTransaction-2".to_string()), send(200_000))
+            .with_origin(Some(ORIGIN.to_string())),
+    ]));
+    let adaptor = Arc::new(Adaptor::new(
+        sender.clone(),
+        ApplicationEventsChannel::unbounded(),
+    ));
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(adaptor.clone().init()).unwrap();
+
+    let is_request = |amount_sompi| {
+        matches!(
+            adaptor.request(),
+            Some(Request::SendTransaction(transaction)) if transaction.total_amount_sompi() == Some(amount_sompi)
+        )
+    };
+
+    wait_for(|| is_request(100_000));
+    // the first request has been submitted when the page is closed
+    let first_result = adaptor.transaction_result();
+    first_result.store(Ok(Response::SendTransaction {
+        transaction_ids: vec!["first".to_string()],
+    }));
+    let first_estimate = adaptor.transaction_estimate();
+    first_estimate.store(Err(Error::custom("first estimate")));
+    adaptor.cancel(1);
+
+    wait_for(|| is_request(200_000));
+    assert!(adaptor.transaction_result().take().is_none());
+    assert!(adaptor.transaction_estimate().take().is_none());
+
+    // results of the canceled request completing late
+    first_result.store(Ok(Response::SendTransaction {
+        transaction_ids: vec!["late".to_string()],
+    }));
+    first_estimate.store(Err(Error::custom("late estimate")));
+    assert!(adaptor.transaction_result().take().is_none());
+    assert!(adaptor.transaction_estimate().take().is_none());
+
+    adaptor.reject("User rejected the request.");
+    wait_for(|| sender.responses.lock().unwrap().len() == 2);
+    let responses = sender.responses.lock().unwrap();
+    assert_eq!(responses[0].1.as_deref(), Some("SendTransaction-1"));
+    assert!(matches!(responses[0].2, Response::Canceled { .. }));
+    assert_eq!(responses[1].1.as_deref(), Some("SendTransaction-2"));
+    assert!(matches!(responses[1].2, Response::Canceled { .. }));
+}
+
+#[test]
+fn test_adaptor_without_pending_request() {
+    let sender = Arc::new(MemorySender::default());
+    let adaptor = Arc::new(Adaptor::new(
+        sender.clone(),
+        ApplicationEventsChannel::unbounded(),
+    ));
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(adaptor.clone().init()).unwrap();
+
+    assert!(adaptor.request().is_none());
+    assert!(sender.responses.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_adaptor_relays_notifications() {
+    let sender = Arc::new(MemorySender::default());
+    let adaptor = Arc::new(Adaptor::new(
+        sender.clone(),
+        ApplicationEventsChannel::unbounded(),
+    ));
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        adaptor.notify(Notification::NetworkChanged {
+            network: "testnet-10".to_string(),
+        });
+    });
+
+    wait_for(|| !sender.notifications.lock().unwrap().is_empty());
+    let notifications = sender.notifications.lock().unwrap();
+    assert_eq!(notifications.len(), 1);
+    assert!(matches!(
+        &notifications[0],
+        Notification::NetworkChanged { network } if network == "testnet-10"
+    ));
+    // notifications are not responses to pending requests
+    assert!(sender.responses.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_origin_permission() {
+    let account_id: AccountId = serde_json::from_str(
+        r#""b6c8f27e7d9a3c5f1e2d4b6a8c0e1f3a5b7d9c2e4f6a8b0c1d3e5f7a9b2c4d6e""#,
+    )
+    .unwrap();
+    let permission = OriginPermission {
+        origin: "https://example.org".to_string(),
+        accounts: vec![account_id],
+        kinds: vec![RequestKind::Connect, RequestKind::SignMessage],
+        created: 1_000,
+        expires: GrantDuration::Day.expires(1_000),
+    };
+
+    assert!(permission.allows(RequestKind::SignMessage));
+    assert!(!permission.allows(RequestKind::SendTransaction));
+    assert!(permission.allows_account(&account_id));
+    assert!(!permission.is_expired_at(1_000));
+    assert!(permission.is_expired_at(1_000 + 24 * 60 * 60 * 1000));
+    assert_eq!(GrantDuration::Unlimited.expires(1_000), None);
+
+    let request = Request::SendTransaction(transaction_request(TESTNET_ADDRESS, 1));
+    assert_eq!(request.kind(), Some(RequestKind::SendTransaction));
+    assert_eq!(Request::CloseWindow.kind(), None);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn test_bridge_relays_adaptor_responses() {
+    let bridge = Arc::new(Bridge::new("token"));
+    let adaptor = bridge.bind();
+    let outbound = Channel::<String>::unbounded();
+    let origin = Bridge::session_origin();
+    bridge.register(7, origin.as_str(), outbound.sender.clone());
+
+    // clients without an origin do not share grants across sessions
+    assert!(origin.starts_with(LOCAL_SCRIPT_ORIGIN_PREFIX));
+    assert_ne!(origin, Bridge::session_origin());
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(bridge.handle_message(
+        7,
+        origin.as_str(),
+        r#"{"action":"Unknown","rid":"x-1"}"#,
+    ));
+    let reply: serde_json::Value =
+        serde_json::from_str(&outbound.receiver.try_recv().unwrap()).unwrap();
+    assert_eq!(reply["type"], "Canceled");
+    assert_eq!(reply["rid"], "x-1");
+
+    runtime.block_on(async {
+        bridge.enqueue(PendingRequest::new(
+            7,
+            Some("Connect-1".to_string()),
+            Request::Connect {},
+        ));
+    });
+
+    wait_for(|| matches!(adaptor.request(), Some(Request::Connect {})));
+    adaptor.reject("User rejected the request.");
+
+    wait_for(|| !outbound.receiver.is_empty());
+    let reply: serde_json::Value =
+        serde_json::from_str(&outbound.receiver.try_recv().unwrap()).unwrap();
+    assert_eq!(reply["type"], "Canceled");
+    assert_eq!(reply["rid"], "Connect-1");
+    assert_eq!(reply["data"]["error"], "User rejected the request.");
+}
+
+#[test]
+fn test_notification_events() {
+    let account_id: AccountId = serde_json::from_str(
+        r#""b6c8f27e7d9a3c5f1e2d4b6a8c0e1f3a5b7d9c2e4f6a8b0c1d3e5f7a9b2c4d6e""#,
+    )
+    .unwrap();
+    let other_id: AccountId = serde_json::from_str(
+        r#""0f1e2d3c4b5a69788796a5b4c3d2e1f00f1e2d3c4b5a69788796a5b4c3d2e1f0""#,
+    )
+    .unwrap();
+    let permission = OriginPermission::new(
+        "https://example.org".to_string(),
+        vec![account_id],
+        vec![RequestKind::Connect],
+        GrantDuration::Day,
+    );
+
+    let notification = Notification::AccountChanged {
+        account_id,
+        address: TESTNET_ADDRESS.to_string(),
+    };
+    assert_eq!(
+        notification.event_for(Some(&permission)),
+        Some(PageEvent::AccountsChanged {
+            accounts: vec![TESTNET_ADDRESS.to_string()]
+        })
+    );
+    // origins without a grant are not notified
+    assert_eq!(notification.event_for(None), None);
+
+    // accounts not shared with the origin are not disclosed
+    let notification = Notification::AccountChanged {
+        account_id: other_id,
+        address: TESTNET_ADDRESS.to_string(),
+    };
+    assert_eq!(
+        notification.event_for(Some(&permission)),
+        Some(PageEvent::AccountsChanged { accounts: vec![] })
+    );
+
+    let event = PageEvent::NetworkChanged {
+        network: "testnet-10".to_string(),
+    };
+    let value = serde_json::to_value(&event).unwrap();
+    assert_eq!(value["event"], "networkChanged");
+    assert_eq!(value["data"]["network"], "testnet-10");
+}
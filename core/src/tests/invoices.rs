@@ -0,0 +1,60 @@
+use crate::imports::*;
+use crate::invoices::*;
+
+#[test]
+fn test_payment_request_uri() {
+    let address = "kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j";
+
+    assert_eq!(payment_request_uri(address, None, None), address);
+    assert_eq!(
+        payment_request_uri(address, Some(150_000_000), None),
+        format!("{address}?amount=1.5")
+    );
+    assert_eq!(
+        payment_request_uri(address, None, Some("coffee")),
+        format!("{address}?label=coffee")
+    );
+    assert_eq!(
+        payment_request_uri(address, Some(150_000_000), Some("Order #12 & tip?")),
+        format!("{address}?amount=1.5&label=Order%20%2312%20%26%20tip%3F")
+    );
+}
+
+#[test]
+fn test_invoice_reorg_revert() {
+    let account_id: AccountId = serde_json::from_str(
+        r#""b6c8f27e7d9a3c5f1e2d4b6a8c0e1f3a5b7d9c2e4f6a8b0c1d3e5f7a9b2c4d6e""#,
+    )
+    .unwrap();
+    let address =
+        Address::try_from("kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j")
+            .unwrap();
+    let mut invoice = Invoice::new(
+        account_id,
+        Network::Mainnet,
+        &address,
+        Some(300_000_000),
+        None,
+        InvoiceExpiry::Never,
+    );
+
+    let first = TransactionId::from_u64_word(1);
+    let second = TransactionId::from_u64_word(2);
+
+    assert!(invoice.credit(first, 100_000_000));
+    assert!(!invoice.credit(first, 100_000_000));
+    assert_eq!(invoice.status, InvoiceStatus::PartiallyPaid);
+    assert!(invoice.credit(second, 200_000_000));
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+
+    // a reorg of the second payment reopens the invoice
+    assert!(invoice.revert(&second, 200_000_000));
+    assert!(!invoice.revert(&second, 200_000_000));
+    assert_eq!(invoice.received_sompi, 100_000_000);
+    assert_eq!(invoice.transactions, vec![first]);
+    assert_eq!(invoice.status, InvoiceStatus::PartiallyPaid);
+
+    assert!(invoice.revert(&first, 100_000_000));
+    assert_eq!(invoice.received_sompi, 0);
+    assert_eq!(invoice.status, InvoiceStatus::Pending);
+}
@@ -0,0 +1,458 @@
+use crate::imports::*;
+use crate::market::*;
+use crate::runtime::services::market_monitor::*;
+
+fn candle(timestamp: f64, close: f64) -> Candle {
+    [timestamp, close, close, close, close].into()
+}
+
+#[test]
+fn test_portfolio_series() {
+    let ohlc = Ohlc::new(vec![
+        candle(3000.0, 0.2),
+        candle(1000.0, 0.1),
+        candle(2000.0, 0.1),
+    ]);
+    assert_eq!(ohlc.last_close(), Some(0.2));
+
+    let balances = [(1500, 100 * SOMPI_PER_KASPA), (2500, 50 * SOMPI_PER_KASPA)];
+    let series = portfolio_series(&balances, &ohlc);
+
+    assert_eq!(series, vec![[2000.0, 10.0], [3000.0, 10.0]]);
+}
+
+#[test]
+fn test_balance_history_record() {
+    let history = BalanceHistory::default();
+    let network = Network::Mainnet;
+
+    assert!(history.record("wallet", network, 0, 100));
+    // unchanged balance is not recorded
+    assert!(!history.record("wallet", network, 120_000, 100));
+    assert!(history.record("wallet", network, 120_000, 200));
+    // a change within the resolution interval replaces the last record
+    assert!(history.record("wallet", network, 130_000, 300));
+    assert!(history.record("other", network, 130_000, 1));
+
+    assert_eq!(
+        history.series("wallet", network),
+        vec![(0, 100), (130_000, 300)]
+    );
+    assert!(history.series("wallet", Network::Testnet10).is_empty());
+}
+
+/// Minimal HTTP server standing in for a self-hosted price endpoint.
+/// Responds with the body routed to the request path or with 404.
+struct StandIn {
+    url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+    headers: Arc<Mutex<Vec<Vec<String>>>>,
+}
+
+impl StandIn {
+    fn start(routes: Vec<(&'static str, &'static str)>) -> Self {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let headers = Arc::new(Mutex::new(Vec::new()));
+
+        let log = requests.clone();
+        let header_log = headers.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    break;
+                };
+
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                reader.read_line(&mut request).unwrap();
+                let mut request_headers = Vec::new();
+                loop {
+                    let mut header = String::new();
+                    if reader.read_line(&mut header).unwrap_or(0) <= 2 {
+                        break;
+                    }
+                    request_headers.push(header.trim().to_lowercase());
+                }
+
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                log.lock().unwrap().push(path.to_string());
+                header_log.lock().unwrap().push(request_headers);
+                let (status, body) = routes
+                    .iter()
+                    .find(|(route, _)| *route == path)
+                    .map(|(_, body)| ("200 OK", *body))
+                    .unwrap_or(("404 Not Found", ""));
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        Self {
+            url,
+            requests,
+            headers,
+        }
+    }
+
+    fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Lowercase header lines of each request
+    fn headers(&self) -> Vec<Vec<String>> {
+        self.headers.lock().unwrap().clone()
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    tokio::runtime::Runtime::new().unwrap().block_on(future)
+}
+
+const PRICES: &str = r#"{
+    "data": {
+        "usd": { "price": 0.1375, "cap": 2954668910.5, "vol": "138844602.5" },
+        "eur": { "price": 0.1265 }
+    }
+}"#;
+
+fn custom_endpoint(url: &str) -> CustomEndpointSettings {
+    CustomEndpointSettings {
+        price_url: format!("{url}/prices?vs={{currencies}}"),
+        price: "/data/{currency}/price".to_string(),
+        market_cap: "/data/{currency}/cap".to_string(),
+        volume: "/data/{currency}/vol".to_string(),
+        ohlc_url: format!("{url}/history/{{currency}}?days={{days}}"),
+        ohlc: "/candles".to_string(),
+        currencies_url: format!("{url}/currencies"),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_custom_endpoint() {
+    let server = StandIn::start(vec![
+        ("/prices?vs=usd,eur,btc", PRICES),
+        (
+            "/history/usd?days=30",
+            r#"{ "candles": [[2000, 0.1, 0.2, 0.1, 0.15], [1000, 0.1, 0.1, 0.1, 0.1], ["bad"]] }"#,
+        ),
+        ("/currencies", r#"["USD", "eur", 1]"#),
+    ]);
+    let provider = CustomEndpoint::new(custom_endpoint(&server.url));
+
+    let prices = block_on(provider.fetch_market_price_list(&["USD", "eur", "btc"])).unwrap();
+    assert_eq!(prices.len(), 2);
+    let usd = prices.get("usd").unwrap();
+    assert_eq!(usd.price, 0.1375);
+    assert_eq!(usd.market_cap, 2954668910.5);
+    assert_eq!(usd.volume, 138844602.5);
+    assert_eq!(usd.change, 0.0);
+    assert_eq!(prices.get("eur").unwrap().price, 0.1265);
+
+    let ohlc = block_on(provider.fetch_ohlc("usd", 30)).unwrap();
+    assert_eq!(
+        ohlc.candles,
+        vec![candle(1000.0, 0.1), [2000.0, 0.1, 0.2, 0.1, 0.15].into()]
+    );
+
+    let currencies = block_on(provider.fetch_available_currencies()).unwrap();
+    let symbols = currencies
+        .iter()
+        .map(|currency| currency.symbol.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(symbols, vec!["usd", "eur"]);
+}
+
+#[test]
+fn test_custom_endpoint_failover() {
+    let server = StandIn::start(vec![("/fallback/prices?vs=usd", PRICES)]);
+
+    let unavailable = custom_endpoint(&server.url);
+    let fallback = CustomEndpointSettings {
+        price_url: format!("{}/fallback/prices?vs={{currencies}}", server.url),
+        ..custom_endpoint(&server.url)
+    };
+
+    let provider = Failover::new(vec![
+        Arc::new(CustomEndpoint::new(unavailable.clone())),
+        Arc::new(CustomEndpoint::new(fallback)),
+    ]);
+    let prices = block_on(provider.fetch_market_price_list(&["usd"])).unwrap();
+    assert_eq!(prices.get("usd").unwrap().price, 0.1375);
+    assert_eq!(
+        server.requests(),
+        vec!["/prices?vs=usd", "/fallback/prices?vs=usd"]
+    );
+
+    let provider = Failover::new(vec![Arc::new(CustomEndpoint::new(unavailable))]);
+    assert!(block_on(provider.fetch_market_price_list(&["usd"])).is_err());
+}
+
+#[test]
+fn test_market_provider_failover_chain() {
+    let mut settings = MarketMonitorSettings {
+        provider: MarketDataProviderKind::Custom,
+        ..Default::default()
+    };
+    assert_eq!(settings.provider().name(), "Custom, CoinGecko");
+
+    settings.provider = MarketDataProviderKind::CoinGecko;
+    assert_eq!(settings.provider().name(), "CoinGecko");
+
+    settings.custom = custom_endpoint("http://127.0.0.1");
+    assert_eq!(settings.provider().name(), "CoinGecko, Custom");
+
+    settings.coinmarketcap.api_key = "key".to_string();
+    assert_eq!(
+        settings.provider().name(),
+        "CoinGecko, CoinMarketCap, Custom"
+    );
+
+    settings.failover = false;
+    assert_eq!(settings.provider().name(), "CoinGecko");
+}
+
+#[test]
+fn test_coinmarketcap() {
+    let server = StandIn::start(vec![
+        (
+            "/v2/cryptocurrency/quotes/latest?id=20396&convert=USD,BTC",
+            r#"{
+                "status": { "error_code": 0 },
+                "data": {
+                    "20396": {
+                        "quote": {
+                            "USD": { "price": 0.1375, "volume_24h": 138844602.5, "percent_change_24h": 16.5, "market_cap": 2954668910.5 },
+                            "BTC": { "price": 0.000002, "volume_24h": null, "percent_change_24h": null, "market_cap": null }
+                        }
+                    }
+                }
+            }"#,
+        ),
+        (
+            "/v2/cryptocurrency/ohlcv/historical?id=20396&convert=USD&time_period=daily&count=30",
+            r#"{
+                "data": {
+                    "quotes": [
+                        { "time_open": "1970-01-01T00:00:02.000Z", "quote": { "USD": { "open": 0.1, "high": 0.2, "low": 0.1, "close": 0.15 } } },
+                        { "time_open": "1970-01-01T00:00:01.000Z", "quote": { "USD": { "open": 0.1, "high": 0.1, "low": 0.1, "close": 0.1 } } },
+                        { "time_open": "invalid", "quote": {} }
+                    ]
+                }
+            }"#,
+        ),
+        (
+            "/v1/fiat/map",
+            r#"{ "data": [{ "id": 2781, "name": "United States Dollar", "sign": "$", "symbol": "USD" }] }"#,
+        ),
+    ]);
+    let settings = CoinMarketCapSettings {
+        api_key: "key".to_string(),
+    };
+    let provider = CoinMarketCap::new(settings).with_api_url(&server.url);
+
+    let prices = block_on(provider.fetch_market_price_list(&["usd", "btc"])).unwrap();
+    assert_eq!(prices.len(), 2);
+    let usd = prices.get("usd").unwrap();
+    assert_eq!(usd.price, 0.1375);
+    assert_eq!(usd.market_cap, 2954668910.5);
+    assert_eq!(usd.volume, 138844602.5);
+    assert_eq!(usd.change, 16.5);
+    assert_eq!(prices.get("btc").unwrap().market_cap, 0.0);
+
+    let ohlc = block_on(provider.fetch_ohlc("usd", 30)).unwrap();
+    assert_eq!(
+        ohlc.candles,
+        vec![candle(1000.0, 0.1), [2000.0, 0.1, 0.2, 0.1, 0.15].into()]
+    );
+
+    let currencies = block_on(provider.fetch_available_currencies()).unwrap();
+    let symbols = currencies
+        .iter()
+        .map(|currency| currency.symbol.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(symbols, vec!["btc", "usd"]);
+
+    // no request is made without an API key
+    let provider = CoinMarketCap::new(CoinMarketCapSettings::default()).with_api_url(&server.url);
+    assert!(block_on(provider.fetch_market_price_list(&["usd"])).is_err());
+    assert_eq!(server.requests().len(), 3);
+
+    // the API key is sent in a header rather than in the url
+    assert!(server.requests().iter().all(|path| !path.contains("key")));
+    assert!(
+        server
+            .headers()
+            .iter()
+            .all(|headers| headers.contains(&"x-cmc_pro_api_key: key".to_string()))
+    );
+}
+
+fn market_price_list(price: f64, change: f64) -> MarketDataMap {
+    let mut data = MarketData::new("usd");
+    data.price = price;
+    data.change = change;
+    [("usd".to_string(), data)].into_iter().collect()
+}
+
+#[test]
+fn test_price_alerts() {
+    let alerts = vec![
+        Alert::new(AlertCondition::PriceCrosses {
+            currency: "USD".to_string(),
+            price: 0.15,
+        }),
+        Alert::new(AlertCondition::ChangeExceeds {
+            currency: "usd".to_string(),
+            percent: 10.0,
+        }),
+    ];
+    let mut monitor = AlertMonitor::default();
+
+    // the first observation does not trigger a crossing
+    assert!(
+        monitor
+            .evaluate_prices(&alerts, &market_price_list(0.14, 2.0))
+            .is_empty()
+    );
+    assert_eq!(
+        monitor.evaluate_prices(&alerts, &market_price_list(0.16, 12.0)),
+        vec![
+            AlertEvent::PriceCrossed {
+                currency: "USD".to_string(),
+                price: 0.16,
+                threshold: 0.15,
+                rising: true,
+            },
+            AlertEvent::ChangeExceeded {
+                currency: "usd".to_string(),
+                change: 12.0,
+                percent: 10.0,
+            },
+        ]
+    );
+    // alerts are delivered once until the condition clears
+    assert!(
+        monitor
+            .evaluate_prices(&alerts, &market_price_list(0.17, -11.0))
+            .is_empty()
+    );
+    assert_eq!(
+        monitor.evaluate_prices(&alerts, &market_price_list(0.14, 5.0)),
+        vec![AlertEvent::PriceCrossed {
+            currency: "USD".to_string(),
+            price: 0.14,
+            threshold: 0.15,
+            rising: false,
+        }]
+    );
+    assert_eq!(
+        monitor
+            .evaluate_prices(&alerts, &market_price_list(0.14, -10.0))
+            .len(),
+        1
+    );
+
+    let disabled = alerts
+        .into_iter()
+        .map(|alert| Alert {
+            enabled: false,
+            ..alert
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        monitor
+            .evaluate_prices(&disabled, &market_price_list(0.2, 20.0))
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_balance_alerts() {
+    let account_id: AccountId = serde_json::from_str(
+        r#""b6c8f27e7d9a3c5f1e2d4b6a8c0e1f3a5b7d9c2e4f6a8b0c1d3e5f7a9b2c4d6e""#,
+    )
+    .unwrap();
+    let other_id: AccountId = serde_json::from_str(
+        r#""0f1e2d3c4b5a69788796a5b4c3d2e1f00f1e2d3c4b5a69788796a5b4c3d2e1f0""#,
+    )
+    .unwrap();
+
+    let alerts = vec![Alert::new(AlertCondition::BalanceBelow {
+        account_id: Some(account_id),
+        amount_sompi: 100,
+    })];
+    let mut monitor = AlertMonitor::default();
+
+    // a balance that is already low when first observed does not raise an alert
+    assert!(
+        monitor
+            .evaluate_balance(&alerts, &account_id, "main", 50)
+            .is_empty()
+    );
+    assert!(
+        monitor
+            .evaluate_balance(&alerts, &account_id, "main", 150)
+            .is_empty()
+    );
+    assert!(
+        monitor
+            .evaluate_balance(&alerts, &other_id, "other", 50)
+            .is_empty()
+    );
+    assert_eq!(
+        monitor.evaluate_balance(&alerts, &account_id, "main", 50),
+        vec![AlertEvent::BalanceBelow {
+            account: "main".to_string(),
+            balance_sompi: 50,
+            amount_sompi: 100,
+        }]
+    );
+    assert!(
+        monitor
+            .evaluate_balance(&alerts, &account_id, "main", 40)
+            .is_empty()
+    );
+
+    let any_account = vec![Alert::new(AlertCondition::BalanceBelow {
+        account_id: None,
+        amount_sompi: 100,
+    })];
+    assert!(
+        monitor
+            .evaluate_balance(&any_account, &other_id, "other", 150)
+            .is_empty()
+    );
+    assert_eq!(
+        monitor
+            .evaluate_balance(&any_account, &other_id, "other", 50)
+            .len(),
+        1
+    );
+
+    monitor.reset();
+    assert!(
+        monitor
+            .evaluate_balance(&alerts, &account_id, "main", 40)
+            .is_empty()
+    );
+    assert!(
+        monitor
+            .evaluate_balance(&alerts, &account_id, "main", 150)
+            .is_empty()
+    );
+    assert_eq!(
+        monitor
+            .evaluate_balance(&alerts, &account_id, "main", 40)
+            .len(),
+        1
+    );
+}
@@ -0,0 +1,418 @@
+use crate::imports::*;
+use crate::runtime::services::metrics_monitor::history::*;
+use kaspa_metrics_core::Metric;
+
+fn temp_folder(name: &str) -> std::path::PathBuf {
+    let folder = std::env::temp_dir().join(format!("kaspa-ng-test-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&folder);
+    folder
+}
+
+fn values(value: f32) -> Vec<f32> {
+    vec![value; Metric::into_iter().count()]
+}
+
+#[test]
+fn test_metrics_history_tiers() {
+    let folder = temp_folder("metrics-history");
+    let metric = Metric::into_iter().next().unwrap();
+
+    // align to an hour so that all samples fall into the same 1h bucket
+    let hour = TIERS[2].resolution as f64;
+    let start = ((unixtime_as_millis_f64() - 2.0 * hour) / hour).floor() * hour;
+
+    {
+        let mut history = MetricsHistory::load(&folder, "testnet-10").unwrap();
+        // 3 minutes of 1s samples; the value equals the minute index
+        for second in 0..180 {
+            let timestamp = start + second as f64 * 1000.0;
+            history
+                .append(timestamp, &values((second / 60) as f32))
+                .unwrap();
+        }
+        // closes the pending 1s, 1m and 1h buckets
+        history.append(start + hour, &values(10.0)).unwrap();
+
+        let seconds = history.series(metric, start, start + 180_000.0);
+        assert_eq!(seconds.len(), 180);
+        assert_eq!(seconds[61].y, 1.0);
+    }
+
+    let history = MetricsHistory::load(&folder, "testnet-10").unwrap();
+    assert_eq!(history.oldest(), Some(start));
+
+    // a range exceeding the 1s tier limit is served from the 1m tier
+    let minutes = history.series(metric, start - 30.0 * hour, start + hour);
+    assert_eq!(
+        minutes.iter().map(|point| point.y).collect::<Vec<_>>(),
+        vec![0.0, 1.0, 2.0]
+    );
+    assert_eq!(minutes[1].x, start + 60_000.0);
+
+    // a range exceeding the 1m tier limit is served from the 1h tier
+    let hours = history.series(metric, start - 100.0 * 24.0 * hour, start + hour);
+    assert_eq!(hours.len(), 1);
+    assert_eq!(hours[0].y, 1.0);
+
+    // histories are kept per network
+    let other = MetricsHistory::load(&folder, "mainnet").unwrap();
+    assert_eq!(other.oldest(), None);
+
+    drop(history);
+    drop(other);
+    std::fs::remove_dir_all(&folder).unwrap();
+}
+
+#[test]
+fn test_metrics_history_partial_bucket() {
+    let folder = temp_folder("metrics-history-partial");
+    let metric = Metric::into_iter().next().unwrap();
+
+    let hour = TIERS[2].resolution as f64;
+    let start = ((unixtime_as_millis_f64() - 2.0 * hour) / hour).floor() * hour;
+
+    {
+        let mut history = MetricsHistory::load(&folder, "testnet-10").unwrap();
+        history.append(start, &values(1.0)).unwrap();
+        history.append(start + 1000.0, &values(3.0)).unwrap();
+    }
+    assert!(folder.join("testnet-10-1m.partial").exists());
+
+    {
+        // samples taken after the restart are merged into the open buckets
+        let mut history = MetricsHistory::load(&folder, "testnet-10").unwrap();
+        history.append(start + 2000.0, &values(5.0)).unwrap();
+        history.append(start + hour, &values(10.0)).unwrap();
+    }
+
+    let history = MetricsHistory::load(&folder, "testnet-10").unwrap();
+    let minutes = history.series(metric, start - 30.0 * hour, start + hour);
+    assert_eq!(
+        minutes.iter().map(|point| point.y).collect::<Vec<_>>(),
+        vec![3.0]
+    );
+    let hours = history.series(metric, start - 100.0 * 24.0 * hour, start + hour);
+    assert_eq!(
+        hours.iter().map(|point| point.y).collect::<Vec<_>>(),
+        vec![3.0]
+    );
+
+    drop(history);
+    std::fs::remove_dir_all(&folder).unwrap();
+}
+
+#[test]
+fn test_metrics_exporter_render() {
+    use crate::runtime::services::metrics_exporter::*;
+    use kaspa_metrics_core::MetricsSnapshot;
+
+    let snapshot = MetricsSnapshot {
+        node_active_peers: 8.0,
+        network_mempool_size: 120.0,
+        node_cpu_usage: f64::NAN,
+        ..Default::default()
+    };
+
+    let gauges = ExporterGauges {
+        network: Some("testnet-10".to_string()),
+        is_synced: Some(true),
+        peers: Some(8),
+        mempool_size: Some(120),
+        feerate: vec![("low", FeerateBucket::new(1.0, 60.0))],
+        balances: vec![AccountBalanceGauge {
+            account_id: "abc".to_string(),
+            name: "My \"main\" account".to_string(),
+            mature_sompi: 150_000_000,
+            pending_sompi: 0,
+        }],
+    };
+
+    let output = render(Some(&snapshot), &gauges);
+    let lines = output.lines().collect::<Vec<_>>();
+
+    // every metric is exported as a gauge family
+    let families = lines
+        .iter()
+        .filter(|line| line.starts_with("# TYPE kaspa_") && !line.starts_with("# TYPE kaspa_ng_"))
+        .count();
+    assert_eq!(families, Metric::into_iter().count());
+    assert!(lines.contains(&"# TYPE kaspa_node_p2p_bytes_rx_per_second gauge"));
+
+    assert!(lines.contains(&"kaspa_node_active_peers 8"));
+    assert!(lines.contains(&"kaspa_network_mempool_size 120"));
+    assert!(lines.contains(&"kaspa_node_cpu_usage NaN"));
+    assert!(lines.contains(&"kaspa_ng_synced 1"));
+    assert!(lines.contains(&"kaspa_ng_peers 8"));
+    assert!(lines.contains(&"kaspa_ng_mempool_size 120"));
+    assert!(lines.contains(&"kaspa_ng_feerate{bucket=\"low\"} 1"));
+    assert!(lines.contains(&"kaspa_ng_feerate_estimated_seconds{bucket=\"low\"} 60"));
+    assert!(lines.contains(
+        &"kaspa_ng_account_balance_kas{account_id=\"abc\",name=\"My \\\"main\\\" account\",state=\"mature\"} 1.5"
+    ));
+    assert_eq!(lines.last(), Some(&"# EOF"));
+
+    // application gauges are omitted when unavailable
+    let output = render(None, &ExporterGauges::default());
+    assert_eq!(output, "# EOF\n");
+}
+
+#[test]
+fn test_metrics_csv_export() {
+    use crate::runtime::services::metrics_monitor::metrics_to_csv;
+
+    let series = vec![
+        (
+            Metric::NodeActivePeers,
+            vec![
+                PlotPoint { x: 1000.0, y: 8.0 },
+                PlotPoint { x: 2000.0, y: 9.0 },
+            ],
+        ),
+        (
+            Metric::NetworkMempoolSize,
+            vec![
+                PlotPoint {
+                    x: 2000.0,
+                    y: 120.5,
+                },
+                PlotPoint {
+                    x: 3000.0,
+                    y: f64::NAN,
+                },
+            ],
+        ),
+    ];
+
+    let csv = metrics_to_csv(&series);
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        vec![
+            "timestamp,time,NodeActivePeers,NetworkMempoolSize",
+            "1000,1970-01-01T00:00:01.000Z,8,",
+            "2000,1970-01-01T00:00:02.000Z,9,120.5",
+            "3000,1970-01-01T00:00:03.000Z,,",
+        ]
+    );
+}
+
+#[test]
+fn test_metrics_mark_comparison() {
+    use crate::runtime::services::metrics_monitor::{MetricsMark, compare_marks};
+    use kaspa_metrics_core::MetricsSnapshot;
+
+    let first = MetricsMark::new(&MetricsSnapshot {
+        unixtime_millis: 1000.0,
+        node_active_peers: 8.0,
+        node_resident_set_size_bytes: 1000.0,
+        ..Default::default()
+    });
+    let second = MetricsMark::new(&MetricsSnapshot {
+        unixtime_millis: 2000.0,
+        node_active_peers: 6.0,
+        node_resident_set_size_bytes: 1500.0,
+        network_mempool_size: 10.0,
+        ..Default::default()
+    });
+
+    let deltas = compare_marks(&first, &second);
+    assert_eq!(deltas.len(), Metric::into_iter().count());
+
+    let find = |metric: Metric| {
+        deltas
+            .iter()
+            .find(|delta| delta.metric == metric)
+            .cloned()
+            .unwrap()
+    };
+
+    let peers = find(Metric::NodeActivePeers);
+    assert_eq!((peers.first, peers.second, peers.delta), (8.0, 6.0, -2.0));
+    assert_eq!(peers.percent, Some(-25.0));
+    assert_eq!(find(Metric::NodeResidentSetSizeBytes).percent, Some(50.0));
+
+    // relative change is undefined when starting from zero
+    let mempool = find(Metric::NetworkMempoolSize);
+    assert_eq!(mempool.delta, 10.0);
+    assert_eq!(mempool.percent, None);
+}
+
+#[test]
+fn test_metrics_dashboards() {
+    use crate::settings::{MetricsDashboard, MetricsGraph};
+
+    let mut settings = UserInterfaceSettings::default();
+    assert!(settings.active_dashboard().is_none());
+
+    let mut dashboard = MetricsDashboard::new(
+        "Network",
+        [
+            Metric::NodeActivePeers,
+            Metric::NetworkMempoolSize,
+            Metric::NetworkTransactionsPerSecond,
+        ],
+    );
+    dashboard.move_graph(2, -2);
+    dashboard.move_graph(0, -1);
+    dashboard.move_graph(2, 1);
+    assert_eq!(
+        dashboard
+            .graphs
+            .iter()
+            .map(|graph| graph.metric)
+            .collect::<Vec<_>>(),
+        vec![
+            Metric::NetworkTransactionsPerSecond,
+            Metric::NodeActivePeers,
+            Metric::NetworkMempoolSize,
+        ]
+    );
+    dashboard.graphs[0].overlay = Some(Metric::NetworkMempoolSize);
+
+    settings.dashboards.push(dashboard.clone());
+    settings.dashboard = Some("Network".to_string());
+    assert_eq!(settings.active_dashboard(), Some(&dashboard));
+
+    // dashboards survive a settings round trip
+    let json = serde_json::to_string(&settings).unwrap();
+    let restored = serde_json::from_str::<UserInterfaceSettings>(&json).unwrap();
+    assert_eq!(restored.active_dashboard(), Some(&dashboard));
+    assert_eq!(
+        restored.active_dashboard().unwrap().graphs[0],
+        MetricsGraph {
+            metric: Metric::NetworkTransactionsPerSecond,
+            overlay: Some(Metric::NetworkMempoolSize),
+        }
+    );
+
+    // settings stored before dashboards were introduced
+    let mut legacy = serde_json::to_value(UserInterfaceSettings::default()).unwrap();
+    let object = legacy.as_object_mut().unwrap();
+    object.remove("dashboards");
+    object.remove("dashboard");
+    let legacy = serde_json::from_value::<UserInterfaceSettings>(legacy).unwrap();
+    assert!(legacy.dashboards.is_empty());
+}
+
+#[test]
+fn test_metric_alert_rules() {
+    use crate::runtime::services::metrics_monitor::{
+        MetricAlertMonitor, MetricCondition, MetricRule,
+    };
+    use kaspa_metrics_core::MetricsSnapshot;
+
+    let snapshot = |seconds: u64, peers: f64, daa_score: f64| MetricsSnapshot {
+        unixtime_millis: seconds as f64 * 1000.0,
+        node_active_peers: peers,
+        network_virtual_daa_score: daa_score,
+        ..Default::default()
+    };
+
+    let peers = MetricRule::new(
+        MetricCondition::Below {
+            metric: Metric::NodeActivePeers,
+            value: 3.0,
+        },
+        120,
+    );
+    let stalled = MetricRule::new(
+        MetricCondition::Stalled {
+            metric: Metric::NetworkVirtualDaaScore,
+        },
+        60,
+    );
+    let rules = vec![peers.clone(), stalled.clone()];
+    let mut monitor = MetricAlertMonitor::default();
+
+    // rules are not evaluated until the node is synced
+    assert!(
+        monitor
+            .evaluate(&rules, &snapshot(0, 0.0, 100.0))
+            .is_empty()
+    );
+    assert!(
+        monitor
+            .evaluate(&rules, &snapshot(600, 0.0, 100.0))
+            .is_empty()
+    );
+    monitor.update_sync_state(true);
+
+    // peers drop at 0s; the alert is raised once the condition held for 2 minutes
+    assert!(
+        monitor
+            .evaluate(&rules, &snapshot(0, 2.0, 100.0))
+            .is_empty()
+    );
+    assert!(
+        monitor
+            .evaluate(&rules, &snapshot(30, 2.0, 110.0))
+            .is_empty()
+    );
+    assert!(
+        monitor
+            .evaluate(&rules, &snapshot(119, 1.0, 120.0))
+            .is_empty()
+    );
+    let events = monitor.evaluate(&rules, &snapshot(120, 1.0, 130.0));
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].rule, peers);
+    assert!(events[0].active);
+    assert_eq!(events[0].value, 1.0);
+    assert_eq!(monitor.active(&rules), vec![peers.clone()]);
+
+    // raised alerts are not repeated
+    assert!(
+        monitor
+            .evaluate(&rules, &snapshot(121, 1.0, 140.0))
+            .is_empty()
+    );
+
+    // the DAA score stalls at 140 from 121s
+    assert!(
+        monitor
+            .evaluate(&rules, &snapshot(180, 1.0, 140.0))
+            .is_empty()
+    );
+    let events = monitor.evaluate(&rules, &snapshot(181, 8.0, 140.0));
+    assert_eq!(events.len(), 2);
+    assert_eq!(
+        (events[0].rule.clone(), events[0].active),
+        (peers.clone(), false)
+    );
+    assert_eq!(
+        (events[1].rule.clone(), events[1].active),
+        (stalled.clone(), true)
+    );
+    assert_eq!(monitor.active(&rules), vec![stalled.clone()]);
+
+    // the alert clears once the score advances
+    let events = monitor.evaluate(&rules, &snapshot(182, 8.0, 150.0));
+    assert_eq!(events.len(), 1);
+    assert!(!events[0].active);
+    assert!(monitor.active(&rules).is_empty());
+
+    // once synced, losing sync neither suspends rules nor clears alerts
+    assert!(
+        monitor
+            .evaluate(&rules, &snapshot(200, 1.0, 150.0))
+            .is_empty()
+    );
+    monitor.update_sync_state(false);
+    let events = monitor.evaluate(&rules, &snapshot(320, 1.0, 160.0));
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        (events[0].rule.clone(), events[0].active),
+        (peers.clone(), true)
+    );
+    monitor.update_sync_state(false);
+    assert_eq!(monitor.active(&rules), vec![peers]);
+
+    // disabled rules are ignored
+    let mut disabled = stalled;
+    disabled.enabled = false;
+    assert!(
+        monitor
+            .evaluate(&[disabled], &snapshot(1000, 8.0, 150.0))
+            .is_empty()
+    );
+}
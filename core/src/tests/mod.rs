@@ -1 +1,9 @@
+mod blocks;
+mod interop;
+mod invoices;
+mod market;
+mod metrics;
+mod peers;
+mod storage;
 mod transactions;
+mod wallet;
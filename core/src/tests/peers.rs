@@ -0,0 +1,98 @@
+use crate::imports::*;
+use crate::runtime::services::peer_monitor::*;
+
+#[test]
+fn test_peer_version_histogram() {
+    assert_eq!(
+        user_agent_version("/kaspad:1.0.0/kaspad:1.0.0(kdx)/"),
+        "kaspad:1.0.0"
+    );
+    assert_eq!(user_agent_version("/kaspad:0.16.1/"), "kaspad:0.16.1");
+    assert_eq!(user_agent_version(""), "unknown");
+
+    let histogram = version_histogram([
+        "/kaspad:0.16.1/",
+        "/kaspad:1.0.0/kaspad:1.0.0(kaspa-ng)/",
+        "/kaspad:1.0.0/",
+        "",
+        "/kaspad:0.15.2/",
+        "/kaspad:0.16.1/",
+        "/kaspad:1.0.0/",
+    ]);
+    assert_eq!(
+        histogram,
+        vec![
+            ("kaspad:1.0.0".to_string(), 3),
+            ("kaspad:0.16.1".to_string(), 2),
+            ("kaspad:0.15.2".to_string(), 1),
+            ("unknown".to_string(), 1),
+        ]
+    );
+}
+
+#[test]
+fn test_peer_country_histogram() {
+    use std::net::IpAddr;
+
+    let ip = |ip: &str| ip.parse::<IpAddr>().unwrap();
+    assert!(is_public_address(&ip("203.0.113.7")));
+    assert!(is_public_address(&ip("2001:db8::1")));
+    assert!(!is_public_address(&ip("127.0.0.1")));
+    assert!(!is_public_address(&ip("192.168.1.10")));
+    assert!(!is_public_address(&ip("fe80::1")));
+    assert!(!is_public_address(&ip("fd00::1")));
+
+    let countries = [
+        (ip("203.0.113.7"), "DE".to_string()),
+        (ip("203.0.113.8"), "DE".to_string()),
+        (ip("2001:db8::1"), "US".to_string()),
+    ]
+    .into_iter()
+    .collect::<AHashMap<_, _>>();
+
+    let histogram = country_histogram(
+        [
+            ip("203.0.113.7"),
+            ip("2001:db8::1"),
+            ip("203.0.113.8"),
+            ip("10.0.0.1"),
+            ip("198.51.100.1"),
+        ],
+        &countries,
+    );
+    assert_eq!(
+        histogram,
+        vec![
+            ("DE".to_string(), 2),
+            ("US".to_string(), 1),
+            ("local".to_string(), 1),
+            ("unknown".to_string(), 1),
+        ]
+    );
+}
+
+#[test]
+fn test_peer_management_unsafe_rpc() {
+    use crate::settings::*;
+
+    let mut settings = NodeSettings::default();
+    // unsafe RPC is opt-in
+    assert!(!settings.unsafe_rpc_enabled());
+    settings.enable_unsafe_rpc = true;
+    assert!(settings.unsafe_rpc_enabled());
+
+    settings.enable_wrpc_borsh = true;
+    assert!(settings.is_rpc_public());
+    assert!(!settings.unsafe_rpc_enabled());
+    settings.enable_wrpc_borsh = false;
+
+    // custom rpc listeners are treated as public
+    settings.kaspad_daemon_args = "--rpclisten-json=0.0.0.0:18110".to_string();
+    assert!(settings.unsafe_rpc_enabled());
+    settings.kaspad_daemon_args_enable = true;
+    assert!(!settings.unsafe_rpc_enabled());
+    settings.kaspad_daemon_args_enable = false;
+
+    settings.node_kind = KaspadNodeKind::Remote;
+    assert!(!settings.unsafe_rpc_enabled());
+}
@@ -0,0 +1,94 @@
+use crate::imports::*;
+use crate::storage::*;
+
+#[test]
+fn test_storage_free_space() {
+    assert_eq!(FreeSpace::from_available(0), FreeSpace::Critical);
+    assert_eq!(
+        FreeSpace::from_available(FREE_SPACE_CRITICAL - 1),
+        FreeSpace::Critical
+    );
+    assert_eq!(
+        FreeSpace::from_available(FREE_SPACE_CRITICAL),
+        FreeSpace::Low
+    );
+    assert_eq!(
+        FreeSpace::from_available(FREE_SPACE_WARNING),
+        FreeSpace::Sufficient
+    );
+}
+
+#[test]
+fn test_storage_move_folder() {
+    let root = std::env::temp_dir().join(format!("kaspa-ng-storage-test-{}", std::process::id()));
+    let source = root.join("source").join("kaspa-mainnet");
+    let target = root.join("target").join("kaspa-mainnet");
+
+    std::fs::create_dir_all(source.join("datadir").join("consensus")).unwrap();
+    std::fs::write(
+        source.join("datadir").join("consensus").join("data"),
+        b"data",
+    )
+    .unwrap();
+    std::fs::write(source.join("rusty-kaspa.log"), b"log").unwrap();
+    std::fs::create_dir_all(root.join("target")).unwrap();
+
+    let progress = AtomicU64::new(0);
+    assert!(move_folder(&source, &target, &progress).unwrap().is_none());
+
+    assert!(!source.exists());
+    assert_eq!(
+        std::fs::read(target.join("datadir").join("consensus").join("data")).unwrap(),
+        b"data"
+    );
+    assert_eq!(
+        std::fs::read(target.join("rusty-kaspa.log")).unwrap(),
+        b"log"
+    );
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_storage_copy_folder() {
+    let root =
+        std::env::temp_dir().join(format!("kaspa-ng-storage-copy-test-{}", std::process::id()));
+    let source = root.join("source").join("kaspa-mainnet");
+    let target = root.join("target").join("kaspa-mainnet");
+
+    std::fs::create_dir_all(source.join("datadir")).unwrap();
+    std::fs::write(source.join("datadir").join("data"), b"data").unwrap();
+    std::fs::write(source.join("rusty-kaspa.log"), b"log").unwrap();
+
+    let progress = AtomicU64::new(0);
+    copy_folder(&source, &target, &progress).unwrap();
+    assert_eq!(progress.load(Ordering::Relaxed), 7);
+    assert_eq!(
+        std::fs::read(target.join("datadir").join("data")).unwrap(),
+        b"data"
+    );
+    assert!(source.exists());
+
+    // a failed copy does not leave a partial target behind
+    let missing = root.join("missing");
+    let partial = root.join("partial");
+    assert!(copy_folder(&missing, &partial, &progress).is_err());
+    assert!(!partial.exists());
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_storage_remove_folder() {
+    let root = std::env::temp_dir().join(format!(
+        "kaspa-ng-storage-remove-test-{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(root.join("datadir").join("consensus")).unwrap();
+    std::fs::write(root.join("datadir").join("consensus").join("data"), b"data").unwrap();
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(remove_folder(root.clone())).unwrap();
+    assert!(!root.exists());
+    assert!(runtime.block_on(remove_folder(root)).is_err());
+}
@@ -0,0 +1,165 @@
+use crate::imports::*;
+use crate::utils::*;
+use kaspa_wallet_core::derivation::AddressDerivationManagerTrait;
+
+const XPUB_KEYS: [&str; 3] = [
+    "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8",
+    "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw",
+    "xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ",
+];
+
+fn wallet_file(
+    version: u32,
+    mnemonics: usize,
+    keys: &[&str],
+    minimum_signatures: u16,
+    cosigner_index: u8,
+) -> String {
+    let encrypted_mnemonics = (0..mnemonics)
+        .map(|n| format!(r#"{{ "cipher": "0{n}0102030405", "salt": "0{n}0a0b0c" }}"#))
+        .collect::<Vec<_>>()
+        .join(",");
+    let public_keys = keys
+        .iter()
+        .map(|key| format!(r#""{key}""#))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!(
+        r#"{{
+            "version": {version},
+            "numThreads": 8,
+            "encryptedMnemonics": [{encrypted_mnemonics}],
+            "publicKeys": [{public_keys}],
+            "minimumSignatures": {minimum_signatures},
+            "cosignerIndex": {cosigner_index},
+            "ecdsa": false
+        }}"#
+    )
+}
+
+#[test]
+fn test_wallet_file_multisig() {
+    for version in [0, 1] {
+        let contents = wallet_file(version, 2, &XPUB_KEYS, 2, 1);
+        let Ok(WalletFileData::GoWallet(wallet_type)) = parse_wallet_file(&contents) else {
+            panic!("multisig wallet file should be parsed");
+        };
+
+        let multisig = match (version, wallet_type) {
+            (0, WalletType::MultiV0(multisig)) | (1, WalletType::MultiV1(multisig)) => multisig,
+            (_, wallet_type) => panic!("unexpected wallet type: {wallet_type:?}"),
+        };
+
+        assert_eq!(multisig.num_threads, 8);
+        assert_eq!(multisig.required_signatures, 2);
+        assert_eq!(multisig.cosigner_index, 1);
+        assert_eq!(multisig.xpublic_keys, XPUB_KEYS);
+        assert_eq!(multisig.encrypted_mnemonics.len(), 2);
+        assert_eq!(
+            multisig.encrypted_mnemonics[1].cipher,
+            vec![0x01, 0x01, 0x02, 0x03, 0x04, 0x05]
+        );
+        assert_eq!(
+            multisig.encrypted_mnemonics[1].salt,
+            vec![0x01, 0x0a, 0x0b, 0x0c]
+        );
+    }
+}
+
+#[test]
+fn test_wallet_file_single_signer() {
+    // 1-of-1 files hold a single signer key and are imported as single key wallets
+    let contents = wallet_file(1, 1, &XPUB_KEYS[..1], 1, 0);
+    let Ok(WalletFileData::GoWallet(WalletType::SingleV0(single))) = parse_wallet_file(&contents)
+    else {
+        panic!("1-of-1 wallet file should be parsed as a single key wallet");
+    };
+    assert_eq!(single.xpublic_key, XPUB_KEYS[0]);
+    assert_eq!(
+        single.encrypted_mnemonic.cipher,
+        vec![0x00, 0x01, 0x02, 0x03, 0x04, 0x05]
+    );
+}
+
+#[test]
+fn test_wallet_file_invalid() {
+    // more required signatures than cosigners
+    assert!(parse_wallet_file(&wallet_file(1, 2, &XPUB_KEYS, 4, 0)).is_err());
+    // no required signatures
+    assert!(parse_wallet_file(&wallet_file(1, 2, &XPUB_KEYS, 0, 0)).is_err());
+    // more mnemonics than signer keys
+    assert!(parse_wallet_file(&wallet_file(1, 2, &XPUB_KEYS[..1], 1, 0)).is_err());
+    // no mnemonics
+    assert!(parse_wallet_file(&wallet_file(1, 0, &XPUB_KEYS, 2, 0)).is_err());
+    // cosigner index out of range
+    assert!(parse_wallet_file(&wallet_file(1, 2, &XPUB_KEYS, 2, 3)).is_err());
+    // unsupported version
+    assert!(parse_wallet_file(&wallet_file(2, 2, &XPUB_KEYS, 2, 0)).is_err());
+}
+
+// XPUB_KEYS re-encoded with the kaspawallet mainnet `kpub` prefix
+const KPUB_KEYS: [&str; 3] = [
+    "kpub2C2CKMtB3F5r4LEGRnS3o73omeQB3KJ5QfAzC5R3t9bpChBEZNitvn92JYeCTMtnR7oE1im7DhsxGqV72JErXFG9G3YnTHRnZPkGZLFE6PZ",
+    "kpub2EHcK5Be8WCqCwMydYJgg99v6TxXRPn66GbtAAoArLo6ZyUQycFz3vVS5pCuCfoKRL5nsxJXxLx3FETEyKyEb8isTgM3NbL15KsprxXRXYP",
+    "kpub2GTjWrjXXD5u3PQRMoCZGt3a9qwdRRWP2bGikSZynybJoWyYhQgJ1VPfVtfUccWfP3hqfNke4wSWqYC4Sf98GnYoktBtrELGi4Qc9xmGTUP",
+];
+
+// 2-of-3 multisig addresses of cosigner 1 (m/1/<keychain>/<index>)
+const MULTISIG_RECEIVE_ADDRESSES: [&str; 2] = [
+    "kaspa:pzvz7l7750wngkdm9rym8ffglaeusc8xxeu26rq3huass4jwdg8aukvqc42k6",
+    "kaspa:prfes335y2y2qgprll3nadywvpalhly4fxg7wyug7ej03vzllzfy59gwhxx58",
+];
+const MULTISIG_CHANGE_ADDRESSES: [&str; 2] = [
+    "kaspa:prxqnuszqwtpjcsd3dqvdym68tw20eme3zgue757f5jc23stdgc4kq7z494sq",
+    "kaspa:prqggzx3nx0g34tm8d4t6fkjzxhcrjjfpqmtqhk0s8mvtrcnsrf6zku8lphnu",
+];
+
+#[test]
+fn test_wallet_file_multisig_addresses() {
+    // keys are listed out of order; the cosigner index refers to the sorted keys
+    let keys = [KPUB_KEYS[2], KPUB_KEYS[0], KPUB_KEYS[1]];
+    let contents = wallet_file(1, 1, &keys, 2, 1);
+    let Ok(WalletFileData::GoWallet(WalletType::MultiV1(multisig))) = parse_wallet_file(&contents)
+    else {
+        panic!("multisig wallet file should be parsed");
+    };
+
+    let import = MultisigWalletImport {
+        mnemonics: vec![],
+        xpub_keys: multisig.signer_keys().unwrap(),
+        cosigner_index: multisig.cosigner_index,
+        minimum_signatures: multisig.required_signatures,
+    };
+    assert_eq!(import.xpub_keys, XPUB_KEYS);
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let wallet = Arc::new(
+            CoreWallet::try_with_rpc(
+                None,
+                CoreWallet::resident_store().unwrap(),
+                Some(NetworkId::new(NetworkType::Mainnet)),
+            )
+            .unwrap(),
+        );
+        let account = import.create_account(&wallet, None, vec![]).await.unwrap();
+        let derivation = account.derivation();
+
+        let addresses =
+            |addresses: Vec<Address>| addresses.into_iter().map(String::from).collect::<Vec<_>>();
+        assert_eq!(
+            addresses(
+                derivation
+                    .receive_address_manager()
+                    .get_range(0..2)
+                    .unwrap()
+            ),
+            MULTISIG_RECEIVE_ADDRESSES
+        );
+        assert_eq!(
+            addresses(derivation.change_address_manager().get_range(0..2).unwrap()),
+            MULTISIG_CHANGE_ADDRESSES
+        );
+    });
+}
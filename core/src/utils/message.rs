@@ -0,0 +1,54 @@
+use crate::imports::*;
+use kaspa_addresses::Version as AddressVersion;
+use kaspa_bip32::secp256k1::XOnlyPublicKey;
+use kaspa_wallet_core::message::{
+    PersonalMessage, SignMessageOptions, sign_message, verify_message,
+};
+
+/// Signs a personal message with the private key of the current receive
+/// address of the given account. Returns the hex-encoded Schnorr signature
+/// along with the address whose key was used.
+pub async fn sign_personal_message(
+    account_id: AccountId,
+    message: String,
+    wallet_secret: Secret,
+    payment_secret: Option<Secret>,
+) -> Result<(Address, String)> {
+    let account = load_local_account(&account_id).await?;
+    let prv_key_data = account.prv_key_data(wallet_secret).await?;
+    let account = account.as_derivation_capable()?;
+    let address = account.receive_address()?;
+    let index = account.derivation().receive_address_manager().index();
+
+    let private_keys =
+        account.create_private_keys(&prv_key_data, &payment_secret, &[(&address, index)], &[])?;
+    let (_, private_key) = private_keys
+        .first()
+        .ok_or_else(|| Error::custom(i18n("Unable to derive the signing key")))?;
+
+    let signature = sign_message(
+        &PersonalMessage(message.as_str()),
+        &private_key.secret_bytes(),
+        &SignMessageOptions { no_aux_rand: false },
+    )
+    .map_err(|err| Error::custom(err.to_string()))?;
+
+    Ok((address, signature.to_hex()))
+}
+
+/// Verifies a hex-encoded Schnorr signature of a personal message
+/// against the public key embedded in a `PubKey` address.
+pub fn verify_personal_message(address: &Address, message: &str, signature: &str) -> Result<bool> {
+    if address.version != AddressVersion::PubKey {
+        return Err(Error::custom(i18n(
+            "Only Schnorr public key addresses can verify messages",
+        )));
+    }
+
+    let public_key = XOnlyPublicKey::from_slice(address.payload.as_slice())
+        .map_err(|err| Error::custom(err.to_string()))?;
+    let signature = Vec::<u8>::from_hex(signature.trim())
+        .map_err(|_| Error::custom(i18n("Invalid signature encoding")))?;
+
+    Ok(verify_message(&PersonalMessage(message), &signature, &public_key).is_ok())
+}
@@ -25,6 +25,8 @@ mod secret;
 pub use secret::*;
 mod mnemonic;
 pub use mnemonic::*;
+mod message;
+pub use message::*;
 mod wallet;
 pub use wallet::*;
 
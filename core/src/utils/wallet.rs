@@ -1,6 +1,12 @@
 use crate::imports::*;
+use kaspa_bip32::{ExtendedPublicKey, Prefix, secp256k1};
+use kaspa_wallet_core::account::{
+    MULTISIG_ACCOUNT_KIND,
+    multisig::{MultiSig, Payload as MultisigPayload},
+};
 use kaspa_wallet_core::wallet::EncryptedMnemonic;
 use std::fmt::Display;
+use std::str::FromStr;
 
 #[derive(Debug, Deserialize)]
 struct LegacyWalletJSONInner {
@@ -38,12 +44,174 @@ impl Clone for SingleWalletFileV0 {
     }
 }
 
+#[derive(Debug)]
+pub struct MultisigWalletFile {
+    pub num_threads: u32,
+    pub encrypted_mnemonics: Vec<EncryptedMnemonic<Vec<u8>>>,
+    pub xpublic_keys: Vec<String>,
+    pub required_signatures: u16,
+    pub cosigner_index: u8,
+    pub ecdsa: bool,
+}
+impl Clone for MultisigWalletFile {
+    fn clone(&self) -> Self {
+        Self {
+            num_threads: self.num_threads,
+            encrypted_mnemonics: self
+                .encrypted_mnemonics
+                .iter()
+                .map(|encrypted_mnemonic| EncryptedMnemonic {
+                    cipher: encrypted_mnemonic.cipher.clone(),
+                    salt: encrypted_mnemonic.salt.clone(),
+                })
+                .collect(),
+            xpublic_keys: self.xpublic_keys.clone(),
+            required_signatures: self.required_signatures,
+            cosigner_index: self.cosigner_index,
+            ecdsa: self.ecdsa,
+        }
+    }
+}
+
+impl MultisigWalletFile {
+    /// Extended public keys of all cosigners in the order used for
+    /// address derivation: kaspawallet sorts the keys as stored in the
+    /// wallet file and the cosigner index refers to this order.
+    pub fn signer_keys(&self) -> Result<Vec<String>> {
+        let mut xpub_keys = self
+            .xpublic_keys
+            .iter()
+            .map(|xpub_key| xpub_key.trim())
+            .collect::<Vec<_>>();
+        xpub_keys.sort_unstable();
+        xpub_keys.into_iter().map(normalize_xpub).collect()
+    }
+
+    /// Decrypts the local cosigner mnemonics and verifies that they
+    /// match the extended public keys of the wallet file.
+    pub async fn decrypt(self, secret: &Secret) -> Result<MultisigWalletImport> {
+        if self.ecdsa {
+            return Err(Error::custom(
+                "ECDSA multisig wallet import is not supported.",
+            ));
+        }
+
+        let xpub_keys = self.signer_keys()?;
+
+        let mnemonics = self
+            .encrypted_mnemonics
+            .into_iter()
+            .map(|encrypted_mnemonic| {
+                Ok(kaspa_wallet_core::compat::gen1::decrypt_mnemonic(
+                    self.num_threads,
+                    encrypted_mnemonic,
+                    secret.as_ref(),
+                )?)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut local_keys = Vec::with_capacity(mnemonics.len());
+        for mnemonic in mnemonics.iter() {
+            let xpub_key = kaspa_wallet_core::derivation::create_xpub_from_mnemonic(
+                mnemonic,
+                MULTISIG_ACCOUNT_KIND.into(),
+                0,
+            )
+            .await?
+            .to_string(Some(Prefix::XPUB));
+
+            let Some(index) = xpub_keys.iter().position(|key| key == &xpub_key) else {
+                return Err(Error::custom(
+                    "Wallet file mnemonics do not match its extended public keys.",
+                ));
+            };
+            local_keys.push(index);
+        }
+
+        if !local_keys.contains(&(self.cosigner_index as usize)) {
+            return Err(Error::custom(
+                "Wallet file cosigner index does not match its mnemonics.",
+            ));
+        }
+
+        Ok(MultisigWalletImport {
+            mnemonics,
+            xpub_keys,
+            cosigner_index: self.cosigner_index,
+            minimum_signatures: self.required_signatures,
+        })
+    }
+}
+
+fn normalize_xpub(xpub_key: &str) -> Result<String> {
+    let xpub_key = ExtendedPublicKey::<secp256k1::PublicKey>::from_str(xpub_key.trim())
+        .map_err(|err| Error::custom(format!("Invalid extended public key: {err}")))?;
+    Ok(xpub_key.to_string(Some(Prefix::XPUB)))
+}
+
+/// Decrypted contents of a multisig wallet file: mnemonics held by this
+/// cosigner and the extended public keys of all cosigners.
+#[derive(Debug, Default, Clone)]
+pub struct MultisigWalletImport {
+    pub mnemonics: Vec<String>,
+    pub xpub_keys: Vec<String>,
+    pub cosigner_index: u8,
+    pub minimum_signatures: u16,
+}
+
+impl MultisigWalletImport {
+    /// Creates the multisig account of the wallet file. The account is
+    /// created directly on the local wallet because the wallet API derives
+    /// the cosigner index itself, while addresses of kaspawallet multisig
+    /// wallets are derived with the cosigner index stored in the file.
+    pub async fn create_account(
+        &self,
+        wallet: &Arc<CoreWallet>,
+        name: Option<String>,
+        prv_key_data_ids: Vec<PrvKeyDataId>,
+    ) -> Result<Arc<MultiSig>> {
+        let xpub_keys = self
+            .xpub_keys
+            .iter()
+            .map(|xpub_key| {
+                ExtendedPublicKey::<secp256k1::PublicKey>::from_str(xpub_key)
+                    .map_err(|err| Error::custom(format!("Invalid extended public key: {err}")))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let account = MultiSig::try_new(
+            wallet,
+            name,
+            Arc::new(xpub_keys),
+            (!prv_key_data_ids.is_empty()).then(|| Arc::new(prv_key_data_ids)),
+            Some(self.cosigner_index),
+            self.minimum_signatures,
+            false,
+        )
+        .await?;
+
+        Ok(Arc::new(account))
+    }
+}
+
+impl Zeroize for MultisigWalletImport {
+    fn zeroize(&mut self) {
+        self.mnemonics
+            .iter_mut()
+            .for_each(|mnemonic| mnemonic.zeroize());
+        self.mnemonics.clear();
+        self.xpub_keys.clear();
+        self.cosigner_index = 0;
+        self.minimum_signatures = 0;
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum WalletType {
     SingleV0(SingleWalletFileV0),
     // SingleV1(SingleWalletFileV1<'a, Vec<u8>>),
-    // MultiV0(MultisigWalletFileV0<'a, Vec<u8>>),
-    // MultiV1(MultisigWalletFileV1<'a, Vec<u8>>),
+    MultiV0(MultisigWalletFile),
+    MultiV1(MultisigWalletFile),
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -70,16 +238,29 @@ struct UnifiedWalletIntermediate {
     num_threads: Option<u8>,
     encrypted_mnemonics: Vec<EncryptedMnemonicIntermediate>,
     public_keys: Vec<String>,
-    //minimum_signatures: u16,
-    //cosigner_index: u8,
+    minimum_signatures: Option<u16>,
+    cosigner_index: Option<u8>,
     ecdsa: bool,
 }
 
 impl UnifiedWalletIntermediate {
     fn into_wallet_type(mut self) -> Result<WalletType> {
-        let single = self.encrypted_mnemonics.len() == 1 && self.public_keys.len() == 1;
-        let wallet = match (single, self.version) {
-            (true, 0) | (true, 1) => {
+        // a multisig wallet holds more than one signer key (a 1-of-1
+        // multisig file is equivalent to a single key wallet)
+        let multisig = self.public_keys.len() > 1;
+        if self.encrypted_mnemonics.is_empty() || self.public_keys.is_empty() {
+            return Err(Error::custom("Wallet file does not contain any keys"));
+        }
+        if self.encrypted_mnemonics.len() > self.public_keys.len()
+            || !multisig && self.encrypted_mnemonics.len() != 1
+        {
+            return Err(Error::custom(
+                "Wallet file mnemonics do not match its public keys",
+            ));
+        }
+
+        let wallet = match (multisig, self.version) {
+            (false, 0) | (false, 1) => {
                 WalletType::SingleV0(SingleWalletFileV0 {
                     num_threads: self
                     .num_threads
@@ -91,7 +272,49 @@ impl UnifiedWalletIntermediate {
                     ecdsa: self.ecdsa,
                 })
             }
-            _ => return Err(Error::custom("Multisig wallet import is not supported.")),
+            (true, 0) | (true, 1) => {
+                let required_signatures = self.minimum_signatures.ok_or(Error::custom(
+                    "Multisig wallet file is missing minimum signatures",
+                ))?;
+                if required_signatures == 0 || required_signatures as usize > self.public_keys.len()
+                {
+                    return Err(Error::custom(format!(
+                        "Invalid multisig wallet minimum signatures: {required_signatures} of {}",
+                        self.public_keys.len()
+                    )));
+                }
+                let cosigner_index = self.cosigner_index.unwrap_or_default();
+                if cosigner_index as usize >= self.public_keys.len() {
+                    return Err(Error::custom(format!(
+                        "Invalid multisig wallet cosigner index: {cosigner_index} of {}",
+                        self.public_keys.len()
+                    )));
+                }
+
+                let multisig = MultisigWalletFile {
+                    num_threads: self.num_threads.unwrap_or(8) as u32,
+                    encrypted_mnemonics: self
+                        .encrypted_mnemonics
+                        .into_iter()
+                        .map(Into::into)
+                        .collect(),
+                    xpublic_keys: self.public_keys,
+                    required_signatures,
+                    cosigner_index,
+                    ecdsa: self.ecdsa,
+                };
+                if self.version == 0 {
+                    WalletType::MultiV0(multisig)
+                } else {
+                    WalletType::MultiV1(multisig)
+                }
+            }
+            _ => {
+                return Err(Error::custom(format!(
+                    "Unsupported wallet file version: {}",
+                    self.version
+                )));
+            }
         };
         // (true, WalletVersion::One) => WalletType::SingleV1(SingleWalletFileV1 {
         //     num_threads: 8,
@@ -99,42 +322,6 @@ impl UnifiedWalletIntermediate {
         //     xpublic_key: self.public_keys[0],
         //     ecdsa: self.ecdsa,
         // }),
-        // (false, WalletVersion::Zero) => WalletType::MultiV0(MultisigWalletFileV0 {
-        //     num_threads: self
-        //         .num_threads
-        //         .expect("num_threads must present in case of v0")
-        //         as u32,
-        //     encrypted_mnemonics: self
-        //         .encrypted_mnemonics
-        //         .into_iter()
-        //         .map(
-        //             |EncryptedMnemonicIntermediate { cipher, salt }| EncryptedMnemonic {
-        //                 cipher,
-        //                 salt,
-        //             },
-        //         )
-        //         .collect(),
-        //     xpublic_keys: self.public_keys,
-        //     required_signatures: self.minimum_signatures,
-        //     cosigner_index: self.cosigner_index,
-        //     ecdsa: self.ecdsa,
-        // }),
-        // (false, WalletVersion::One) => WalletType::MultiV1(MultisigWalletFileV1 {
-        //     encrypted_mnemonics: self
-        //         .encrypted_mnemonics
-        //         .into_iter()
-        //         .map(
-        //             |EncryptedMnemonicIntermediate { cipher, salt }| EncryptedMnemonic {
-        //                 cipher,
-        //                 salt,
-        //             },
-        //         )
-        //         .collect(),
-        //     xpublic_keys: self.public_keys,
-        //     required_signatures: self.minimum_signatures,
-        //     cosigner_index: self.cosigner_index,
-        //     ecdsa: self.ecdsa,
-        // }),
 
         Ok(wallet)
     }
@@ -152,6 +339,7 @@ pub enum WalletFileDecryptedData {
     Legacy(String),
     //GoWallet(WalletType),
     Core(String),
+    Multisig(MultisigWalletImport),
 }
 impl Display for WalletFileData {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -174,3 +362,116 @@ pub fn parse_wallet_file(contents: &str) -> Result<WalletFileData> {
         Err(Error::Custom("Unable to parse wallet file".into()))
     }
 }
+
+/// Resolves an account of the locally running wallet. Operations that need
+/// direct access to account keys are not available over the wallet API.
+pub async fn load_local_account(account_id: &AccountId) -> Result<Arc<dyn CoreAccount>> {
+    let wallet = runtime()
+        .wallet()
+        .downcast_arc::<CoreWallet>()
+        .map_err(|_| Error::WalletIsNotLocal)?;
+    let binding = wallet.guard();
+    let guard = binding.lock().await;
+    wallet
+        .get_account_by_id(account_id, &guard)
+        .await?
+        .ok_or_else(|| Error::custom(i18n("Account not found")))
+}
+
+/// Derives the multisig cosigner key of the private key data at the first
+/// account index that is not used by a multisig account of the wallet.
+pub async fn next_multisig_cosigner_key(
+    wallet: &Arc<CoreWallet>,
+    wallet_secret: &Secret,
+    prv_key_data_id: &PrvKeyDataId,
+    payment_secret: Option<&Secret>,
+) -> Result<String> {
+    let prv_key_data = wallet
+        .store()
+        .as_prv_key_data_store()?
+        .load_key_data(wallet_secret, prv_key_data_id)
+        .await?
+        .ok_or_else(|| Error::custom(i18n("Private key not found")))?;
+
+    let mut used_keys = AHashSet::new();
+    let mut accounts = wallet.store().as_account_store()?.iter(None).await?;
+    while let Some(account) = accounts.next().await {
+        let (account, _) = account?;
+        if account.kind == MULTISIG_ACCOUNT_KIND {
+            let payload = MultisigPayload::try_load(&account)?;
+            used_keys.extend(
+                payload
+                    .xpub_keys
+                    .iter()
+                    .map(|xpub_key| xpub_key.to_string(Some(Prefix::XPUB))),
+            );
+        }
+    }
+
+    let mut account_index = 0;
+    loop {
+        let xpub_key = prv_key_data
+            .create_xpub(payment_secret, MULTISIG_ACCOUNT_KIND.into(), account_index)
+            .await?
+            .to_string(Some(Prefix::XPUB));
+        if !used_keys.contains(&xpub_key) {
+            return Ok(xpub_key);
+        }
+        account_index += 1;
+    }
+}
+
+/// Creates a multisig account on the local wallet for the next unused
+/// cosigner key of the private key data (see [`next_multisig_cosigner_key`]).
+/// The wallet API always binds multisig accounts to account index 0.
+pub async fn create_multisig_account(
+    wallet: &Arc<CoreWallet>,
+    wallet_secret: &Secret,
+    prv_key_data_id: PrvKeyDataId,
+    payment_secret: Option<&Secret>,
+    xpub_keys: Vec<String>,
+    name: Option<String>,
+    minimum_signatures: u16,
+) -> Result<AccountDescriptor> {
+    let cosigner_key =
+        next_multisig_cosigner_key(wallet, wallet_secret, &prv_key_data_id, payment_secret).await?;
+
+    let mut xpub_keys = xpub_keys
+        .iter()
+        .map(|xpub_key| normalize_xpub(xpub_key))
+        .collect::<Result<Vec<_>>>()?;
+    xpub_keys.push(cosigner_key.clone());
+    xpub_keys.sort_unstable();
+    let cosigner_index = xpub_keys
+        .iter()
+        .position(|xpub_key| xpub_key == &cosigner_key)
+        .unwrap_or_default();
+
+    let multisig = MultisigWalletImport {
+        mnemonics: vec![],
+        xpub_keys,
+        cosigner_index: cosigner_index as u8,
+        minimum_signatures,
+    };
+    let account = multisig
+        .create_account(wallet, name, vec![prv_key_data_id])
+        .await?;
+
+    let account_store = wallet.store().as_account_store()?;
+    if account_store.load_single(account.id()).await?.is_some() {
+        return Err(Error::custom(i18n("Account already exists")));
+    }
+    account_store
+        .store_single(&account.to_storage()?, None)
+        .await?;
+    wallet.store().commit(wallet_secret).await?;
+
+    let account_descriptor = account.descriptor()?;
+    wallet
+        .notify(kaspa_wallet_core::events::Events::AccountCreate {
+            account_descriptor: account_descriptor.clone(),
+        })
+        .await?;
+
+    Ok(account_descriptor)
+}
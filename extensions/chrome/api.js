@@ -12,9 +12,11 @@ async function apiBuilder(uuid, eventKey) {
 
     log("event key", eventKey);
     let events = new Map();
+    let requestSeq = 0;
 
     function postMessage(action, data, rid) {
-        rid = rid === false ? undefined : rid || action;
+        // unique request ids allow concurrent requests of the same kind
+        rid = rid === false ? undefined : rid || `${action}-${++requestSeq}`;
         let result;
         if (rid) {
             result = new Promise((resolve, reject) => {
@@ -30,10 +32,20 @@ async function apiBuilder(uuid, eventKey) {
         return result;
     }
 
+    // handlers of wallet events (`accountsChanged`, `networkChanged`)
+    let listeners = new Map();
+
     window.addEventListener(EVENT_REPLY, (msg) => {
-        let { data, rid } = msg.detail;
+        let { type, event, data, rid } = msg.detail;
 
         log("reply:", msg.detail);
+        if (type === "Event") {
+            for (const handler of listeners.get(event) || []) {
+                handler(data);
+            }
+            return;
+        }
+
         if (rid && events.has(rid)) {
             if (data.error){
                 events.get(rid).reject(data)
@@ -52,11 +64,29 @@ async function apiBuilder(uuid, eventKey) {
             return postMessage("Connect");
         }
 
-        signTransaction(data) {
-            // Communicate a message back to the extension
-            return postMessage("SignTransaction", data);
+        signMessage(message) {
+            return postMessage("SignMessage", message);
+        }
+
+        sendTransaction(request) {
+            return postMessage("SendTransaction", request);
+        }
+
+        signTransaction(request) {
+            return postMessage("SignTransaction", request);
         }
         
+        on(event, handler) {
+            if (!listeners.has(event)) {
+                listeners.set(event, new Set());
+            }
+            listeners.get(event).add(handler);
+        }
+
+        off(event, handler) {
+            listeners.get(event)?.delete(handler);
+        }
+
         testRequestResponse(data) {
             return postMessage("TestRequestResponse", data);
         }
@@ -93,6 +93,9 @@ impl ClientReceiver {
                         //let _ = window().alert_with_message("Closing window");
                         let _ = window().close();
                     }
+                    Request::Cancel { sender_id } => {
+                        self.client.adaptor().cancel(sender_id);
+                    }
                     _ => {
                         let self_ = self.clone();
                         spawn_local(async move {
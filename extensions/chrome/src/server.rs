@@ -1,6 +1,8 @@
 use kaspa_ng_core::{
     imports::{KaspaRpcClient, MetricsUpdateKind, NetworkId},
-    interop::{PendingRequest, Request, ServerAction},
+    interop::{
+        Notification, PendingRequest, Permissions, Request, ServerAction, TransactionRequest,
+    },
 };
 use kaspa_wallet_core::rpc::{
     // ConnectOptions, ConnectStrategy, RpcCtl,
@@ -15,13 +17,24 @@ pub type PortListenerClosure = Closure<dyn FnMut(chrome_runtime_port::Port) -> J
 pub type PortEventClosure = Closure<dyn FnMut(JsValue) -> JsValue>;
 use kaspa_ng_core::interop::Target;
 use rand::RngExt;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
 use workflow_core::enums::Describe;
+use workflow_core::time::unixtime_as_millis_f64;
 use workflow_wasm::extensions::ObjectExtension;
 
 type PortId = u64;
 type ReqId = String;
 
+// time after which a request that has not been
+// presented to the user is rejected
+const PENDING_REQUEST_TIMEOUT: f64 = 5.0 * 60.0 * 1000.0;
+
+struct QueuedRequest {
+    request: PendingRequest,
+    expires: f64,
+}
+
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(js_name = "initPageScript")]
@@ -41,10 +54,10 @@ pub struct Server {
     port_closure: Mutex<Option<Rc<PortListenerClosure>>>,
     port_events_closures: PortEventsClosures,
     chrome_extension_id: String,
-    // event pending delivery after the popup is open
-    pending_request: Mutex<Option<PendingRequest>>,
-    // id of request waiting for response
-    waiting_response: Mutex<Option<(PortId, ReqId)>>,
+    // requests pending delivery to the popup
+    pending_requests: Mutex<VecDeque<QueuedRequest>>,
+    // request currently presented in the popup and waiting for response
+    waiting_response: Mutex<Option<(PortId, Option<ReqId>)>>,
 }
 
 unsafe impl Send for Server {}
@@ -54,6 +67,9 @@ unsafe impl Sync for Server {}
 enum ExtensionActions {
     InjectPageScript,
     Connect,
+    SignMessage,
+    SendTransaction,
+    SignTransaction,
     TestRequestResponse,
 }
 
@@ -164,7 +180,7 @@ impl Server {
             port_events_closures: Mutex::new(HashMap::new()),
             wallet,
             wallet_server,
-            pending_request: Default::default(),
+            pending_requests: Default::default(),
             waiting_response: Default::default(),
             // runtime,
         }
@@ -191,6 +207,14 @@ impl Server {
         self.register_port_listener();
         self.wallet_server.start();
 
+        let this = self.clone();
+        spawn_local(async move {
+            loop {
+                workflow_core::task::sleep(Duration::from_secs(10)).await;
+                this.prune_requests();
+            }
+        });
+
         // log_info!("chrome: starting wallet...");
         self.wallet
             .start()
@@ -239,6 +263,8 @@ impl Server {
                         .remove(&port_id);
                     if port_clone.name() == Some("POPUP".to_string()) {
                         let _ = this_clone.on_popup_disconnect();
+                    } else {
+                        this_clone.on_port_disconnect(port_id);
                     }
                     JsValue::from(true)
                 }));
@@ -258,16 +284,88 @@ impl Server {
     }
 
     fn on_popup_disconnect(self: &Arc<Self>) -> Result<()> {
-        let rid = self.waiting_response.lock().unwrap().take();
-        if rid.is_some() {
-            let response = interop::Response::Canceled {
-                error: "User canceled the request.".into(),
-            };
-            self.send_message_to_port(rid, response)?;
+        let waiting = self.waiting_response.lock().unwrap().take();
+        let queued = self
+            .pending_requests
+            .lock()
+            .unwrap()
+            .drain(..)
+            .map(|queued| (queued.request.sender_id, queued.request.id))
+            .collect::<Vec<_>>();
+
+        for (port_id, rid) in waiting.into_iter().chain(queued) {
+            self.cancel_request(port_id, rid, "User canceled the request.".into());
         }
         Ok(())
     }
 
+    // drop requests issued by a page that has been closed
+    fn on_port_disconnect(self: &Arc<Self>, port_id: PortId) {
+        self.pending_requests
+            .lock()
+            .unwrap()
+            .retain(|queued| queued.request.sender_id != port_id);
+
+        let in_flight = {
+            let mut waiting = self.waiting_response.lock().unwrap();
+            if waiting.as_ref().is_some_and(|(pid, _)| *pid == port_id) {
+                waiting.take()
+            } else {
+                None
+            }
+        };
+
+        if in_flight.is_some() {
+            let req = borsh::to_vec(&Request::Cancel { sender_id: port_id }).unwrap();
+            spawn_local(async move {
+                if let Err(err) = send_message(&notify_to_jsv(Target::Runtime, &req)).await {
+                    log_warn!("Unable to post Request::Cancel: {:?}", err);
+                }
+            });
+        }
+    }
+
+    // queue a request for approval; the popup steps through
+    // queued requests, so it is opened only when idle
+    fn enqueue_request(self: &Arc<Self>, request: PendingRequest) {
+        self.prune_requests();
+
+        let expires = unixtime_as_millis_f64() + PENDING_REQUEST_TIMEOUT;
+        self.pending_requests
+            .lock()
+            .unwrap()
+            .push_back(QueuedRequest { request, expires });
+
+        if self.waiting_response.lock().unwrap().is_none() {
+            open_popup_window();
+        }
+    }
+
+    fn next_request(self: &Arc<Self>) -> Option<PendingRequest> {
+        self.prune_requests();
+
+        let mut queue = self.pending_requests.lock().unwrap();
+        let QueuedRequest { request, .. } = queue.pop_front()?;
+        *self.waiting_response.lock().unwrap() = Some((request.sender_id, request.id.clone()));
+        Some(request.with_remaining(queue.len() as u32))
+    }
+
+    // reject queued requests that have not been presented in time
+    fn prune_requests(self: &Arc<Self>) {
+        let now = unixtime_as_millis_f64();
+        let expired = {
+            let mut queue = self.pending_requests.lock().unwrap();
+            let (expired, pending): (VecDeque<_>, VecDeque<_>) =
+                queue.drain(..).partition(|queued| queued.expires <= now);
+            *queue = pending;
+            expired
+        };
+
+        for QueuedRequest { request, .. } in expired {
+            self.cancel_request(request.sender_id, request.id, "Request timed out.".into());
+        }
+    }
+
     fn send_message_to_port(
         self: &Arc<Self>,
         req: Option<(PortId, ReqId)>,
@@ -301,6 +399,82 @@ impl Server {
         Ok(())
     }
 
+    // post wallet state changes to pages of origins holding a grant
+    async fn notify_ports(self: &Arc<Self>, notification: &Notification) {
+        let permissions = Permissions::default();
+        if let Err(err) = permissions.load().await {
+            log_error!("Unable to load permissions: {err}");
+        }
+
+        let ports: Vec<Rc<chrome_runtime_port::Port>> = self
+            .port_events_closures
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|(port, _)| port.name() != Some("POPUP".to_string()))
+            .map(|(port, _)| port.clone())
+            .collect();
+
+        for port in ports {
+            let Some(origin) = port.sender().origin() else {
+                continue;
+            };
+
+            if let Some(event) = notification.event_for(permissions.get(&origin).as_ref()) {
+                let object = serde_wasm_bindgen::to_value(&event).unwrap();
+                js_sys::Reflect::set(&object, &"type".into(), &"Event".into()).unwrap();
+                port.post_message(object);
+            }
+        }
+    }
+
+    fn cancel_request(
+        self: &Arc<Self>,
+        port_id: PortId,
+        rid: Option<ReqId>,
+        error: String,
+    ) -> JsValue {
+        if let Some(rid) = rid {
+            let response = interop::Response::Canceled { error };
+            let _ = self.send_message_to_port(Some((port_id, rid)), response);
+        }
+        JsValue::from(false)
+    }
+
+    // requests other than `Connect` are only accepted from
+    // origins that have been granted the corresponding permission
+    async fn queue_authorized_request(
+        self: &Arc<Self>,
+        port: Rc<chrome_runtime_port::Port>,
+        port_id: PortId,
+        rid: Option<ReqId>,
+        request: Request,
+    ) {
+        let Some(origin) = port.sender().origin() else {
+            self.cancel_request(port_id, rid, "Unknown request origin.".into());
+            return;
+        };
+
+        let permissions = Permissions::default();
+        if let Err(err) = permissions.load().await {
+            log_error!("Unable to load permissions: {err}");
+        }
+
+        let allowed = request
+            .kind()
+            .is_some_and(|kind| permissions.is_allowed(&origin, kind));
+        if !allowed {
+            self.cancel_request(
+                port_id,
+                rid,
+                format!("{origin} is not permitted to issue this request. Please connect first."),
+            );
+            return;
+        }
+
+        self.enqueue_request(PendingRequest::new(port_id, rid, request).with_origin(Some(origin)));
+    }
+
     async fn handle_port_event(
         self: &Arc<Self>,
         msg_jsv: js_sys::Object,
@@ -316,27 +490,58 @@ impl Server {
                     init_page_script(tab_id, msg.data);
                 }
                 ExtensionActions::Connect => {
-                    self.pending_request
-                        .lock()
-                        .unwrap()
-                        .replace(PendingRequest::new(port_id, msg.rid, Request::Connect {}));
-                    open_popup_window();
+                    self.enqueue_request(
+                        PendingRequest::new(port_id, msg.rid, Request::Connect {})
+                            .with_origin(port.sender().origin()),
+                    );
                 }
-                ExtensionActions::TestRequestResponse => {
-                    // TODO - ENQUEUE PENDING REQUEST DATA
-                    self.pending_request
-                        .lock()
-                        .unwrap()
-                        .replace(PendingRequest::new(
+                ExtensionActions::SignMessage => {
+                    let Some(message) = msg.data.as_string() else {
+                        return self.cancel_request(
                             port_id,
                             msg.rid,
-                            Request::Test {
-                                data: msg.data.as_string().unwrap(),
-                            },
-                        ));
+                            "SignMessage requires a string message.".into(),
+                        );
+                    };
+
+                    self.queue_authorized_request(
+                        port,
+                        port_id,
+                        msg.rid,
+                        Request::SignMessage { message },
+                    )
+                    .await;
+                }
+                ExtensionActions::SendTransaction | ExtensionActions::SignTransaction => {
+                    let transaction =
+                        match serde_wasm_bindgen::from_value::<TransactionRequest>(msg.data) {
+                            Ok(transaction) => transaction,
+                            Err(err) => {
+                                return self.cancel_request(
+                                    port_id,
+                                    msg.rid,
+                                    format!("Invalid transaction request: {err}"),
+                                );
+                            }
+                        };
+
+                    let request = if matches!(msg.action, ExtensionActions::SendTransaction) {
+                        Request::SendTransaction(transaction)
+                    } else {
+                        Request::SignTransaction(transaction)
+                    };
 
-                    // OPENED POPUP MUST CONSUME PENDING REQUEST
-                    open_popup_window();
+                    self.queue_authorized_request(port, port_id, msg.rid, request)
+                        .await;
+                }
+                ExtensionActions::TestRequestResponse => {
+                    self.enqueue_request(PendingRequest::new(
+                        port_id,
+                        msg.rid,
+                        Request::Test {
+                            data: msg.data.as_string().unwrap(),
+                        },
+                    ));
                 }
             },
             Message::Internal(_) => {
@@ -424,18 +629,9 @@ impl Server {
                 // log_info!("[Server] Adaptor: action: {action:?}");
                 match action {
                     ServerAction::PendingRequests => {
-                        let pending_request =
-                            self.pending_request
-                                .lock()
-                                .unwrap()
-                                .take()
-                                .map_or(vec![], |a| {
-                                    if let Some(id) = a.id.clone() {
-                                        *self.waiting_response.lock().unwrap() =
-                                            Some((a.sender_id, id));
-                                    }
-                                    borsh::to_vec(&a).unwrap()
-                                });
+                        let pending_request = self
+                            .next_request()
+                            .map_or(vec![], |request| borsh::to_vec(&request).unwrap());
 
                         let res = resp_to_jsv(Target::Adaptor, Ok(pending_request));
                         // log_info!("[Server] Adaptor: res: {res:?}");
@@ -448,7 +644,8 @@ impl Server {
                     }
                     ServerAction::Response(port_id, rid, data) => {
                         let response = interop::Response::try_from_slice(&data).unwrap();
-                        if let Some((pid, id)) = self.waiting_response.lock().unwrap().take()
+                        let waiting = self.waiting_response.lock().unwrap().take();
+                        if let Some((pid, Some(id))) = waiting
                             && pid == port_id
                             && Some(id.clone()) == rid
                         {
@@ -481,6 +678,17 @@ impl Server {
                         //     }
                         // });
                     }
+                    ServerAction::Notify(data) => {
+                        let notification = Notification::try_from_slice(&data)?;
+                        let res = resp_to_jsv(Target::Adaptor, Ok(vec![]));
+
+                        spawn_local(async move {
+                            self.notify_ports(&notification).await;
+                            if let Err(err) = callback.call1(&JsValue::UNDEFINED, &res) {
+                                log_error!("Notify: callback error: {:?}", err);
+                            }
+                        });
+                    }
                 }
             }
         }